@@ -0,0 +1,186 @@
+//! Compares the bitboard move generator with one that works on the board
+//! array alone, like this crate did before bitboards: it scans the board for
+//! pieces, walks their rays cell by cell and checks the legality of a move by
+//! making it and scanning every opponent piece for one attacking the king.
+//!
+//! Run with `cargo bench -p chers`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use chers::{perft::perft, Board, Color, Figure, Game, Piece, BOARD_SIZE};
+
+fn move_generation(c: &mut Criterion) {
+    let state = Game::new().start();
+    let mut group = c.benchmark_group("perft from the starting position");
+    group.sample_size(10);
+
+    for depth in [3, 4] {
+        assert_eq!(
+            perft(&state, depth),
+            array::perft(state.board(), state.player(), depth)
+        );
+
+        group.bench_with_input(BenchmarkId::new("bitboards", depth), &depth, |b, depth| {
+            b.iter(|| perft(&state, *depth))
+        });
+        group.bench_with_input(BenchmarkId::new("array", depth), &depth, |b, depth| {
+            b.iter(|| array::perft(state.board(), state.player(), *depth))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, move_generation);
+criterion_main!(benches);
+
+/// The move generator working on the board array.
+///
+/// It knows neither castling nor en passant, which can't happen within the
+/// first four plies of a game, so its node counts match up to that depth.
+mod array {
+    use super::*;
+
+    type Cell = (usize, usize);
+
+    const ROOK: [(isize, isize); 4] = [(0, 1), (0, -1), (1, 0), (-1, 0)];
+    const BISHOP: [(isize, isize); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+    const KNIGHT: [(isize, isize); 8] = [
+        (1, 2),
+        (2, 1),
+        (2, -1),
+        (1, -2),
+        (-1, -2),
+        (-2, -1),
+        (-2, 1),
+        (-1, 2),
+    ];
+
+    pub fn perft(board: &Board, player: Color, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let mut nodes = 0;
+        for (from, piece) in pieces(board, player) {
+            for to in targets(board, from, piece) {
+                let mut next = *board;
+                next[to.1][to.0] = next[from.1][from.0].take();
+                if is_attacked(&next, king_of(&next, player), player.other()) {
+                    continue;
+                }
+
+                let promotes =
+                    piece.figure == Figure::Pawn && (to.1 == 0 || to.1 == BOARD_SIZE - 1);
+                nodes += match promotes {
+                    true => 4 * perft(&next, player.other(), depth - 1),
+                    false => perft(&next, player.other(), depth - 1),
+                };
+            }
+        }
+
+        nodes
+    }
+
+    fn pieces(board: &Board, player: Color) -> Vec<(Cell, Piece)> {
+        let mut pieces = Vec::new();
+        for (y, row) in board.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if let Some(piece) = cell.filter(|piece| piece.color == player) {
+                    pieces.push(((x, y), piece));
+                }
+            }
+        }
+
+        pieces
+    }
+
+    fn king_of(board: &Board, player: Color) -> Cell {
+        pieces(board, player)
+            .into_iter()
+            .find(|(_, piece)| piece.figure == Figure::King)
+            .map(|(cell, _)| cell)
+            .expect("Board does not contain a king!")
+    }
+
+    fn is_attacked(board: &Board, cell: Cell, attacker: Color) -> bool {
+        pieces(board, attacker)
+            .into_iter()
+            .any(|(from, piece)| targets(board, from, piece).contains(&cell))
+    }
+
+    fn offset(cell: Cell, (dx, dy): (isize, isize)) -> Option<Cell> {
+        let x = cell.0.checked_add_signed(dx)?;
+        let y = cell.1.checked_add_signed(dy)?;
+        (x < BOARD_SIZE && y < BOARD_SIZE).then_some((x, y))
+    }
+
+    fn targets(board: &Board, from: Cell, piece: Piece) -> Vec<Cell> {
+        let at = |cell: Cell| board[cell.1][cell.0];
+        let is_free_or_opponent =
+            |cell: &Cell| at(*cell).is_none_or(|other| other.color != piece.color);
+
+        let slide = |directions: &[(isize, isize)]| {
+            let mut targets = Vec::new();
+            for direction in directions {
+                let mut cell = from;
+                while let Some(next) = offset(cell, *direction) {
+                    cell = next;
+                    match at(cell) {
+                        None => targets.push(cell),
+                        Some(other) => {
+                            if other.color != piece.color {
+                                targets.push(cell);
+                            }
+                            break;
+                        }
+                    }
+                }
+            }
+            targets
+        };
+        let step = |directions: &[(isize, isize)]| {
+            directions
+                .iter()
+                .filter_map(|direction| offset(from, *direction))
+                .filter(is_free_or_opponent)
+                .collect()
+        };
+
+        match piece.figure {
+            Figure::Rook => slide(&ROOK),
+            Figure::Bishop => slide(&BISHOP),
+            Figure::Queen => [slide(&ROOK), slide(&BISHOP)].concat(),
+            Figure::Knight => step(&KNIGHT),
+            Figure::King => step(&[ROOK, BISHOP].concat()),
+            Figure::Pawn => {
+                let (forward, start_row) = match piece.color {
+                    Color::White => (-1, BOARD_SIZE - 2),
+                    Color::Black => (1, 1),
+                };
+
+                let mut targets = Vec::new();
+                if let Some(one) = offset(from, (0, forward)).filter(|cell| at(*cell).is_none()) {
+                    targets.push(one);
+                    if let Some(two) = offset(one, (0, forward))
+                        .filter(|cell| from.1 == start_row && at(*cell).is_none())
+                    {
+                        targets.push(two);
+                    }
+                }
+                for side in [-1, 1] {
+                    if let Some(capture) = offset(from, (side, forward))
+                        .filter(|cell| at(*cell).is_some_and(|other| other.color != piece.color))
+                    {
+                        targets.push(capture);
+                    }
+                }
+
+                targets
+            }
+            Figure::Archbishop | Figure::Chancellor => {
+                unreachable!("Not part of standard chess")
+            }
+        }
+    }
+}
@@ -0,0 +1,244 @@
+//! Precomputed attack tables, which are generated at compile time.
+//!
+//! Sliding pieces use the "classical" approach: for every cell and direction
+//! the full ray is stored, and the first blocker along it cuts off the ray
+//! behind it.
+
+use crate::{Color, Coordinate, Figure, Player, BOARD_SIZE};
+
+use super::{Bitboard, Bitboards};
+
+const CELLS: usize = BOARD_SIZE * BOARD_SIZE;
+
+const KNIGHT_OFFSETS: [(isize, isize); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+
+const KING_OFFSETS: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// The directions sliding pieces move in. The first four increase the index
+/// of the cells along the ray, the last four decrease it.
+const DIRECTIONS: [(isize, isize); 8] = [
+    (1, 0),
+    (0, 1),
+    (1, 1),
+    (-1, 1),
+    (-1, 0),
+    (0, -1),
+    (-1, -1),
+    (1, -1),
+];
+
+const ROOK_DIRECTIONS: [usize; 4] = [0, 1, 4, 5];
+const BISHOP_DIRECTIONS: [usize; 4] = [2, 3, 6, 7];
+
+const KNIGHT_ATTACKS: [Bitboard; CELLS] = leaper_attacks(&KNIGHT_OFFSETS);
+const KING_ATTACKS: [Bitboard; CELLS] = leaper_attacks(&KING_OFFSETS);
+/// White pawns move up, i.e. towards lower rows, while black ones move down.
+const PAWN_ATTACKS: [[Bitboard; CELLS]; 2] = [
+    leaper_attacks(&[(-1, -1), (1, -1)]),
+    leaper_attacks(&[(-1, 1), (1, 1)]),
+];
+const RAYS: [[Bitboard; CELLS]; DIRECTIONS.len()] = rays();
+
+/// Returns the cell at the given offset, if it is still on the board.
+const fn offset(index: usize, (dx, dy): (isize, isize)) -> Option<usize> {
+    let x = (index % BOARD_SIZE) as isize + dx;
+    let y = (index / BOARD_SIZE) as isize + dy;
+
+    if x < 0 || y < 0 || x >= BOARD_SIZE as isize || y >= BOARD_SIZE as isize {
+        return None;
+    }
+
+    Some(y as usize * BOARD_SIZE + x as usize)
+}
+
+const fn leaper_attacks(offsets: &[(isize, isize)]) -> [Bitboard; CELLS] {
+    let mut attacks = [Bitboard::EMPTY; CELLS];
+
+    let mut index = 0;
+    while index < CELLS {
+        let mut i = 0;
+        while i < offsets.len() {
+            if let Some(target) = offset(index, offsets[i]) {
+                attacks[index].0 |= 1 << target;
+            }
+            i += 1;
+        }
+        index += 1;
+    }
+
+    attacks
+}
+
+const fn rays() -> [[Bitboard; CELLS]; DIRECTIONS.len()] {
+    let mut rays = [[Bitboard::EMPTY; CELLS]; DIRECTIONS.len()];
+
+    let mut direction = 0;
+    while direction < DIRECTIONS.len() {
+        let mut index = 0;
+        while index < CELLS {
+            let mut current = index;
+            while let Some(next) = offset(current, DIRECTIONS[direction]) {
+                rays[direction][index].0 |= 1 << next;
+                current = next;
+            }
+            index += 1;
+        }
+        direction += 1;
+    }
+
+    rays
+}
+
+/// Cells reachable from [from] in [direction], up to and including the
+/// first occupied cell.
+fn ray_attacks(from: usize, direction: usize, occupied: Bitboard) -> Bitboard {
+    let ray = RAYS[direction][from];
+    let blockers = ray.0 & occupied.0;
+    if blockers == 0 {
+        return ray;
+    }
+
+    let first_blocker = match direction < 4 {
+        true => blockers.trailing_zeros(),
+        false => 63 - blockers.leading_zeros(),
+    };
+
+    ray ^ RAYS[direction][first_blocker as usize]
+}
+
+fn slider_attacks(from: Coordinate, occupied: Bitboard, directions: [usize; 4]) -> Bitboard {
+    let index = Bitboard::index(from);
+
+    directions
+        .into_iter()
+        .fold(Bitboard::EMPTY, |attacks, direction| {
+            attacks | ray_attacks(index, direction, occupied)
+        })
+}
+
+pub fn knight(from: Coordinate) -> Bitboard {
+    KNIGHT_ATTACKS[Bitboard::index(from)]
+}
+
+pub fn king(from: Coordinate) -> Bitboard {
+    KING_ATTACKS[Bitboard::index(from)]
+}
+
+/// The cells a pawn of [color] residing on [from] can capture on.
+pub fn pawn(color: Color, from: Coordinate) -> Bitboard {
+    PAWN_ATTACKS[color as usize][Bitboard::index(from)]
+}
+
+pub fn rook(from: Coordinate, occupied: Bitboard) -> Bitboard {
+    slider_attacks(from, occupied, ROOK_DIRECTIONS)
+}
+
+pub fn bishop(from: Coordinate, occupied: Bitboard) -> Bitboard {
+    slider_attacks(from, occupied, BISHOP_DIRECTIONS)
+}
+
+/// The pieces of [attacker] that could capture a piece residing on [target],
+/// regardless of whether the cell is currently occupied.
+pub fn attackers_of(bitboards: &Bitboards, target: Coordinate, attacker: Player) -> Bitboard {
+    let occupied = bitboards.occupied();
+    let pieces = |figure| bitboards.pieces(attacker, figure);
+
+    // Attacks are symmetric, so we look from the target towards the attackers.
+    // Pawns are the exception, which is why we use the ones of the other color.
+    (pawn(attacker.other(), target) & pieces(Figure::Pawn))
+        | (knight(target) & pieces(Figure::Knight))
+        | (king(target) & pieces(Figure::King))
+        | (rook(target, occupied) & (pieces(Figure::Rook) | pieces(Figure::Queen)))
+        | (bishop(target, occupied) & (pieces(Figure::Bishop) | pieces(Figure::Queen)))
+}
+
+pub fn is_attacked(bitboards: &Bitboards, target: Coordinate, attacker: Player) -> bool {
+    !attackers_of(bitboards, target, attacker).is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fen::parse_state, Cell};
+
+    use super::*;
+
+    #[test]
+    fn leapers_stay_on_the_board() {
+        assert_eq!(Bitboard::from_iter([Cell::B3, Cell::C2]), knight(Cell::A1));
+        assert_eq!(8, king(Cell::E4).len());
+        assert_eq!(Bitboard::of(Cell::B3), pawn(Color::White, Cell::A2));
+        assert_eq!(Bitboard::of(Cell::G1), pawn(Color::Black, Cell::H2));
+    }
+
+    #[test]
+    fn sliders_stop_at_the_first_blocker() {
+        let occupied = Bitboard::from_iter([Cell::D6, Cell::F4, Cell::B2]);
+
+        assert_eq!(
+            Bitboard::from_iter([
+                Cell::D5,
+                Cell::D6,
+                Cell::E4,
+                Cell::F4,
+                Cell::C4,
+                Cell::B4,
+                Cell::A4,
+                Cell::D3,
+                Cell::D2,
+                Cell::D1,
+            ]),
+            rook(Cell::D4, occupied)
+        );
+        assert_eq!(
+            Bitboard::from_iter([
+                Cell::C3,
+                Cell::B2,
+                Cell::E5,
+                Cell::F6,
+                Cell::G7,
+                Cell::H8,
+                Cell::C5,
+                Cell::B6,
+                Cell::A7,
+                Cell::E3,
+                Cell::F2,
+                Cell::G1,
+            ]),
+            bishop(Cell::D4, occupied)
+        );
+    }
+
+    #[test]
+    fn it_finds_attackers_of_a_cell() {
+        let state = parse_state("4k3/8/8/8/1b2r3/2P5/N7/4K3 w - - 0 1").unwrap();
+        let bitboards = Bitboards::of(&state.board);
+
+        assert_eq!(
+            Bitboard::from_iter([Cell::E4]),
+            attackers_of(&bitboards, Cell::E1, Color::Black)
+        );
+        assert_eq!(
+            Bitboard::from_iter([Cell::A2, Cell::C3]),
+            attackers_of(&bitboards, Cell::B4, Color::White)
+        );
+        assert!(!is_attacked(&bitboards, Cell::D2, Color::Black));
+    }
+}
@@ -0,0 +1,295 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Board, Color, Coordinate, Figure, Piece, Player, BOARD_SIZE};
+
+pub mod attacks;
+
+/// A set of cells, where each bit of the number represents one cell.
+///
+/// Cells are numbered row by row, starting with a8 as bit 0 and ending with
+/// h1 as bit 63, which matches the order of [Coordinate::y] and
+/// [Coordinate::x].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    pub const EMPTY: Bitboard = Bitboard(0);
+
+    pub const fn index(coordinate: Coordinate) -> usize {
+        coordinate.y * BOARD_SIZE + coordinate.x
+    }
+
+    pub const fn coordinate(index: usize) -> Coordinate {
+        Coordinate {
+            x: index % BOARD_SIZE,
+            y: index / BOARD_SIZE,
+        }
+    }
+
+    /// A set containing only [coordinate].
+    pub const fn of(coordinate: Coordinate) -> Self {
+        Bitboard(1 << Self::index(coordinate))
+    }
+
+    pub const fn contains(self, coordinate: Coordinate) -> bool {
+        self.0 & (1 << Self::index(coordinate)) != 0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// All cells from [a] to [b], which must be on the same row, including
+    /// both of them.
+    pub fn row_span(a: Coordinate, b: Coordinate) -> Self {
+        (a.x.min(b.x)..=a.x.max(b.x))
+            .map(|x| Coordinate::new(x, a.y))
+            .collect()
+    }
+
+    /// The cell with the lowest index, if any.
+    pub const fn first(self) -> Option<Coordinate> {
+        match self.0 {
+            0 => None,
+            bits => Some(Self::coordinate(bits.trailing_zeros() as usize)),
+        }
+    }
+}
+
+/// Bitboards are serialized as lists of cells, since JavaScript numbers can't
+/// represent all 64 bit values.
+impl Serialize for Bitboard {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(*self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bitboard {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let cells = Vec::<Coordinate>::deserialize(deserializer)?;
+        if cells
+            .iter()
+            .any(|cell| cell.x >= BOARD_SIZE || cell.y >= BOARD_SIZE)
+        {
+            return Err(serde::de::Error::custom("cell outside of the board"));
+        }
+
+        Ok(cells.into_iter().collect())
+    }
+}
+
+impl From<Coordinate> for Bitboard {
+    fn from(value: Coordinate) -> Self {
+        Bitboard::of(value)
+    }
+}
+
+impl FromIterator<Coordinate> for Bitboard {
+    fn from_iter<T: IntoIterator<Item = Coordinate>>(iter: T) -> Self {
+        iter.into_iter()
+            .fold(Bitboard::EMPTY, |set, coordinate| set | coordinate.into())
+    }
+}
+
+/// Iterates the cells of a [Bitboard] in ascending order of their index.
+pub struct Cells(u64);
+
+impl Iterator for Cells {
+    type Item = Coordinate;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let index = self.0.trailing_zeros() as usize;
+        // Clears the lowest set bit
+        self.0 &= self.0 - 1;
+
+        Some(Bitboard::coordinate(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = Bitboard(self.0).len();
+        (len, Some(len))
+    }
+}
+
+impl IntoIterator for Bitboard {
+    type Item = Coordinate;
+    type IntoIter = Cells;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Cells(self.0)
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Bitboard;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Bitboard;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 & rhs.0)
+    }
+}
+
+impl BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl BitXor for Bitboard {
+    type Output = Bitboard;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 ^ rhs.0)
+    }
+}
+
+impl BitXorAssign for Bitboard {
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.0 ^= rhs.0;
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    fn not(self) -> Self::Output {
+        Bitboard(!self.0)
+    }
+}
+
+/// The pieces on a [Board], stored as one [Bitboard] per color and figure.
+///
+/// This is kept next to the [Board] in [crate::State], since it allows to
+/// answer questions like "which cells does this piece attack" or "is this
+/// cell attacked" with a few bit operations instead of scanning the board.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Bitboards {
+    pieces: [[Bitboard; Figure::STANDARD.len()]; 2],
+    colors: [Bitboard; 2],
+}
+
+impl Bitboards {
+    pub fn of(board: &Board) -> Self {
+        let mut bitboards = Bitboards::default();
+
+        for (y, row) in board.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if let Some(piece) = cell {
+                    bitboards.toggle(*piece, Coordinate::new(x, y));
+                }
+            }
+        }
+
+        bitboards
+    }
+
+    /// The cells occupied by the given kind of piece.
+    pub fn pieces(&self, color: Color, figure: Figure) -> Bitboard {
+        self.pieces[color as usize][figure as usize]
+    }
+
+    /// The cells occupied by any piece of [color].
+    pub fn color(&self, color: Color) -> Bitboard {
+        self.colors[color as usize]
+    }
+
+    /// The cells occupied by any piece.
+    pub fn occupied(&self) -> Bitboard {
+        self.colors[0] | self.colors[1]
+    }
+
+    pub fn piece_at(&self, at: Coordinate) -> Option<Piece> {
+        let color = if self.color(Color::White).contains(at) {
+            Color::White
+        } else if self.color(Color::Black).contains(at) {
+            Color::Black
+        } else {
+            return None;
+        };
+
+        Figure::STANDARD
+            .into_iter()
+            .find(|figure| self.pieces(color, *figure).contains(at))
+            .map(|figure| Piece::new(color, figure))
+    }
+
+    pub fn king_of(&self, player: Player) -> Option<Coordinate> {
+        self.pieces(player, Figure::King).first()
+    }
+
+    /// Puts [piece] onto [at], or removes it from there if it already resides
+    /// there.
+    pub fn toggle(&mut self, piece: Piece, at: Coordinate) {
+        let cell = Bitboard::of(at);
+        self.pieces[piece.color as usize][piece.figure as usize] ^= cell;
+        self.colors[piece.color as usize] ^= cell;
+    }
+
+    /// Moves whatever piece resides on [from] to [to], replacing the piece
+    /// residing there.
+    pub fn relocate(&mut self, from: Coordinate, to: Coordinate) {
+        if let Some(captured) = self.piece_at(to) {
+            self.toggle(captured, to);
+        }
+
+        if let Some(moved) = self.piece_at(from) {
+            self.toggle(moved, from);
+            self.toggle(moved, to);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Cell, INITIAL_BOARD};
+
+    use super::*;
+
+    #[test]
+    fn it_iterates_cells_in_board_order() {
+        let cells: Bitboard = [Cell::H1, Cell::A8, Cell::E4].into_iter().collect();
+
+        assert_eq!(3, cells.len());
+        assert_eq!(
+            vec![Cell::A8, Cell::E4, Cell::H1],
+            cells.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn it_mirrors_the_board() {
+        let bitboards = Bitboards::of(&INITIAL_BOARD);
+
+        assert_eq!(32, bitboards.occupied().len());
+        assert_eq!(8, bitboards.pieces(Color::Black, Figure::Pawn).len());
+        assert_eq!(Some(Cell::E1), bitboards.king_of(Color::White));
+        assert_eq!(
+            Some(Piece::black(Figure::Queen)),
+            bitboards.piece_at(Cell::D8)
+        );
+        assert_eq!(None, bitboards.piece_at(Cell::E4));
+    }
+}
@@ -1,74 +1,60 @@
 use crate::{
-    force_move_piece, moves_available::possible_moves, pieces, Board, Coordinate, Figure, Move,
-    Piece, Player, State,
+    bitboard::{attacks, Bitboards},
+    moves_available::legal_targets,
+    piece_at, Coordinate, Figure, Piece, Player, State,
 };
 
 /// Computes if any of the current player's pieces can capture their opponents king.
 pub fn checking_pieces_of_opponent(state: &State) -> Vec<(Coordinate, Piece)> {
-    let king = find_king_of(&state.board, state.player);
-    let opponents_turn = state.reversed();
-
-    let mut checking_pieces = Vec::new();
-    for (coordinate, piece) in pieces(&state.board) {
-        if state.player.owns(piece) {
-            continue;
-        }
-
-        let moves = possible_moves(&opponents_turn, coordinate);
-        for target in moves {
-            if target == king {
-                checking_pieces.push((coordinate, piece))
-            }
-        }
-    }
+    let king = find_king_of(&state.bitboards, state.player);
+
+    attackers_of(state, king, state.opponent())
+}
 
-    checking_pieces
+/// Computes which pieces of [attacker] could capture a piece residing on
+/// [target], regardless of whether the cell is currently occupied.
+pub fn attackers_of(
+    state: &State,
+    target: Coordinate,
+    attacker: Player,
+) -> Vec<(Coordinate, Piece)> {
+    attacks::attackers_of(&state.bitboards, target, attacker)
+        .into_iter()
+        .filter_map(|coordinate| {
+            piece_at(coordinate, &state.board).map(|piece| (coordinate, piece))
+        })
+        .collect()
 }
 
 /// Computes if the state represents checkmate.
 ///
 /// If all potential moves all pieces still lead to a check, we have mate.
 pub fn check_by_opponent_is_mate(state: &State) -> bool {
-    for (from, piece) in pieces(&state.board) {
-        if !state.player.owns(piece) {
-            continue;
-        }
-
-        println!(
-            "Checking moves of {piece}, since {:?} owns it",
-            state.player
-        );
-        for to in possible_moves(state, from) {
-            let (resulting_state, _) = force_move_piece(state, Move::simple(from, to)).unwrap();
-            let checking = checking_pieces_of_opponent(&resulting_state.reversed());
-            if checking.is_empty() {
-                println!(
-                    "[info] The {} could move from {} to {} in order to escape the check",
-                    piece, from, to
-                );
-                return false;
-            } else {
-                println!(
-                    "Moving the {} from {} to {} still results in the king being checked",
-                    piece, from, to
-                );
-            }
-        }
-    }
+    has_no_legal_moves(state)
+}
 
-    println!("We are checking");
-    true
+/// Computes if the state represents stalemate.
+///
+/// The current player is not in check, but every move they could make would
+/// put their own king in check.
+pub fn is_stalemate(state: &State) -> bool {
+    checking_pieces_of_opponent(state).is_empty() && has_no_legal_moves(state)
 }
 
-/// Finds the king of the given [player].
-fn find_king_of(board: &Board, player: Player) -> Coordinate {
-    for (coordinate, piece) in pieces(board) {
-        if piece.color == player && piece.figure == Figure::King {
-            return coordinate;
-        }
-    }
+fn has_no_legal_moves(state: &State) -> bool {
+    state
+        .bitboards
+        .color(state.player)
+        .into_iter()
+        .all(|from| legal_targets(state, from).is_empty())
+}
 
-    panic!("Board does not contain a king!");
+/// Finds the king of the given [player].
+fn find_king_of(bitboards: &Bitboards, player: Player) -> Coordinate {
+    bitboards
+        .pieces(player, Figure::King)
+        .first()
+        .expect("Board does not contain a king!")
 }
 
 #[cfg(test)]
@@ -81,8 +67,14 @@ mod tests {
     fn it_finds_the_king() {
         let initial_state = Game::new().start();
 
-        assert_eq!(Cell::E1, find_king_of(&initial_state.board, Player::White));
-        assert_eq!(Cell::E8, find_king_of(&initial_state.board, Player::Black));
+        assert_eq!(
+            Cell::E1,
+            find_king_of(&initial_state.bitboards, Player::White)
+        );
+        assert_eq!(
+            Cell::E8,
+            find_king_of(&initial_state.bitboards, Player::Black)
+        );
     }
 
     #[test]
@@ -118,6 +110,16 @@ mod tests {
         assert!(check_by_opponent_is_mate(&state), "mate was not detected");
     }
 
+    #[test]
+    fn it_detects_stalemate() {
+        // The black queen covers every cell the cornered king could move to
+        let notation = "7k/8/8/8/8/8/2q5/K7 w - - 0 1";
+        let state = parse_state(notation).unwrap();
+
+        assert!(is_stalemate(&state), "stalemate was not detected");
+        assert!(!is_stalemate(&Game::new().start()));
+    }
+
     #[test]
     fn king_cant_move_if_result_still_checks() {
         let notation = "rnb1kbnr/pppp1ppp/8/4P3/7q/8/PPPPP1PP/RNBQKBNR w KQkq - 0 1";
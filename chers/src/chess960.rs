@@ -0,0 +1,229 @@
+use crate::{
+    empty_board, CastlingOrigins, CastlingRights, Color, Figure, Piece, State, BOARD_SIZE,
+};
+
+/// The number of distinct start positions.
+///
+/// To start from a random one, pick an index below this with a random number
+/// generator of the target platform, since the crate does not bring its own.
+pub const POSITIONS: u16 = 960;
+
+/// The index of the start position of standard chess.
+pub const STANDARD_POSITION: u16 = 518;
+
+/// The knights on the five cells left after placing bishops and queen,
+/// indexed by the Scharnagl numbering.
+const KNIGHTS: [[usize; 2]; 10] = [
+    [0, 1],
+    [0, 2],
+    [0, 3],
+    [0, 4],
+    [1, 2],
+    [1, 3],
+    [1, 4],
+    [2, 3],
+    [2, 4],
+    [3, 4],
+];
+
+/// The figures on the back rank of the start position with the given
+/// [index], from the a-file to the h-file.
+///
+/// Positions are numbered from 0 to 959 as proposed by Reinhard Scharnagl,
+/// where [STANDARD_POSITION] is the one of standard chess.
+pub fn back_rank(index: u16) -> Option<[Figure; BOARD_SIZE]> {
+    if index >= POSITIONS {
+        return None;
+    }
+
+    let mut index = index as usize;
+    let mut rank = [None; BOARD_SIZE];
+
+    // One bishop on each color, starting with the one on the light cells
+    rank[(index % 4) * 2 + 1] = Some(Figure::Bishop);
+    index /= 4;
+    rank[(index % 4) * 2] = Some(Figure::Bishop);
+    index /= 4;
+
+    let empty = |rank: &[Option<Figure>; BOARD_SIZE], nth: usize| {
+        (0..BOARD_SIZE)
+            .filter(|x| rank[*x].is_none())
+            .nth(nth)
+            .expect("there are enough empty cells left")
+    };
+
+    rank[empty(&rank, index % 6)] = Some(Figure::Queen);
+    index /= 6;
+
+    // The second knight comes first, since placing the first one would shift
+    // the empty cells.
+    let [first, second] = KNIGHTS[index];
+    rank[empty(&rank, second)] = Some(Figure::Knight);
+    rank[empty(&rank, first)] = Some(Figure::Knight);
+
+    // The king always ends up between the rooks.
+    for figure in [Figure::Rook, Figure::King, Figure::Rook] {
+        rank[empty(&rank, 0)] = Some(figure);
+    }
+
+    Some(rank.map(|figure| figure.expect("every cell is filled")))
+}
+
+/// The start position with the given [index], see [back_rank].
+pub fn position(index: u16) -> Option<State> {
+    let figures = back_rank(index)?;
+
+    let mut board = empty_board();
+    for color in [Color::White, Color::Black] {
+        let back_rank = color.back_rank();
+        let pawn_rank = match color {
+            Color::White => back_rank - 1,
+            Color::Black => back_rank + 1,
+        };
+
+        for (x, figure) in figures.iter().enumerate() {
+            board[back_rank][x] = Some(Piece::new(color, *figure));
+            board[pawn_rank][x] = Some(Piece::new(color, Figure::Pawn));
+        }
+    }
+
+    let file_of = |figure: Figure| figures.iter().position(|candidate| *candidate == figure);
+    let king = file_of(Figure::King)?;
+    let origins = CastlingOrigins {
+        king,
+        queen_side_rook: file_of(Figure::Rook)?,
+        king_side_rook: figures.iter().rposition(|figure| *figure == Figure::Rook)?,
+    };
+
+    Some(State::new(
+        Color::White,
+        board,
+        CastlingRights::all().with_origins(origins),
+        None,
+        0,
+        1,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        fen::{parse_state, serialize_board, to_fen, to_shredder_fen},
+        legal_moves, make_move, move_piece, piece_at, unmake_move, CastleSide, Cell, Event, Game,
+        Move,
+    };
+
+    use super::*;
+
+    #[test]
+    fn it_numbers_the_standard_position_518() {
+        assert_eq!(Some(Game::new().start()), position(STANDARD_POSITION));
+        assert_eq!(None, position(POSITIONS));
+    }
+
+    #[test]
+    fn it_generates_960_distinct_positions() {
+        let boards: HashSet<_> = (0..POSITIONS)
+            .map(|index| serialize_board(&position(index).unwrap().board))
+            .collect();
+        assert_eq!(POSITIONS as usize, boards.len());
+
+        for rank in (0..POSITIONS).map(|index| back_rank(index).unwrap()) {
+            let files = |figure| (0..BOARD_SIZE).filter(move |x| rank[*x] == figure);
+            let bishops: Vec<_> = files(Figure::Bishop).collect();
+            let rooks: Vec<_> = files(Figure::Rook).collect();
+            let king = files(Figure::King).next().unwrap();
+
+            assert_ne!(bishops[0] % 2, bishops[1] % 2, "{rank:?}");
+            assert!(rooks[0] < king && king < rooks[1], "{rank:?}");
+        }
+    }
+
+    #[test]
+    fn it_describes_positions_in_x_fen_and_shredder_fen() {
+        // The king starts on g1 between the rooks on f1 and h1
+        let state = position(0).unwrap();
+        assert_eq!(
+            "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1",
+            to_fen(&state)
+        );
+        assert_eq!(
+            "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w HFhf - 0 1",
+            to_shredder_fen(&state)
+        );
+        assert_eq!(state, parse_state(&to_fen(&state)).unwrap());
+        assert_eq!(state, parse_state(&to_shredder_fen(&state)).unwrap());
+
+        // With a second rook beyond the one allowed to castle, K would refer
+        // to the outer one.
+        let state = parse_state("4k3/8/8/8/8/8/8/1K3R1R w F - 0 1").unwrap();
+        assert_eq!(
+            Cell::F1,
+            state
+                .castling_rights
+                .rook_origin(Color::White, CastleSide::KingSide)
+        );
+        assert_eq!("4k3/8/8/8/8/8/8/1K3R1R w F - 0 1", to_fen(&state));
+
+        let state = parse_state("4k3/8/8/8/8/8/8/1K3R1R w K - 0 1").unwrap();
+        assert_eq!("4k3/8/8/8/8/8/8/1K3R1R w H - 0 1", to_shredder_fen(&state));
+    }
+
+    #[test]
+    fn the_king_and_rook_castle_onto_the_standard_cells() {
+        for (notation, the_move, king, rook) in [
+            // The rook resides where the king ends up
+            (
+                "7k/8/8/8/8/8/8/RK4R1 w AG - 0 1",
+                (Cell::B1, Cell::G1),
+                Cell::G1,
+                Cell::F1,
+            ),
+            (
+                "7k/8/8/8/8/8/8/RK4R1 w AG - 0 1",
+                (Cell::B1, Cell::A1),
+                Cell::C1,
+                Cell::D1,
+            ),
+            // The king already resides where it ends up
+            (
+                "7k/8/8/8/8/8/8/R5KR w HA - 0 1",
+                (Cell::G1, Cell::H1),
+                Cell::G1,
+                Cell::F1,
+            ),
+            (
+                "7k/8/8/8/8/8/8/R5KR w HA - 0 1",
+                (Cell::G1, Cell::A1),
+                Cell::C1,
+                Cell::D1,
+            ),
+        ] {
+            let state = parse_state(notation).unwrap();
+            let the_move = Move::simple(the_move.0, the_move.1);
+            assert!(legal_moves(&state).contains(&the_move), "{the_move:?}");
+
+            let (after, events) = move_piece(&state, the_move).unwrap();
+            assert_eq!(
+                Some(Piece::white(Figure::King)),
+                piece_at(king, &after.board)
+            );
+            assert_eq!(
+                Some(Piece::white(Figure::Rook)),
+                piece_at(rook, &after.board)
+            );
+            assert_eq!(3, after.board[7].iter().flatten().count());
+            assert!(events
+                .iter()
+                .any(|event| matches!(event, Event::Castle { .. })));
+
+            let mut played = state.clone();
+            let undo = make_move(&mut played, the_move).unwrap();
+            assert_eq!(after, played);
+            unmake_move(&mut played, undo);
+            assert_eq!(state, played);
+        }
+    }
+}
@@ -0,0 +1,511 @@
+use std::ops::{Add, AddAssign, Mul, Sub};
+
+use crate::{
+    bitboard::{attacks, Bitboard, Bitboards},
+    movement_patterns, Color, Coordinate, Figure, Piece, State, BOARD_SIZE,
+};
+
+/// A score that differs between the middlegame and the endgame, e.g. since
+/// the king should hide while queens are around, but take part once they are
+/// traded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Tapered {
+    middlegame: i32,
+    endgame: i32,
+}
+
+impl Tapered {
+    const fn new(middlegame: i32, endgame: i32) -> Self {
+        Self {
+            middlegame,
+            endgame,
+        }
+    }
+
+    /// Mixes both scores by the [phase] of the game, see [phase].
+    fn blend(self, phase: i32) -> i32 {
+        (self.middlegame * phase + self.endgame * (MIDDLEGAME_PHASE - phase)) / MIDDLEGAME_PHASE
+    }
+}
+
+impl Add for Tapered {
+    type Output = Tapered;
+
+    fn add(self, other: Tapered) -> Tapered {
+        Tapered::new(
+            self.middlegame + other.middlegame,
+            self.endgame + other.endgame,
+        )
+    }
+}
+
+impl AddAssign for Tapered {
+    fn add_assign(&mut self, other: Tapered) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Tapered {
+    type Output = Tapered;
+
+    fn sub(self, other: Tapered) -> Tapered {
+        Tapered::new(
+            self.middlegame - other.middlegame,
+            self.endgame - other.endgame,
+        )
+    }
+}
+
+impl Mul<i32> for Tapered {
+    type Output = Tapered;
+
+    fn mul(self, factor: i32) -> Tapered {
+        Tapered::new(self.middlegame * factor, self.endgame * factor)
+    }
+}
+
+/// The phase of the start position. Fewer pieces move the game towards the
+/// endgame, which has a phase of zero.
+const MIDDLEGAME_PHASE: i32 = 24;
+
+const BISHOP_PAIR: Tapered = Tapered::new(30, 50);
+const DOUBLED_PAWN: Tapered = Tapered::new(-10, -20);
+const ISOLATED_PAWN: Tapered = Tapered::new(-10, -15);
+/// Indexed by the number of rows a passed pawn advanced from its start.
+const PASSED_PAWN: [Tapered; BOARD_SIZE - 2] = [
+    Tapered::new(0, 0),
+    Tapered::new(5, 10),
+    Tapered::new(10, 20),
+    Tapered::new(20, 40),
+    Tapered::new(35, 70),
+    Tapered::new(60, 120),
+];
+/// Only matters in the middlegame, since there are too few pieces left to
+/// attack the king in the endgame.
+const PAWN_SHIELD: Tapered = Tapered::new(10, 0);
+const ATTACKED_KING_ZONE: Tapered = Tapered::new(-10, 0);
+
+/// Scores [state] in centipawns from the view of the player to move, i.e.
+/// positive scores favor them.
+///
+/// Besides material, this considers where pieces reside, how freely they
+/// move, the pawn structure, the safety of the kings and the bishop pair.
+/// Each of these is scored once for the middlegame and once for the
+/// endgame, which are blended by the material left on the board.
+pub fn evaluate(state: &State) -> i32 {
+    let bitboards = &state.bitboards;
+    let score = side(state, Color::White) - side(state, Color::Black);
+    let score = score.blend(phase(bitboards));
+
+    match state.player {
+        Color::White => score,
+        Color::Black => -score,
+    }
+}
+
+fn side(state: &State, color: Color) -> Tapered {
+    let bitboards = &state.bitboards;
+
+    pieces(state, color)
+        + pawn_structure(bitboards, color)
+        + king_safety(bitboards, color)
+        + bishop_pair(bitboards, color)
+}
+
+/// How far the game progressed from the middlegame, which is the start
+/// position, towards the endgame, which has no pieces but kings and pawns.
+fn phase(bitboards: &Bitboards) -> i32 {
+    let mut phase = 0;
+
+    for color in [Color::White, Color::Black] {
+        for (figure, weight) in [
+            (Figure::Knight, 1),
+            (Figure::Bishop, 1),
+            (Figure::Rook, 2),
+            (Figure::Queen, 4),
+        ] {
+            phase += bitboards.pieces(color, figure).len() as i32 * weight;
+        }
+    }
+
+    // Promotions may add more pieces than the game started with
+    phase.min(MIDDLEGAME_PHASE)
+}
+
+/// The material of [color], where each piece resides and how many cells it
+/// could move to.
+fn pieces(state: &State, color: Color) -> Tapered {
+    let mut score = Tapered::default();
+
+    for figure in Figure::STANDARD {
+        let piece = Piece::new(color, figure);
+        for at in state.bitboards.pieces(color, figure) {
+            score += value(figure) + placement(piece, at);
+
+            if let Some(weight) = mobility(figure) {
+                let moves = movement_patterns::of(state, at, piece).len() as i32;
+                score += weight * moves;
+            }
+        }
+    }
+
+    score
+}
+
+fn value(figure: Figure) -> Tapered {
+    match figure {
+        Figure::King => Tapered::new(0, 0),
+        Figure::Queen => Tapered::new(950, 1000),
+        Figure::Rook => Tapered::new(480, 540),
+        Figure::Bishop => Tapered::new(330, 320),
+        Figure::Knight => Tapered::new(320, 290),
+        Figure::Pawn => Tapered::new(100, 120),
+        Figure::Archbishop | Figure::Chancellor => {
+            unreachable!("Only played on a CapablancaState")
+        }
+    }
+}
+
+/// The score per cell a piece of [figure] could move to. Pawns and kings
+/// are left out, since moving them around is rarely a good idea.
+fn mobility(figure: Figure) -> Option<Tapered> {
+    match figure {
+        Figure::King | Figure::Pawn => None,
+        Figure::Queen => Some(Tapered::new(1, 2)),
+        Figure::Rook => Some(Tapered::new(2, 4)),
+        Figure::Bishop => Some(Tapered::new(5, 5)),
+        Figure::Knight => Some(Tapered::new(4, 4)),
+        Figure::Archbishop | Figure::Chancellor => {
+            unreachable!("Only played on a CapablancaState")
+        }
+    }
+}
+
+/// Looks up the score of [piece] residing on [at] in the tables below,
+/// which are laid out from the view of white.
+fn placement(piece: Piece, at: Coordinate) -> Tapered {
+    // Black's tables are the ones of white, mirrored vertically
+    let y = match piece.color {
+        Color::White => at.y,
+        Color::Black => BOARD_SIZE - 1 - at.y,
+    };
+    let index = Bitboard::index(Coordinate::new(at.x, y));
+
+    let (middlegame, endgame) = match piece.figure {
+        Figure::King => (&KING_MIDDLEGAME, &KING_ENDGAME),
+        Figure::Queen => (&QUEEN, &QUEEN),
+        Figure::Rook => (&ROOK, &ROOK),
+        Figure::Bishop => (&BISHOP, &BISHOP),
+        Figure::Knight => (&KNIGHT, &KNIGHT),
+        Figure::Pawn => (&PAWN_MIDDLEGAME, &PAWN_ENDGAME),
+        Figure::Archbishop | Figure::Chancellor => {
+            unreachable!("Only played on a CapablancaState")
+        }
+    };
+
+    Tapered::new(middlegame[index], endgame[index])
+}
+
+fn pawn_structure(bitboards: &Bitboards, color: Color) -> Tapered {
+    let pawns = bitboards.pieces(color, Figure::Pawn);
+    let opponent_pawns = bitboards.pieces(color.other(), Figure::Pawn);
+    let mut score = Tapered::default();
+
+    let mut per_file = [0; BOARD_SIZE];
+    for pawn in pawns {
+        per_file[pawn.x] += 1;
+    }
+    for count in per_file {
+        if count > 1 {
+            score += DOUBLED_PAWN * (count - 1);
+        }
+    }
+
+    for pawn in pawns {
+        let neighbours = pawn.x.saturating_sub(1)..=(pawn.x + 1).min(BOARD_SIZE - 1);
+
+        let isolated = neighbours.clone().all(|x| x == pawn.x || per_file[x] == 0);
+        if isolated {
+            score += ISOLATED_PAWN;
+        }
+
+        // No pawn of the opponent can stop or capture it on its way
+        let passed = opponent_pawns
+            .into_iter()
+            .filter(|other| neighbours.contains(&other.x))
+            .all(|other| !is_ahead(other, pawn, color));
+        if passed {
+            // FEN allows pawns on their own back rank, which count as not
+            // advanced at all
+            let advanced = color.back_rank().abs_diff(pawn.y).saturating_sub(1);
+            score += PASSED_PAWN[advanced.min(PASSED_PAWN.len() - 1)];
+        }
+    }
+
+    score
+}
+
+/// Whether [cell] is closer to the last row than [pawn] of [color].
+fn is_ahead(cell: Coordinate, pawn: Coordinate, color: Color) -> bool {
+    match color {
+        Color::White => cell.y < pawn.y,
+        Color::Black => cell.y > pawn.y,
+    }
+}
+
+/// Pawns in front of the king protect it, while attacks on the cells
+/// around it make it harder to defend.
+fn king_safety(bitboards: &Bitboards, color: Color) -> Tapered {
+    let Some(king) = bitboards.king_of(color) else {
+        return Tapered::default();
+    };
+    let mut score = Tapered::default();
+
+    let pawns = bitboards.pieces(color, Figure::Pawn);
+    let shield = attacks::king(king)
+        .into_iter()
+        .filter(|cell| is_ahead(*cell, king, color))
+        .filter(|cell| pawns.contains(*cell))
+        .count();
+    score += PAWN_SHIELD * shield as i32;
+
+    let zone = attacks::king(king) | Bitboard::of(king);
+    let attacked = zone
+        .into_iter()
+        .filter(|cell| attacks::is_attacked(bitboards, *cell, color.other()))
+        .count();
+    score += ATTACKED_KING_ZONE * attacked as i32;
+
+    score
+}
+
+fn bishop_pair(bitboards: &Bitboards, color: Color) -> Tapered {
+    match bitboards.pieces(color, Figure::Bishop).len() >= 2 {
+        true => BISHOP_PAIR,
+        false => Tapered::default(),
+    }
+}
+
+// The tables list the cells row by row from the view of white, starting at
+// a8 in the top left, like a [crate::Board] does.
+
+#[rustfmt::skip]
+const PAWN_MIDDLEGAME: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     50,  50,  50,  50,  50,  50,  50,  50,
+     10,  10,  20,  30,  30,  20,  10,  10,
+      5,   5,  10,  25,  25,  10,   5,   5,
+      0,   0,   0,  20,  20,   0,   0,   0,
+      5,  -5, -10,   0,   0, -10,  -5,   5,
+      5,  10,  10, -20, -20,  10,  10,   5,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const PAWN_ENDGAME: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     50,  50,  50,  50,  50,  50,  50,  50,
+     30,  30,  30,  30,  30,  30,  30,  30,
+     20,  20,  20,  20,  20,  20,  20,  20,
+     10,  10,  10,  10,  10,  10,  10,  10,
+      5,   5,   5,   5,   5,   5,   5,   5,
+      0,   0,   0,   0,   0,   0,   0,   0,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const KNIGHT: [i32; 64] = [
+    -50, -40, -30, -30, -30, -30, -40, -50,
+    -40, -20,   0,   0,   0,   0, -20, -40,
+    -30,   0,  10,  15,  15,  10,   0, -30,
+    -30,   5,  15,  20,  20,  15,   5, -30,
+    -30,   0,  15,  20,  20,  15,   0, -30,
+    -30,   5,  10,  15,  15,  10,   5, -30,
+    -40, -20,   0,   5,   5,   0, -20, -40,
+    -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+#[rustfmt::skip]
+const BISHOP: [i32; 64] = [
+    -20, -10, -10, -10, -10, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,  10,  10,   5,   0, -10,
+    -10,   5,   5,  10,  10,   5,   5, -10,
+    -10,   0,  10,  10,  10,  10,   0, -10,
+    -10,  10,  10,  10,  10,  10,  10, -10,
+    -10,   5,   0,   0,   0,   0,   5, -10,
+    -20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const ROOK: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+      5,  10,  10,  10,  10,  10,  10,   5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+      0,   0,   0,   5,   5,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const QUEEN: [i32; 64] = [
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,   5,   5,   5,   0, -10,
+     -5,   0,   5,   5,   5,   5,   0,  -5,
+      0,   0,   5,   5,   5,   5,   0,  -5,
+    -10,   5,   5,   5,   5,   5,   0, -10,
+    -10,   0,   5,   0,   0,   0,   0, -10,
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const KING_MIDDLEGAME: [i32; 64] = [
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -20, -30, -30, -40, -40, -30, -30, -20,
+    -10, -20, -20, -20, -20, -20, -20, -10,
+     20,  20,   0,   0,   0,   0,  20,  20,
+     20,  30,  10,   0,   0,  10,  30,  20,
+];
+
+#[rustfmt::skip]
+const KING_ENDGAME: [i32; 64] = [
+    -50, -40, -30, -20, -20, -30, -40, -50,
+    -30, -20, -10,   0,   0, -10, -20, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -30,   0,   0,   0,   0, -30, -30,
+    -50, -30, -30, -30, -30, -30, -30, -50,
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::{fen::parse_state, Game};
+
+    use super::*;
+
+    /// The same position with the colors swapped and the board flipped.
+    fn mirrored(notation: &str) -> String {
+        let parts: Vec<&str> = notation.split(' ').collect();
+        let swap_case = |part: &str| {
+            part.chars()
+                .map(|c| match c.is_uppercase() {
+                    true => c.to_ascii_lowercase(),
+                    false => c.to_ascii_uppercase(),
+                })
+                .collect::<String>()
+        };
+
+        let board = parts[0].split('/').rev().collect::<Vec<_>>().join("/");
+        let player = match parts[1] {
+            "w" => "b",
+            _ => "w",
+        };
+
+        format!(
+            "{} {} {} - {} {}",
+            swap_case(&board),
+            player,
+            swap_case(parts[2]),
+            parts[4],
+            parts[5]
+        )
+    }
+
+    #[test]
+    fn equal_positions_score_zero() {
+        assert_eq!(0, evaluate(&Game::new().start()));
+    }
+
+    #[test]
+    fn scores_are_relative_to_the_player_to_move() {
+        for notation in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        ] {
+            let score = evaluate(&parse_state(notation).unwrap());
+
+            let mirrored = mirrored(notation);
+            assert_eq!(
+                score,
+                evaluate(&parse_state(&mirrored).unwrap()),
+                "{mirrored}"
+            );
+
+            let other_player = notation.replace(" w ", " b ");
+            assert_eq!(-score, evaluate(&parse_state(&other_player).unwrap()));
+        }
+    }
+
+    #[test]
+    fn material_dominates_the_score() {
+        let state = parse_state("4k3/8/8/8/8/8/3Q4/4K3 w - - 0 1").unwrap();
+        assert!(evaluate(&state) > 800);
+
+        let state = parse_state("4k3/8/8/8/8/8/3Q4/4K3 b - - 0 1").unwrap();
+        assert!(evaluate(&state) < -800);
+    }
+
+    #[test]
+    fn kings_should_hide_in_the_middlegame_but_not_in_the_endgame() {
+        let hidden = "r1bq1rk1/pppp1ppp/2n2n2/2b1p3/2B1P3/2N2N2/PPPP1PPP/R1BQ1RK1 w - - 0 1";
+        let exposed = "r1bq1rk1/pppp1ppp/2n2n2/2b1p3/2B1P3/2N1KN2/PPPP1PPP/R1BQ1R2 w - - 0 1";
+        assert!(evaluate(&parse_state(hidden).unwrap()) > evaluate(&parse_state(exposed).unwrap()));
+
+        let corner = "8/8/8/8/8/8/4k2p/K7 w - - 0 1";
+        let center = "8/8/8/8/3K4/8/4k2p/8 w - - 0 1";
+        assert!(evaluate(&parse_state(center).unwrap()) > evaluate(&parse_state(corner).unwrap()));
+    }
+
+    #[test]
+    fn it_judges_the_pawn_structure() {
+        let structure = |notation: &str| {
+            let state = parse_state(notation).unwrap();
+            pawn_structure(&state.bitboards, Color::White)
+        };
+
+        // Two pawns on the same file, one of them isolated
+        let healthy = structure("4k3/pp6/8/8/8/8/PP6/4K3 w - - 0 1");
+        let doubled = structure("4k3/pp6/8/8/8/P7/P7/4K3 w - - 0 1");
+        assert_eq!(Tapered::default(), healthy);
+        assert_eq!(DOUBLED_PAWN + ISOLATED_PAWN * 2, doubled);
+
+        // Nothing stops the pawn on e5 anymore
+        let blocked = structure("4k3/3p4/8/4P3/8/8/8/4K3 w - - 0 1");
+        let passed = structure("4k3/2p5/8/4P3/8/8/8/4K3 w - - 0 1");
+        assert_eq!(ISOLATED_PAWN, blocked);
+        assert_eq!(ISOLATED_PAWN + PASSED_PAWN[3], passed);
+
+        // Pawns can't move onto their own back rank, but FEN may put them there
+        let back_rank = "4k3/8/8/8/8/8/8/P3K3 w - - 0 1";
+        assert_eq!(ISOLATED_PAWN + PASSED_PAWN[0], structure(back_rank));
+        let score = evaluate(&parse_state(back_rank).unwrap());
+        assert_eq!(score, evaluate(&parse_state(&mirrored(back_rank)).unwrap()));
+    }
+
+    #[test]
+    fn it_rewards_shelters_and_the_bishop_pair() {
+        let state = parse_state("6k1/5ppp/8/8/8/8/5PPP/1B3BK1 w - - 0 1").unwrap();
+        assert_eq!(BISHOP_PAIR, bishop_pair(&state.bitboards, Color::White));
+        assert_eq!(
+            Tapered::default(),
+            bishop_pair(&state.bitboards, Color::Black)
+        );
+        assert_eq!(PAWN_SHIELD * 3, king_safety(&state.bitboards, Color::White));
+
+        // The rook attacks the king and the cell next to it
+        let state = parse_state("6k1/5ppp/8/8/8/8/PPP5/2r3K1 w - - 0 1").unwrap();
+        assert_eq!(
+            ATTACKED_KING_ZONE * 2,
+            king_safety(&state.bitboards, Color::White)
+        );
+    }
+}
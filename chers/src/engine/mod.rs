@@ -1 +1,10 @@
+/// Judges positions by more than just their material.
+pub mod evaluation;
+
 pub mod score;
+
+/// Finds the best move by looking ahead a number of moves.
+pub mod search;
+
+/// Remembers positions the search already looked at.
+pub mod transposition;
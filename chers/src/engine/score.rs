@@ -1,8 +1,11 @@
-use crate::{pieces, Color, State};
+use crate::{pieces, Color, Figure, State};
 
 /// Computes a score for each player roughly based on
 /// https://www.pi.infn.it/%7Ecarosi/chess/shannon.txt
-pub fn shannon_value(state: &State) -> (u8, u8) {
+///
+/// This only counts material, see [super::evaluation::evaluate] for a score
+/// that considers the position as well.
+pub fn shannon_value(state: &State) -> (u16, u16) {
     let mut white = 0;
     let mut black = 0;
 
@@ -14,6 +17,9 @@ pub fn shannon_value(state: &State) -> (u8, u8) {
             crate::Figure::Bishop => 3,
             crate::Figure::Knight => 3,
             crate::Figure::Pawn => 1,
+            crate::Figure::Archbishop | crate::Figure::Chancellor => {
+                unreachable!("Only played on a CapablancaState")
+            }
         };
 
         if piece.color == Color::White {
@@ -25,3 +31,37 @@ pub fn shannon_value(state: &State) -> (u8, u8) {
 
     (white, black)
 }
+
+/// The value of a piece of [figure] in centipawns, i.e. hundredths of a pawn.
+///
+/// Kings can't be traded, so they are worth nothing here. Losing them is
+/// scored by the search instead.
+pub fn centipawns(figure: Figure) -> i32 {
+    match figure {
+        Figure::King => 0,
+        Figure::Queen => 900,
+        Figure::Rook => 500,
+        Figure::Bishop => 300,
+        Figure::Knight => 300,
+        Figure::Pawn => 100,
+        Figure::Archbishop | Figure::Chancellor => {
+            unreachable!("Only played on a CapablancaState")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fen::parse_state, Game};
+
+    use super::*;
+
+    #[test]
+    fn it_counts_the_material_of_both_players() {
+        assert_eq!((239, 239), shannon_value(&Game::new().start()));
+
+        // Promoting every pawn would not fit into a byte
+        let state = parse_state("QQQQQQQQ/QQQQQQQQ/QQQQQQQQ/QQQQQQQQ/8/8/8/K6k w - - 0 1").unwrap();
+        assert_eq!((200 + 32 * 9, 200), shannon_value(&state));
+    }
+}
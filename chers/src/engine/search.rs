@@ -0,0 +1,497 @@
+use crate::{
+    variants::{Standard, Variant},
+    Event, Move, State,
+};
+
+use super::{
+    evaluation::evaluate,
+    score::centipawns,
+    transposition::{Bound, TranspositionTable},
+};
+
+/// The score of mating the opponent right away.
+///
+/// Mates further ahead score [MATE] minus the number of plies (half moves)
+/// until the mate, so shorter mates are preferred and getting mated later
+/// is preferred to getting mated sooner.
+pub const MATE: i32 = 1_000_000;
+
+/// The deepest the search ever looks, in plies.
+pub const MAX_PLY: usize = 128;
+
+/// Scores of at least this magnitude describe a forced mate rather than an
+/// advantage in material or position.
+pub const MATE_THRESHOLD: i32 = MATE - MAX_PLY as i32;
+
+/// Higher than any score a position can have.
+const INFINITY: i32 = MATE + 1;
+
+/// The outcome of a [search].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    /// The move to play, which is [None] if the game already ended.
+    pub best_move: Option<Move>,
+    /// The score in centipawns from the view of the player to move, see
+    /// [MATE] for how forced mates are scored.
+    pub score: i32,
+    /// The moves both players are expected to play, starting with
+    /// [SearchResult::best_move].
+    pub principal_variation: Vec<Move>,
+    /// The depth of the last completed iteration, in plies.
+    pub depth: usize,
+    /// The number of positions visited.
+    pub nodes: u64,
+}
+
+impl SearchResult {
+    /// The number of moves (not plies) until the mate the score predicts,
+    /// which is positive if the player to move mates and negative if they
+    /// get mated.
+    pub fn mate_in(&self) -> Option<i32> {
+        mate_in(self.score)
+    }
+}
+
+/// Like [SearchResult::mate_in], but for any score.
+pub fn mate_in(score: i32) -> Option<i32> {
+    if score.abs() < MATE_THRESHOLD {
+        return None;
+    }
+
+    let plies = MATE - score.abs();
+    let moves = (plies + 1) / 2;
+
+    Some(match score > 0 {
+        true => moves,
+        false => -moves,
+    })
+}
+
+/// Searches [state] for the best move of the player to move, looking
+/// [depth] plies ahead.
+///
+/// At least one ply is searched, so there is a best move unless the game
+/// already ended.
+pub fn search(state: &State, depth: usize) -> SearchResult {
+    search_by(&Standard, state, depth)
+}
+
+/// Like [search], but by the rules of [variant].
+pub fn search_by(variant: &dyn Variant, state: &State, depth: usize) -> SearchResult {
+    search_with(variant, state, depth, &mut TranspositionTable::default())
+}
+
+/// Like [search_by], but remembering positions in [table], which can be kept
+/// from one move of a game to the next and cleared before the next game.
+///
+/// Uses negamax with alpha-beta pruning and iterative deepening: the
+/// position is searched one ply deeper each iteration, trying the principal
+/// variation of the previous one first, which lets alpha-beta cut off more
+/// of the remaining moves. Positions searched deep enough before are scored
+/// from [table] rather than searched again.
+pub fn search_with(
+    variant: &dyn Variant,
+    state: &State,
+    depth: usize,
+    table: &mut TranspositionTable,
+) -> SearchResult {
+    table.new_search();
+    let mut searcher = Searcher {
+        variant,
+        table,
+        nodes: 0,
+        path: Vec::new(),
+        played: Vec::new(),
+        previous_variation: Vec::new(),
+    };
+    let mut state = state.clone();
+
+    let mut result = SearchResult {
+        best_move: None,
+        score: 0,
+        principal_variation: Vec::new(),
+        depth: 0,
+        nodes: 0,
+    };
+
+    for iteration in 1..=depth.clamp(1, MAX_PLY) {
+        let mut variation = Vec::new();
+        let score = searcher.negamax(
+            &mut state,
+            iteration,
+            0,
+            -INFINITY,
+            INFINITY,
+            &mut variation,
+        );
+
+        result = SearchResult {
+            best_move: variation.first().copied(),
+            score,
+            principal_variation: variation.clone(),
+            depth: iteration,
+            nodes: searcher.nodes,
+        };
+        searcher.previous_variation = variation;
+
+        // A mate within the searched plies is forced, so looking further
+        // can't find a shorter one, or a way out of it.
+        let game_ended = result.best_move.is_none();
+        let mate_found = score.abs() >= MATE_THRESHOLD && MATE - score.abs() <= iteration as i32;
+        if game_ended || mate_found {
+            break;
+        }
+    }
+
+    result
+}
+
+struct Searcher<'a> {
+    variant: &'a dyn Variant,
+    table: &'a mut TranspositionTable,
+    nodes: u64,
+    /// The keys of the positions leading to the current one, which are used
+    /// to detect repetitions.
+    path: Vec<u64>,
+    /// The moves leading to the current position.
+    played: Vec<Move>,
+    /// The principal variation of the previous iteration, which is searched
+    /// first.
+    previous_variation: Vec<Move>,
+}
+
+impl Searcher<'_> {
+    /// Scores [state] from the view of the player to move, assuming both
+    /// players play the best moves [depth] plies ahead.
+    ///
+    /// Moves scoring [alpha] or less are not good enough for the player to
+    /// move, while moves scoring [beta] or more are too good to be allowed by
+    /// the opponent. [variation] receives the best line found.
+    fn negamax(
+        &mut self,
+        state: &mut State,
+        depth: usize,
+        ply: usize,
+        mut alpha: i32,
+        mut beta: i32,
+        variation: &mut Vec<Move>,
+    ) -> i32 {
+        self.nodes += 1;
+        variation.clear();
+
+        // Repeating a position is a draw, as the player who could avoid it
+        // would otherwise just do so.
+        if ply > 0 && self.is_repetition(state) {
+            return 0;
+        }
+
+        // Telling how the game ended is costly, so it is only done once it did
+        let mut moves = self.variant.legal_moves(state);
+        if self.variant.has_ended(state, &moves) {
+            if let Some(score) = self.outcome(state, ply) {
+                return score;
+            }
+        }
+
+        if depth == 0 || ply >= MAX_PLY {
+            return evaluate(state);
+        }
+
+        // No line can be better than mating right away, or worse than getting
+        // mated right away, so the window can be narrowed accordingly.
+        alpha = alpha.max(-MATE + ply as i32);
+        beta = beta.min(MATE - ply as i32 - 1);
+        if alpha >= beta {
+            return alpha;
+        }
+
+        // The root is always searched, as its best move is needed.
+        let key = state.zobrist_key();
+        let entry = self.table.probe(key);
+        if let Some(entry) = entry.filter(|entry| ply > 0 && entry.depth as usize >= depth) {
+            let score = score_from_table(entry.score, ply);
+            let is_cutoff = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => score >= beta,
+                Bound::Upper => score <= alpha,
+            };
+            if is_cutoff {
+                self.follow_table(state, depth, variation);
+                return score;
+            }
+        }
+        let original_alpha = alpha;
+
+        self.order(state, &mut moves, entry.and_then(|entry| entry.best_move));
+
+        let mut best = -INFINITY;
+        let mut line = Vec::new();
+        self.path.push(key);
+        for the_move in moves {
+            let Ok(undo) = self.variant.make_move(state, the_move) else {
+                continue;
+            };
+            self.played.push(the_move);
+            let score = -self.negamax(state, depth - 1, ply + 1, -beta, -alpha, &mut line);
+            self.played.pop();
+            self.variant.unmake_move(state, undo);
+
+            if score > best {
+                best = score;
+                variation.clear();
+                variation.push(the_move);
+                variation.extend_from_slice(&line);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        self.path.pop();
+
+        if best > -INFINITY {
+            let bound = match best {
+                _ if best <= original_alpha => Bound::Upper,
+                _ if best >= beta => Bound::Lower,
+                _ => Bound::Exact,
+            };
+            // When no move reached alpha, the best of them is just a guess.
+            let best_move = match bound {
+                Bound::Upper => None,
+                _ => variation.first().copied(),
+            };
+            let score = score_to_table(best, ply);
+            self.table.store(key, depth as u8, bound, score, best_move);
+        }
+
+        best
+    }
+
+    /// Fills [variation] with the best moves the table stores from [state]
+    /// on, up to [depth] of them, as the line the score of an entry is based
+    /// on isn't stored itself.
+    fn follow_table(&self, state: &mut State, depth: usize, variation: &mut Vec<Move>) {
+        let mut keys = Vec::new();
+        let mut undos = Vec::new();
+
+        while variation.len() < depth {
+            let key = state.zobrist_key();
+            if keys.contains(&key) {
+                break;
+            }
+            // Different positions can share a key, so the move might not be
+            // legal in this one.
+            let Some(the_move) = self.table.probe(key).and_then(|entry| entry.best_move) else {
+                break;
+            };
+            if !self.variant.legal_moves(state).contains(&the_move) {
+                break;
+            }
+            let Ok(undo) = self.variant.make_move(state, the_move) else {
+                break;
+            };
+
+            keys.push(key);
+            undos.push(undo);
+            variation.push(the_move);
+        }
+
+        for undo in undos.into_iter().rev() {
+            self.variant.unmake_move(state, undo);
+        }
+    }
+
+    /// The score of [state] if the game ended there, which is reached after
+    /// [ply] plies.
+    fn outcome(&self, state: &State, ply: usize) -> Option<i32> {
+        let mated = -MATE + ply as i32;
+
+        self.variant
+            .outcome_events(state)
+            .into_iter()
+            .find_map(|event| match event {
+                Event::Mate => Some(mated),
+                Event::Win { winner, reason: _ } => Some(match winner == state.player {
+                    true => -mated,
+                    false => mated,
+                }),
+                Event::Stalemate | Event::Draw { reason: _ } => Some(0),
+                _ => None,
+            })
+    }
+
+    fn is_repetition(&self, state: &State) -> bool {
+        // Captures and pawn moves can't be taken back, so no position before
+        // them can repeat.
+        let key = state.zobrist_key();
+        self.path
+            .iter()
+            .rev()
+            .take(state.halfmove_clock as usize)
+            .any(|earlier| *earlier == key)
+    }
+
+    /// Sorts [moves] so that the most promising ones are searched first: the
+    /// one of the previous principal variation, then the best one found in an
+    /// earlier search of [state], then captures of valuable pieces.
+    fn order(&self, state: &State, moves: &mut [Move], hash_move: Option<Move>) {
+        // The move of the previous variation only applies if the same moves
+        // led to the current position.
+        let ply = self.played.len();
+        let principal = match self.previous_variation.starts_with(&self.played) {
+            true => self.previous_variation.get(ply).copied(),
+            false => None,
+        };
+
+        moves.sort_by_key(|the_move| {
+            if Some(*the_move) == principal {
+                return i32::MIN;
+            }
+            if Some(*the_move) == hash_move {
+                return i32::MIN + 1;
+            }
+
+            match state.bitboards.piece_at(the_move.to) {
+                Some(captured) => -centipawns(captured.figure) - 1,
+                None => 0,
+            }
+        });
+    }
+}
+
+/// Mates are stored counted from the position rather than from the root, as
+/// the position can be reached after any number of plies.
+fn score_to_table(score: i32, ply: usize) -> i32 {
+    match score {
+        _ if score >= MATE_THRESHOLD => score + ply as i32,
+        _ if score <= -MATE_THRESHOLD => score - ply as i32,
+        _ => score,
+    }
+}
+
+/// Reverses [score_to_table] for a position reached after [ply] plies.
+fn score_from_table(score: i32, ply: usize) -> i32 {
+    match score {
+        _ if score >= MATE_THRESHOLD => score - ply as i32,
+        _ if score <= -MATE_THRESHOLD => score + ply as i32,
+        _ => score,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fen::parse_state, move_piece, variants::KingOfTheHill, Cell};
+
+    use super::*;
+
+    #[test]
+    fn it_finds_the_shortest_mate() {
+        let state = parse_state("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let result = search(&state, 4);
+
+        assert_eq!(Some(Move::simple(Cell::A1, Cell::A8)), result.best_move);
+        assert_eq!(Some(1), result.mate_in());
+        assert_eq!(MATE - 1, result.score);
+        // Searching deeper can't find anything better
+        assert_eq!(1, result.depth);
+
+        // Taking the opposition first forces a mate on the next move
+        let state = parse_state("k7/8/2K5/8/8/8/8/7R w - - 0 1").unwrap();
+        let result = search(&state, 4);
+        assert_eq!(Some(2), result.mate_in());
+        assert_eq!(3, result.principal_variation.len());
+    }
+
+    #[test]
+    fn it_knows_when_it_gets_mated() {
+        let state = parse_state("k7/8/1K6/8/8/8/8/7R b - - 1 1").unwrap();
+        let result = search(&state, 3);
+
+        assert_eq!(Some(Move::simple(Cell::A8, Cell::B8)), result.best_move);
+        assert_eq!(Some(-1), result.mate_in());
+    }
+
+    #[test]
+    fn it_wins_material() {
+        let state = parse_state("4k3/8/8/3q4/8/8/8/3RK3 w - - 0 1").unwrap();
+        let result = search(&state, 3);
+
+        assert_eq!(Some(Move::simple(Cell::D1, Cell::D5)), result.best_move);
+        assert!(result.score >= 500, "{}", result.score);
+        assert_eq!(None, result.mate_in());
+    }
+
+    #[test]
+    fn the_principal_variation_can_be_played() {
+        let mut state =
+            parse_state("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let result = search(&state, 3);
+
+        assert_eq!(3, result.depth);
+        assert_eq!(3, result.principal_variation.len());
+        assert_eq!(
+            result.best_move,
+            result.principal_variation.first().copied()
+        );
+        for the_move in result.principal_variation {
+            (state, _) = move_piece(&state, the_move).unwrap();
+        }
+    }
+
+    #[test]
+    fn it_has_no_move_once_the_game_ended() {
+        let state = parse_state("k7/8/1Q6/8/8/8/8/7K b - - 0 1").unwrap();
+        let result = search(&state, 3);
+
+        assert_eq!(None, result.best_move);
+        assert_eq!(0, result.score);
+        assert!(result.principal_variation.is_empty());
+
+        // Even without looking ahead, a game that goes on has a best move
+        let state = parse_state("k7/8/1Q6/8/8/8/8/7K w - - 0 1").unwrap();
+        let result = search(&state, 0);
+        assert_eq!(1, result.depth);
+        assert!(result.best_move.is_some());
+    }
+
+    #[test]
+    fn it_plays_by_the_rules_of_the_variant() {
+        let state = parse_state("k7/8/8/8/8/4K3/8/8 w - - 0 1").unwrap();
+        let result = search_by(&KingOfTheHill, &state, 3);
+
+        assert_eq!(Some(1), result.mate_in());
+        assert!([Cell::D4, Cell::E4].contains(&result.best_move.unwrap().to));
+    }
+
+    #[test]
+    fn it_reuses_earlier_searches() {
+        let state =
+            parse_state("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let mut table = TranspositionTable::new(1);
+
+        let first = search_with(&Standard, &state, 3, &mut table);
+        let second = search_with(&Standard, &state, 3, &mut table);
+        assert_eq!(first.best_move, second.best_move);
+        assert_eq!(first.score, second.score);
+        assert!(second.nodes < first.nodes, "{}", second.nodes);
+
+        table.clear();
+        let cleared = search_with(&Standard, &state, 3, &mut table);
+        assert_eq!(first.nodes, cleared.nodes);
+    }
+
+    #[test]
+    fn mates_are_stored_relative_to_the_position() {
+        // Mating in 2 plies from a position 3 plies into the search
+        let score = MATE - 5;
+        assert_eq!(MATE - 2, score_to_table(score, 3));
+        // ...is mating in 2 plies from it 1 ply into another search
+        assert_eq!(MATE - 3, score_from_table(MATE - 2, 1));
+
+        assert_eq!(-MATE + 2, score_to_table(-MATE + 5, 3));
+        assert_eq!(-MATE + 5, score_from_table(-MATE + 2, 3));
+        assert_eq!(150, score_from_table(score_to_table(150, 3), 7));
+    }
+}
@@ -0,0 +1,201 @@
+use std::mem::size_of;
+
+use crate::Move;
+
+/// How the stored score relates to the true score of a position, which is
+/// only known exactly if no move was cut off by alpha-beta pruning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    /// The score is the true score.
+    Exact,
+    /// A move was found that is at least as good as the score, after which
+    /// the remaining moves were skipped.
+    Lower,
+    /// No move was better than the score.
+    Upper,
+}
+
+/// What a search found out about a position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Entry {
+    /// The key of the position, see [crate::zobrist]. Many positions share a
+    /// slot of the table, so this tells them apart.
+    pub key: u64,
+    /// The number of plies the position was searched ahead.
+    pub depth: u8,
+    pub bound: Bound,
+    /// The score from the view of the player to move. Mates are counted from
+    /// the position itself rather than from the root of the search.
+    pub score: i32,
+    /// The best move found, which is searched first when the position is
+    /// reached again.
+    pub best_move: Option<Move>,
+    /// The search that stored the entry, see [TranspositionTable::new_search].
+    generation: u8,
+}
+
+/// Remembers the results of searching positions, which are reached again
+/// and again through different orders of the same moves.
+///
+/// The table has a fixed size, so each position is assigned a single slot
+/// by its key. When two positions compete for one, the one searched deeper
+/// is kept, unless it is left over from an earlier search.
+#[derive(Debug, Clone)]
+pub struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+    generation: u8,
+}
+
+impl TranspositionTable {
+    /// The size [crate::engine::search::search] uses.
+    pub const DEFAULT_MEGABYTES: usize = 16;
+
+    /// A table taking up about [megabytes] of memory, but holding at least
+    /// one entry.
+    pub fn new(megabytes: usize) -> Self {
+        let slots = (megabytes * 1024 * 1024 / size_of::<Option<Entry>>()).max(1);
+
+        Self {
+            entries: vec![None; slots],
+            generation: 0,
+        }
+    }
+
+    /// The number of entries the table can hold.
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// The number of entries the table currently holds.
+    pub fn len(&self) -> usize {
+        self.entries.iter().flatten().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.iter().all(Option::is_none)
+    }
+
+    /// Forgets every entry, e.g. before starting a new game.
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+        self.generation = 0;
+    }
+
+    /// Marks the entries stored so far as left over from an earlier search,
+    /// so newer ones replace them regardless of their depth.
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// The entry of the position with [key], if it was stored and not
+    /// replaced since.
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        self.entries[self.slot(key)].filter(|entry| entry.key == key)
+    }
+
+    /// Stores what a search of the position with [key] found, unless the
+    /// slot holds a deeper search of the current one.
+    pub fn store(
+        &mut self,
+        key: u64,
+        depth: u8,
+        bound: Bound,
+        score: i32,
+        best_move: Option<Move>,
+    ) {
+        let slot = self.slot(key);
+        let generation = self.generation;
+
+        let mut best_move = best_move;
+        if let Some(existing) = self.entries[slot] {
+            let is_same_position = existing.key == key;
+            let is_outdated = existing.generation != generation;
+            if !is_same_position && !is_outdated && existing.depth > depth {
+                return;
+            }
+
+            // A search that found no best move does not make the earlier
+            // one any worse.
+            if is_same_position && best_move.is_none() {
+                best_move = existing.best_move;
+            }
+        }
+
+        self.entries[slot] = Some(Entry {
+            key,
+            depth,
+            bound,
+            score,
+            best_move,
+            generation,
+        });
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        // Maps the key onto the slots by multiplying, which distributes
+        // the keys evenly without a costly division.
+        ((key as u128 * self.entries.len() as u128) >> 64) as usize
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MEGABYTES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Cell;
+
+    use super::*;
+
+    #[test]
+    fn it_is_sized_in_megabytes() {
+        let table = TranspositionTable::new(1);
+        assert_eq!(1024 * 1024 / size_of::<Option<Entry>>(), table.capacity());
+        assert!(table.is_empty());
+
+        assert_eq!(1, TranspositionTable::new(0).capacity());
+    }
+
+    #[test]
+    fn it_remembers_entries_until_cleared() {
+        let mut table = TranspositionTable::new(1);
+        let the_move = Some(Move::simple(Cell::E2, Cell::E4));
+
+        table.store(42, 3, Bound::Exact, 25, the_move);
+        let entry = table.probe(42).unwrap();
+        assert_eq!((3, Bound::Exact, 25, the_move), {
+            (entry.depth, entry.bound, entry.score, entry.best_move)
+        });
+        assert_eq!(None, table.probe(43));
+        assert_eq!(1, table.len());
+
+        // Searching again without finding a move keeps the earlier one
+        table.store(42, 4, Bound::Upper, -10, None);
+        assert_eq!(the_move, table.probe(42).unwrap().best_move);
+
+        table.clear();
+        assert_eq!(None, table.probe(42));
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn deeper_searches_are_kept_until_outdated() {
+        // With a single slot, every position competes for it
+        let mut table = TranspositionTable::new(0);
+
+        table.store(1, 5, Bound::Exact, 0, None);
+        table.store(2, 3, Bound::Lower, 0, None);
+        assert!(table.probe(1).is_some());
+        assert_eq!(None, table.probe(2));
+
+        table.store(2, 5, Bound::Lower, 0, None);
+        assert!(table.probe(2).is_some());
+
+        table.new_search();
+        table.store(3, 1, Bound::Upper, 0, None);
+        assert!(table.probe(3).is_some());
+    }
+}
@@ -0,0 +1,401 @@
+use std::{fmt::Display, iter::Peekable, str::Chars};
+
+use crate::{
+    fen::{parse_position, serialize_position, validate, CouldNotParse},
+    moves::{
+        san::{parse_san, to_san},
+        serialization::ConversionError,
+    },
+    Move, State,
+};
+
+/// A position in Extended Position Description, together with the
+/// operations describing it.
+///
+/// The common operations are available as fields, all others are kept in
+/// [Epd::operations] as they were written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Epd {
+    /// The position, whose clocks are taken from the `hmvc` and `fmvn`
+    /// operations, if present.
+    pub state: State,
+    /// `bm`: The moves considered best.
+    pub best_moves: Vec<Move>,
+    /// `am`: The moves that should be avoided.
+    pub avoid_moves: Vec<Move>,
+    /// `id`: The name of the position, usually unique within a suite.
+    pub id: Option<String>,
+    /// `c0`: The primary comment.
+    pub comment: Option<String>,
+    /// `acd`: The depth in plies the position was analysed to.
+    pub analysis_depth: Option<u32>,
+    pub operations: Vec<(String, Vec<String>)>,
+}
+
+impl Epd {
+    pub fn new(state: State) -> Self {
+        Self {
+            state,
+            best_moves: Vec::new(),
+            avoid_moves: Vec::new(),
+            id: None,
+            comment: None,
+            analysis_depth: None,
+            operations: Vec::new(),
+        }
+    }
+
+    /// Whether playing [the_move] solves the position, i.e. it is one of the
+    /// best moves or, if none are given, none of the ones to avoid.
+    pub fn is_solved_by(&self, the_move: &Move) -> bool {
+        match self.best_moves.is_empty() {
+            true => !self.avoid_moves.contains(the_move),
+            false => self.best_moves.contains(the_move),
+        }
+    }
+
+    /// The operands of an operation that is not available as a field.
+    pub fn operation(&self, opcode: &str) -> Option<&[String]> {
+        self.operations
+            .iter()
+            .find(|(candidate, _)| candidate == opcode)
+            .map(|(_, operands)| operands.as_slice())
+    }
+}
+
+#[derive(Debug)]
+pub enum EpdError {
+    InvalidNumberOfFields,
+    InvalidPosition(CouldNotParse),
+    /// An operation is malformed, e.g. because a string is never closed.
+    InvalidOperation {
+        opcode: String,
+        reason: String,
+    },
+    /// A move of `bm` or `am` is not legal in the position.
+    InvalidMove {
+        opcode: String,
+        cause: ConversionError,
+    },
+}
+
+impl Display for EpdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EpdError::InvalidNumberOfFields => write!(f, "Expected four fields"),
+            EpdError::InvalidPosition(cause) => write!(f, "Invalid position: {cause:?}"),
+            EpdError::InvalidOperation { opcode, reason } => {
+                write!(f, "Invalid operation '{opcode}': {reason}")
+            }
+            EpdError::InvalidMove { opcode, cause } => {
+                write!(f, "Invalid move in operation '{opcode}': {cause}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EpdError {}
+
+impl From<CouldNotParse> for EpdError {
+    fn from(value: CouldNotParse) -> Self {
+        EpdError::InvalidPosition(value)
+    }
+}
+
+/// Parses a single line of EPD, e.g.
+/// `2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id "WAC.001";`
+///
+/// Since the moves of `bm` and `am` are resolved against the position, it is
+/// validated like [crate::fen::parse_valid_state] does.
+pub fn parse_epd(line: &str) -> Result<Epd, EpdError> {
+    let mut fields = [""; 4];
+    let mut rest = line.trim();
+    for field in fields.iter_mut() {
+        let (current, remaining) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        if current.is_empty() {
+            return Err(EpdError::InvalidNumberOfFields);
+        }
+        *field = current;
+        rest = remaining.trim_start();
+    }
+
+    let operations = parse_operations(rest)?;
+
+    let number = |opcode: &str| -> Result<Option<u32>, EpdError> {
+        let Some((_, operands)) = operations.iter().find(|(candidate, _)| candidate == opcode)
+        else {
+            return Ok(None);
+        };
+
+        match operands.as_slice() {
+            [value] => value
+                .parse()
+                .map(Some)
+                .map_err(|_| EpdError::InvalidOperation {
+                    opcode: opcode.to_string(),
+                    reason: format!("'{value}' is not a number"),
+                }),
+            _ => Err(EpdError::InvalidOperation {
+                opcode: opcode.to_string(),
+                reason: String::from("Expected a single number"),
+            }),
+        }
+    };
+
+    let clock = |opcode, default| -> Result<u16, EpdError> {
+        match number(opcode)? {
+            Some(value) => u16::try_from(value).map_err(|_| EpdError::InvalidOperation {
+                opcode: opcode.to_string(),
+                reason: String::from("Number is too large"),
+            }),
+            None => Ok(default),
+        }
+    };
+
+    let state = parse_position(fields, clock("hmvc", 0)?, clock("fmvn", 1)?)?;
+    validate(&state).map_err(CouldNotParse::from)?;
+
+    let moves = |opcode: &str| -> Result<Vec<Move>, EpdError> {
+        let Some((_, operands)) = operations.iter().find(|(candidate, _)| candidate == opcode)
+        else {
+            return Ok(Vec::new());
+        };
+
+        operands
+            .iter()
+            .map(|san| {
+                parse_san(&state, san).map_err(|cause| EpdError::InvalidMove {
+                    opcode: opcode.to_string(),
+                    cause,
+                })
+            })
+            .collect()
+    };
+
+    let string = |opcode: &str| {
+        operations
+            .iter()
+            .find(|(candidate, _)| candidate == opcode)
+            .map(|(_, operands)| operands.join(" "))
+    };
+
+    Ok(Epd {
+        best_moves: moves("bm")?,
+        avoid_moves: moves("am")?,
+        id: string("id"),
+        comment: string("c0"),
+        analysis_depth: number("acd")?,
+        operations: operations
+            .iter()
+            .filter(|(opcode, _)| !TYPED_OPCODES.contains(&opcode.as_str()))
+            .cloned()
+            .collect(),
+        state,
+    })
+}
+
+/// The opcodes represented by fields of [Epd] instead of its operations.
+const TYPED_OPCODES: [&str; 7] = ["bm", "am", "id", "c0", "acd", "hmvc", "fmvn"];
+
+/// Describes [epd] in Extended Position Description.
+///
+/// The clocks are only written if they differ from the ones of a new game,
+/// since most suites omit them.
+pub fn to_epd(epd: &Epd) -> String {
+    let mut notation = serialize_position(&epd.state, false);
+    let mut operation = |opcode: &str, operands: &[String]| {
+        notation += &format!(" {opcode}");
+        for operand in operands {
+            notation.push(' ');
+            notation += &quote_if_required(operand);
+        }
+        notation.push(';');
+    };
+
+    let san = |moves: &[Move]| -> Vec<String> {
+        moves
+            .iter()
+            .map(|the_move| to_san(&epd.state, the_move))
+            .collect()
+    };
+
+    if !epd.best_moves.is_empty() {
+        operation("bm", &san(&epd.best_moves));
+    }
+    if !epd.avoid_moves.is_empty() {
+        operation("am", &san(&epd.avoid_moves));
+    }
+    if let Some(id) = &epd.id {
+        operation("id", &[format!("\"{id}\"")]);
+    }
+    if let Some(comment) = &epd.comment {
+        operation("c0", &[format!("\"{comment}\"")]);
+    }
+    if let Some(depth) = epd.analysis_depth {
+        operation("acd", &[depth.to_string()]);
+    }
+    if epd.state.halfmove_clock != 0 {
+        operation("hmvc", &[epd.state.halfmove_clock.to_string()]);
+    }
+    if epd.state.fullmove_number != 1 {
+        operation("fmvn", &[epd.state.fullmove_number.to_string()]);
+    }
+    for (opcode, operands) in &epd.operations {
+        operation(opcode, operands);
+    }
+
+    notation
+}
+
+fn quote_if_required(operand: &str) -> String {
+    let is_quoted = operand.len() >= 2 && operand.starts_with('"') && operand.ends_with('"');
+    match !is_quoted && (operand.is_empty() || operand.contains([' ', ';', '"'])) {
+        true => format!("\"{operand}\""),
+        false => operand.to_string(),
+    }
+}
+
+/// Splits operations like `bm Nf3 e4; id "Some name";` into their opcode
+/// and operands, removing the quotes around strings.
+fn parse_operations(notation: &str) -> Result<Vec<(String, Vec<String>)>, EpdError> {
+    let mut operations = Vec::new();
+    let mut chars = notation.chars().peekable();
+
+    loop {
+        skip_whitespace(&mut chars);
+        let opcode = read_word(&mut chars);
+        if opcode.is_empty() {
+            break;
+        }
+
+        let mut operands = Vec::new();
+        loop {
+            skip_whitespace(&mut chars);
+            match chars.peek() {
+                // The last terminator is frequently omitted
+                Some(';') | None => {
+                    chars.next();
+                    break;
+                }
+                Some('"') => {
+                    chars.next();
+                    let string = read_string(&mut chars).ok_or(EpdError::InvalidOperation {
+                        opcode: opcode.clone(),
+                        reason: String::from("String is never closed"),
+                    })?;
+                    operands.push(string);
+                }
+                Some(_) => operands.push(read_word(&mut chars)),
+            }
+        }
+
+        operations.push((opcode, operands));
+    }
+
+    Ok(operations)
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn read_word(chars: &mut Peekable<Chars>) -> String {
+    let mut word = String::new();
+    while let Some(character) = chars.next_if(|c| !c.is_whitespace() && *c != ';') {
+        word.push(character);
+    }
+
+    word
+}
+
+/// Reads up to the closing quote, where the opening one is already read.
+fn read_string(chars: &mut Peekable<Chars>) -> Option<String> {
+    let mut string = String::new();
+    loop {
+        match chars.next()? {
+            '\\' => string.push(chars.next()?),
+            '"' => return Some(string),
+            character => string.push(character),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fen::to_fen, Cell};
+
+    use super::*;
+
+    #[test]
+    fn it_parses_test_suite_positions() {
+        let epd = parse_epd(
+            "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id \"WAC.001\";",
+        )
+        .unwrap();
+
+        assert_eq!(vec![Move::simple(Cell::G3, Cell::G6)], epd.best_moves);
+        assert_eq!(Some(String::from("WAC.001")), epd.id);
+        assert_eq!(
+            "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - 0 1",
+            to_fen(&epd.state)
+        );
+        assert!(epd.is_solved_by(&Move::simple(Cell::G3, Cell::G6)));
+        assert!(!epd.is_solved_by(&Move::simple(Cell::G3, Cell::G4)));
+    }
+
+    #[test]
+    fn it_reads_and_writes_all_operations() {
+        let notation = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - \
+                        am f3 g4; id \"Fool's mate\"; c0 \"Avoid weakening the king\"; \
+                        acd 12; hmvc 3; fmvn 7; ce 15; pv e4 e5";
+        let epd = parse_epd(notation).unwrap();
+
+        assert!(epd.best_moves.is_empty());
+        assert_eq!(
+            vec![
+                Move::simple(Cell::F2, Cell::F3),
+                Move::simple(Cell::G2, Cell::G4)
+            ],
+            epd.avoid_moves
+        );
+        assert_eq!(Some(String::from("Avoid weakening the king")), epd.comment);
+        assert_eq!(Some(12), epd.analysis_depth);
+        assert_eq!(
+            (3, 7),
+            (epd.state.halfmove_clock, epd.state.fullmove_number)
+        );
+        assert_eq!(Some(&[String::from("15")][..]), epd.operation("ce"));
+        assert!(epd.is_solved_by(&Move::simple(Cell::E2, Cell::E4)));
+
+        let written = to_epd(&epd);
+        assert_eq!(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - am f3 g4; \
+             id \"Fool's mate\"; c0 \"Avoid weakening the king\"; acd 12; hmvc 3; fmvn 7; \
+             ce 15; pv e4 e5;",
+            written
+        );
+        assert_eq!(epd, parse_epd(&written).unwrap());
+    }
+
+    #[test]
+    fn it_rejects_invalid_descriptions() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq";
+
+        assert!(matches!(
+            parse_epd(start),
+            Err(EpdError::InvalidNumberOfFields)
+        ));
+        assert!(matches!(
+            parse_epd(&format!("{start} - bm Nf6;")),
+            Err(EpdError::InvalidMove { .. })
+        ));
+        assert!(matches!(
+            parse_epd(&format!("{start} - id \"Unclosed;")),
+            Err(EpdError::InvalidOperation { .. })
+        ));
+        assert!(matches!(
+            parse_epd(&format!("{start} - acd deep;")),
+            Err(EpdError::InvalidOperation { .. })
+        ));
+    }
+}
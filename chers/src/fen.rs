@@ -1,8 +1,9 @@
 use std::{char, num::ParseIntError};
 
 use super::{
-    empty_board, empty_row, Board, CastlingRights, Coordinate, Figure, Piece, Player, Row, State,
-    BOARD_SIZE,
+    bitboard::Bitboard, check::checking_pieces_of_opponent, empty_board, empty_row, piece_at,
+    pieces, variants::CHECKS_TO_WIN, Board, CastleSide, CastlingOrigins, CastlingRights, Checks,
+    Color, Coordinate, Figure, Piece, Player, Pockets, Row, State, BOARD_SIZE,
 };
 
 #[derive(Debug)]
@@ -15,33 +16,224 @@ pub enum CouldNotParse {
     InvalidFullmoveNumber(ParseIntError),
     InvalidPiece(char),
     InvalidEnPassantTarget(String),
+    InvalidCastlingRights(String),
+    InvalidPocket(String),
+    InvalidChecks(String),
+    IllegalPosition(IllegalPosition),
+}
+
+/// Reasons why a syntactically valid description can never occur in a game.
+#[derive(Debug, PartialEq)]
+pub enum IllegalPosition {
+    MissingKing(Player),
+    TooManyKings(Player),
+    TooManyPawns(Player),
+    TooManyPieces(Player),
+    PawnOnBackRank(Coordinate),
+    OpponentInCheck,
+    CastlingWithoutKingOrRook(Player, CastleSide),
+    ImpossibleEnPassantTarget(Coordinate),
+    InvalidFullmoveNumber,
+}
+
+impl From<IllegalPosition> for CouldNotParse {
+    fn from(value: IllegalPosition) -> Self {
+        CouldNotParse::IllegalPosition(value)
+    }
 }
 
 pub fn parse_state(notation: &str) -> Result<State, CouldNotParse> {
-    let parts: Vec<&str> = notation.split(' ').collect();
-    if parts.len() != 6 {
-        return Err(CouldNotParse::InvalidNumberOfParts);
-    }
-
-    Ok(State {
-        board: parse_board(parts[0])?,
-        player: parse_player(parts[1])?,
-        castling_rights: parse_castling_rights(parts[2])?,
-        en_passant_target: parse_en_passant_target(parts[3])?,
-        halfmove_clock: parse_halfmove_clock(parts[4])?,
-        fullmove_number: parse_fullmove_number(parts[5])?,
+    let mut parts: Vec<&str> = notation.split(' ').collect();
+
+    // Three-check adds the checks as another part, either before the clocks
+    // counting the checks left, e.g. `3+2`, or after them counting the checks
+    // given, e.g. `+0+1`.
+    let checks = match parts.len() {
+        6 => Checks::default(),
+        7 if parts[4].contains('+') => parse_remaining_checks(parts.remove(4))?,
+        7 => parse_given_checks(parts.remove(6))?,
+        _ => return Err(CouldNotParse::InvalidNumberOfParts),
+    };
+
+    let state = parse_position(
+        [parts[0], parts[1], parts[2], parts[3]],
+        parse_halfmove_clock(parts[4])?,
+        parse_fullmove_number(parts[5])?,
+    )?;
+
+    Ok(state.with_checks(checks))
+}
+
+/// Parses the first four fields, which describe the position without its
+/// clocks. These are shared with EPD.
+pub(crate) fn parse_position(
+    parts: [&str; 4],
+    halfmove_clock: u16,
+    fullmove_number: u16,
+) -> Result<State, CouldNotParse> {
+    let (board_notation, pockets) = split_pockets(parts[0])?;
+    let (board, promoted) = parse_board_and_promotions(board_notation)?;
+    let player = parse_player(parts[1])?;
+
+    let state = State::new(
+        player,
+        board,
+        parse_castling_rights(parts[2], &board)?,
+        parse_en_passant_target(parts[3])?,
+        halfmove_clock,
+        fullmove_number,
+    )
+    .with_pockets(pockets)
+    .with_promoted(promoted);
+
+    Ok(state)
+}
+
+/// Like [parse_state], but additionally rejects positions that can not be
+/// reached in an actual game, e.g. because a king is missing.
+pub fn parse_valid_state(notation: &str) -> Result<State, CouldNotParse> {
+    let state = parse_state(notation)?;
+    validate(&state)?;
+
+    Ok(state)
+}
+
+/// Makes sure the [state] could actually occur in a game.
+///
+/// Only cheap invariants are checked, so passing the validation does not
+/// guarantee the position is reachable from the initial one. It does however
+/// guarantee it can safely be passed to [crate::move_piece].
+pub fn validate(state: &State) -> Result<(), IllegalPosition> {
+    for player in [Color::White, Color::Black] {
+        let owned: Vec<Piece> = pieces(&state.board)
+            .into_iter()
+            .map(|(_, piece)| piece)
+            .filter(|piece| player.owns(*piece))
+            .collect();
+        let count = |figure: Figure| owned.iter().filter(|p| p.figure == figure).count();
+
+        match count(Figure::King) {
+            0 => return Err(IllegalPosition::MissingKing(player)),
+            1 => {}
+            _ => return Err(IllegalPosition::TooManyKings(player)),
+        }
+
+        // Pieces dropped in Crazyhouse may exceed the counts of a standard
+        // game.
+        if !state.pockets.is_empty() {
+            continue;
+        }
+
+        if count(Figure::Pawn) > BOARD_SIZE {
+            return Err(IllegalPosition::TooManyPawns(player));
+        }
+
+        if owned.len() > 2 * BOARD_SIZE {
+            return Err(IllegalPosition::TooManyPieces(player));
+        }
+    }
+
+    for (coordinate, piece) in pieces(&state.board) {
+        let is_back_rank = coordinate.y == 0 || coordinate.y == BOARD_SIZE - 1;
+        if piece.figure == Figure::Pawn && is_back_rank {
+            return Err(IllegalPosition::PawnOnBackRank(coordinate));
+        }
+    }
+
+    if !checking_pieces_of_opponent(&state.reversed()).is_empty() {
+        return Err(IllegalPosition::OpponentInCheck);
+    }
+
+    for player in [Color::White, Color::Black] {
+        for side in [CastleSide::KingSide, CastleSide::QueenSide] {
+            if !state.castling_rights.allows(player, side) {
+                continue;
+            }
+
+            let king = state.castling_rights.king_origin(player);
+            let rook = state.castling_rights.rook_origin(player, side);
+            if piece_at(king, &state.board) != Some(Piece::new(player, Figure::King))
+                || piece_at(rook, &state.board) != Some(Piece::new(player, Figure::Rook))
+            {
+                return Err(IllegalPosition::CastlingWithoutKingOrRook(player, side));
+            }
+        }
+    }
+
+    if let Some(target) = state.en_passant_target {
+        if !is_possible_en_passant_target(state, target) {
+            return Err(IllegalPosition::ImpossibleEnPassantTarget(target));
+        }
+    }
+
+    if state.fullmove_number == 0 {
+        return Err(IllegalPosition::InvalidFullmoveNumber);
+    }
+
+    Ok(())
+}
+
+/// The opponent must just have moved a pawn two cells forward, passing the
+/// [target] cell.
+fn is_possible_en_passant_target(state: &State, target: Coordinate) -> bool {
+    let opponent = state.opponent();
+    let expected_row = opponent.back_rank().abs_diff(2);
+    if target.y != expected_row {
+        return false;
+    }
+
+    let (Some(origin), Some(pawn)) = (target.backward(opponent, 1), target.forward(opponent, 1))
+    else {
+        return false;
+    };
+
+    piece_at(pawn, &state.board) == Some(Piece::new(opponent, Figure::Pawn))
+        && piece_at(target, &state.board).is_none()
+        && piece_at(origin, &state.board).is_none()
+}
+
+fn parse_given_checks(notation: &str) -> Result<Checks, CouldNotParse> {
+    let invalid = || CouldNotParse::InvalidChecks(notation.to_owned());
+
+    let (white, black) = notation
+        .strip_prefix('+')
+        .and_then(|rest| rest.split_once('+'))
+        .ok_or_else(invalid)?;
+    let count = |given: &str| match given.parse::<u8>() {
+        Ok(given) if given <= CHECKS_TO_WIN => Ok(given),
+        _ => Err(invalid()),
+    };
+
+    Ok(Checks {
+        white: count(white)?,
+        black: count(black)?,
+    })
+}
+
+fn parse_remaining_checks(notation: &str) -> Result<Checks, CouldNotParse> {
+    let invalid = || CouldNotParse::InvalidChecks(notation.to_owned());
+
+    let (white, black) = notation.split_once('+').ok_or_else(invalid)?;
+    let count = |remaining: &str| match remaining.parse::<u8>() {
+        Ok(remaining) if remaining <= CHECKS_TO_WIN => Ok(CHECKS_TO_WIN - remaining),
+        _ => Err(invalid()),
+    };
+
+    Ok(Checks {
+        white: count(white)?,
+        black: count(black)?,
     })
 }
 
-fn parse_halfmove_clock(notation: &str) -> Result<u8, CouldNotParse> {
-    match notation.parse::<u8>() {
+pub(crate) fn parse_halfmove_clock(notation: &str) -> Result<u16, CouldNotParse> {
+    match notation.parse::<u16>() {
         Ok(value) => Ok(value),
         Err(err) => Err(CouldNotParse::InvalidHalfmoveClock(err)),
     }
 }
 
-fn parse_fullmove_number(notation: &str) -> Result<u8, CouldNotParse> {
-    match notation.parse::<u8>() {
+pub(crate) fn parse_fullmove_number(notation: &str) -> Result<u16, CouldNotParse> {
+    match notation.parse::<u16>() {
         Ok(value) => Ok(value),
         Err(err) => Err(CouldNotParse::InvalidFullmoveNumber(err)),
     }
@@ -58,25 +250,80 @@ fn parse_en_passant_target(notation: &str) -> Result<Option<Coordinate>, CouldNo
     }
 }
 
+/// Splits the pockets of Crazyhouse off the board, which are either appended
+/// in brackets, e.g. `.../RNBQKBNR[Qp]`, or given as a ninth row.
+fn split_pockets(notation: &str) -> Result<(&str, Pockets), CouldNotParse> {
+    let (board, pockets) = match notation.strip_suffix(']') {
+        Some(rest) => match rest.split_once('[') {
+            Some(parts) => parts,
+            None => return Err(CouldNotParse::InvalidPocket(notation.to_owned())),
+        },
+        None if notation.matches('/').count() == BOARD_SIZE => {
+            notation.rsplit_once('/').expect("there are separators")
+        }
+        None => return Ok((notation, Pockets::default())),
+    };
+
+    Ok((board, parse_pockets(pockets)?))
+}
+
+fn parse_pockets(notation: &str) -> Result<Pockets, CouldNotParse> {
+    let mut pockets = Pockets::default();
+    if notation == "-" {
+        return Ok(pockets);
+    }
+
+    for character in notation.chars() {
+        let piece = match parse_piece(character) {
+            Ok(piece) if piece.figure != Figure::King => piece,
+            _ => return Err(CouldNotParse::InvalidPocket(notation.to_owned())),
+        };
+
+        pockets.of_mut(piece.color).add(piece.figure);
+    }
+
+    Ok(pockets)
+}
+
 pub fn parse_board(notation: &str) -> Result<Board, CouldNotParse> {
+    parse_board_and_promotions(notation).map(|(board, _)| board)
+}
+
+/// Like [parse_board], but also returns the cells of the pieces marked as
+/// promoted by a trailing `~`, which matters in Crazyhouse.
+fn parse_board_and_promotions(notation: &str) -> Result<(Board, Bitboard), CouldNotParse> {
     let rows: Vec<&str> = notation.split('/').collect();
     if rows.len() != BOARD_SIZE {
         return Err(CouldNotParse::InvalidNumberOfRows);
     }
 
     let mut board: Board = empty_board();
+    let mut promoted = Bitboard::EMPTY;
     for (index, row) in rows.into_iter().enumerate() {
-        board[index] = parse_row(row)?;
+        let (pieces, promoted_in_row) = parse_row(row, index)?;
+        board[index] = pieces;
+        promoted |= promoted_in_row;
     }
 
-    Ok(board)
+    Ok((board, promoted))
 }
 
-fn parse_row(row: &str) -> Result<Row, CouldNotParse> {
+fn parse_row(row: &str, y: usize) -> Result<(Row, Bitboard), CouldNotParse> {
     let mut pieces: Row = empty_row();
-    let mut index = 0;
+    let mut promoted = Bitboard::EMPTY;
+    let mut index: usize = 0;
 
     for character in row.chars() {
+        if character == '~' {
+            match index.checked_sub(1).and_then(|x| pieces[x].map(|_| x)) {
+                Some(x) if !promoted.contains(Coordinate::new(x, y)) => {
+                    promoted |= Coordinate::new(x, y).into();
+                }
+                _ => return Err(CouldNotParse::InvalidPiece(character)),
+            }
+            continue;
+        }
+
         if index == BOARD_SIZE {
             return Err(CouldNotParse::InvalidNumberOfColumns(row.to_owned()));
         }
@@ -100,10 +347,12 @@ fn parse_row(row: &str) -> Result<Row, CouldNotParse> {
         return Err(CouldNotParse::InvalidNumberOfColumns(String::from(row)));
     }
 
-    Ok(pieces)
+    Ok((pieces, promoted))
 }
 
-fn parse_piece(character: char) -> Result<Piece, CouldNotParse> {
+/// Parses the letter of a piece of standard chess. The figures of Capablanca
+/// chess are only read by [crate::variants::CapablancaState::parse].
+pub(crate) fn parse_piece(character: char) -> Result<Piece, CouldNotParse> {
     let figure = match character.to_lowercase().to_string().as_str() {
         "r" => Figure::Rook,
         "n" => Figure::Knight,
@@ -128,7 +377,7 @@ fn owner(character: char) -> Player {
     Player::Black
 }
 
-fn parse_player(notation: &str) -> Result<Player, CouldNotParse> {
+pub(crate) fn parse_player(notation: &str) -> Result<Player, CouldNotParse> {
     match notation {
         "b" => Ok(Player::Black),
         "w" => Ok(Player::White),
@@ -136,14 +385,259 @@ fn parse_player(notation: &str) -> Result<Player, CouldNotParse> {
     }
 }
 
-fn parse_castling_rights(_notation: &str) -> Result<CastlingRights, CouldNotParse> {
-    // TODO
-    Ok(CastlingRights::all())
+/// Parses the castling rights, which also tell where king and rooks started
+/// in Chess960.
+///
+/// Besides `KQkq`, the files of the rooks are accepted, e.g. `HAha`. This
+/// covers Shredder-FEN, which always uses files, as well as X-FEN, which only
+/// uses them if `K` or `Q` would be ambiguous, since those refer to the
+/// outermost rook on the respective side of the king.
+fn parse_castling_rights(notation: &str, board: &Board) -> Result<CastlingRights, CouldNotParse> {
+    let mut rights = CastlingRights::none();
+    if notation == "-" {
+        return Ok(rights);
+    }
+
+    if notation.is_empty() {
+        return Err(CouldNotParse::InvalidCastlingRights(notation.to_owned()));
+    }
+
+    let mut origins = CastlingOrigins::STANDARD;
+    for character in notation.chars() {
+        let player = owner(character);
+        let row = &board[player.back_rank()];
+        let is_rook = |x: &usize| row[*x] == Some(Piece::new(player, Figure::Rook));
+        // Without a king on the back rank the rights are invalid anyway, which
+        // is up to the validation to report.
+        let king = row
+            .iter()
+            .position(|cell| *cell == Some(Piece::new(player, Figure::King)));
+
+        let (side, rook) = match character.to_ascii_lowercase() {
+            'k' => (
+                CastleSide::KingSide,
+                king.and_then(|king| (king + 1..BOARD_SIZE).rev().find(is_rook)),
+            ),
+            'q' => (
+                CastleSide::QueenSide,
+                king.and_then(|king| (0..king).find(is_rook)),
+            ),
+            file @ 'a'..='h' => {
+                let x = file as usize - 'a' as usize;
+                match king {
+                    Some(king) if x < king => (CastleSide::QueenSide, Some(x)),
+                    _ => (CastleSide::KingSide, Some(x)),
+                }
+            }
+            _ => return Err(CouldNotParse::InvalidCastlingRights(notation.to_owned())),
+        };
+
+        if let Some(king) = king {
+            origins.king = king;
+        }
+        match (side, rook) {
+            (CastleSide::KingSide, Some(x)) => origins.king_side_rook = x,
+            (CastleSide::QueenSide, Some(x)) => origins.queen_side_rook = x,
+            (_, None) => {}
+        }
+
+        rights.grant(player, side);
+    }
+
+    Ok(rights.with_origins(origins))
+}
+
+/// Describes the [state] in Forsyth–Edwards Notation.
+///
+/// Castling rights of Chess960 positions are described using X-FEN, which
+/// matches plain FEN for standard chess. The result can be turned back into
+/// an equal [State] using [parse_state].
+pub fn to_fen(state: &State) -> String {
+    format!(
+        "{} {} {}{}",
+        serialize_position(state, false),
+        state.halfmove_clock,
+        state.fullmove_number,
+        serialize_checks(state.checks),
+    )
+}
+
+/// Like [to_fen], but describes castling rights using Shredder-FEN, i.e. by
+/// the files of the rooks, e.g. `HAha` instead of `KQkq`.
+pub fn to_shredder_fen(state: &State) -> String {
+    format!(
+        "{} {} {}{}",
+        serialize_position(state, true),
+        state.halfmove_clock,
+        state.fullmove_number,
+        serialize_checks(state.checks),
+    )
+}
+
+/// Describes the position without its clocks, see [parse_position].
+pub(crate) fn serialize_position(state: &State, shredder: bool) -> String {
+    format!(
+        "{}{} {} {} {}",
+        serialize_board_and_promotions(&state.board, state.promoted),
+        serialize_pockets(state.pockets),
+        serialize_player(state.player),
+        serialize_castling_rights(state.castling_rights, &state.board, shredder),
+        serialize_en_passant_target(state.en_passant_target),
+    )
+}
+
+/// The checks given in Three-check, which are only written once there are
+/// any, so other positions keep their usual notation.
+fn serialize_checks(checks: Checks) -> String {
+    match checks.is_empty() {
+        true => String::new(),
+        false => format!(" +{}+{}", checks.white, checks.black),
+    }
+}
+
+pub fn serialize_board(board: &Board) -> String {
+    serialize_board_and_promotions(board, Bitboard::EMPTY)
+}
+
+/// Like [serialize_board], but marks the pieces on [promoted] with a `~`.
+fn serialize_board_and_promotions(board: &Board, promoted: Bitboard) -> String {
+    board
+        .iter()
+        .enumerate()
+        .map(|(y, row)| serialize_row(row, y, promoted))
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+fn serialize_row(row: &Row, y: usize, promoted: Bitboard) -> String {
+    let mut notation = String::new();
+    let mut empty_cells = 0;
+
+    for (x, cell) in row.iter().enumerate() {
+        let Some(piece) = cell else {
+            empty_cells += 1;
+            continue;
+        };
+
+        if empty_cells > 0 {
+            notation += &empty_cells.to_string();
+            empty_cells = 0;
+        }
+
+        notation.push(serialize_piece(*piece));
+        if promoted.contains(Coordinate::new(x, y)) {
+            notation.push('~');
+        }
+    }
+
+    if empty_cells > 0 {
+        notation += &empty_cells.to_string();
+    }
+
+    notation
+}
+
+pub(crate) fn serialize_piece(piece: Piece) -> char {
+    let character = match piece.figure {
+        Figure::Rook => 'r',
+        Figure::Knight => 'n',
+        Figure::Bishop => 'b',
+        Figure::Queen => 'q',
+        Figure::King => 'k',
+        Figure::Pawn => 'p',
+        Figure::Archbishop => 'a',
+        Figure::Chancellor => 'c',
+    };
+
+    match piece.color {
+        Color::White => character.to_ascii_uppercase(),
+        Color::Black => character,
+    }
+}
+
+/// Describes the pockets in brackets, e.g. `[Qp]`, which is omitted if both
+/// are empty, as they are outside of Crazyhouse.
+fn serialize_pockets(pockets: Pockets) -> String {
+    if pockets.is_empty() {
+        return String::new();
+    }
+
+    let mut notation = String::from("[");
+    for color in [Color::White, Color::Black] {
+        let pocket = pockets.of(color);
+        for figure in pocket.figures() {
+            let piece = serialize_piece(Piece::new(color, figure));
+            notation.extend(std::iter::repeat_n(piece, pocket.count(figure) as usize));
+        }
+    }
+    notation.push(']');
+
+    notation
+}
+
+pub(crate) fn serialize_player(player: Player) -> &'static str {
+    match player {
+        Color::White => "w",
+        Color::Black => "b",
+    }
+}
+
+fn serialize_castling_rights(rights: CastlingRights, board: &Board, shredder: bool) -> String {
+    let mut notation = String::new();
+
+    for (character, player, side) in [
+        ('K', Player::White, CastleSide::KingSide),
+        ('Q', Player::White, CastleSide::QueenSide),
+        ('k', Player::Black, CastleSide::KingSide),
+        ('q', Player::Black, CastleSide::QueenSide),
+    ] {
+        if !rights.allows(player, side) {
+            continue;
+        }
+
+        let rook = rights.rook_origin(player, side);
+        if !shredder && is_outermost_rook(board, rook, side, player) {
+            notation.push(character);
+            continue;
+        }
+
+        let file = (b'a' + rook.x as u8) as char;
+        notation.push(match player {
+            Color::White => file.to_ascii_uppercase(),
+            Color::Black => file,
+        });
+    }
+
+    if notation.is_empty() {
+        return String::from("-");
+    }
+
+    notation
+}
+
+/// Whether no other rook of [player] resides between [rook] and the edge of
+/// the board on [side], in which case X-FEN refers to it as `K` or `Q`.
+fn is_outermost_rook(board: &Board, rook: Coordinate, side: CastleSide, player: Player) -> bool {
+    let outer = match side {
+        CastleSide::KingSide => rook.x + 1..BOARD_SIZE,
+        CastleSide::QueenSide => 0..rook.x,
+    };
+
+    outer
+        .into_iter()
+        .all(|x| board[rook.y][x] != Some(Piece::new(player, Figure::Rook)))
+}
+
+fn serialize_en_passant_target(target: Option<Coordinate>) -> String {
+    match target {
+        Some(coordinate) => coordinate.to_string(),
+        None => String::from("-"),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Game;
+    use crate::{Game, INITIAL_BOARD};
 
     use super::*;
 
@@ -154,4 +648,147 @@ mod tests {
 
         assert_eq!(Game::new().start(), parsed);
     }
+
+    #[test]
+    fn it_parses_castling_rights() {
+        let rights = parse_castling_rights("Kq", &INITIAL_BOARD).unwrap();
+
+        assert!(rights.allows(Player::White, CastleSide::KingSide));
+        assert!(!rights.allows(Player::White, CastleSide::QueenSide));
+        assert!(!rights.allows(Player::Black, CastleSide::KingSide));
+        assert!(rights.allows(Player::Black, CastleSide::QueenSide));
+
+        assert_eq!(
+            CastlingRights::none(),
+            parse_castling_rights("-", &INITIAL_BOARD).unwrap()
+        );
+        assert!(parse_castling_rights("KX", &INITIAL_BOARD).is_err());
+    }
+
+    #[test]
+    fn it_serializes_states_that_can_be_parsed_again() {
+        assert_eq!(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            to_fen(&Game::new().start())
+        );
+
+        for notation in [
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k3/8/8/8/8/8/8/4K2R b Kq - 12 34",
+            "8/8/4k3/8/8/3K4/8/8 w - - 140 300",
+        ] {
+            let state = parse_state(notation).unwrap();
+            assert_eq!(notation, to_fen(&state));
+            assert_eq!(state, parse_state(&to_fen(&state)).unwrap());
+        }
+    }
+
+    #[test]
+    fn it_reads_and_writes_pockets_and_promoted_pieces() {
+        let notation = "r1bqk2r/pppp1ppp/2n2n2/4p3/1b2P3/2NP1N2/PPP2PPP/R1BQKB~1R[Pp] b KQkq - 0 5";
+        let state = parse_state(notation).unwrap();
+        assert_eq!(1, state.pockets.white.count(Figure::Pawn));
+        assert_eq!(1, state.pockets.black.count(Figure::Pawn));
+        assert!(state.promoted.contains(Coordinate::new(5, 7)));
+        assert_eq!(notation, to_fen(&state));
+
+        // Pockets may also be given as a ninth row
+        let ninth_row = notation.replace("1R[Pp]", "1R/Pp");
+        assert_eq!(state, parse_state(&ninth_row).unwrap());
+        let empty = parse_state(&notation.replace("Pp", "-")).unwrap();
+        assert!(empty.pockets.is_empty());
+
+        assert!(matches!(
+            parse_state("4k3/8/8/8/8/8/8/4K3[K] w - - 0 1"),
+            Err(CouldNotParse::InvalidPocket(_))
+        ));
+    }
+
+    #[test]
+    fn it_reads_and_writes_checks() {
+        let state = parse_state("4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +2+1").unwrap();
+        assert_eq!(Checks { white: 2, black: 1 }, state.checks);
+        assert_eq!("4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +2+1", to_fen(&state));
+
+        // The checks left may also be given before the clocks
+        assert_eq!(
+            state,
+            parse_state("4k3/8/8/8/8/8/8/R3K3 w - - 1+2 0 1").unwrap()
+        );
+
+        assert!(matches!(
+            parse_state("4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +4+0"),
+            Err(CouldNotParse::InvalidChecks(_))
+        ));
+    }
+
+    #[test]
+    fn it_rejects_the_figures_of_capablanca_chess() {
+        for (notation, letter) in [
+            ("4k3/8/8/8/8/8/8/A3K3 w - - 0 1", 'A'),
+            ("4k3/8/8/8/8/8/8/c3K3 w - - 0 1", 'c'),
+        ] {
+            assert!(matches!(
+                parse_state(notation),
+                Err(CouldNotParse::InvalidPiece(invalid)) if invalid == letter
+            ));
+        }
+
+        assert!(matches!(
+            parse_state("4k3/8/8/8/8/8/8/4K3[C] w - - 0 1"),
+            Err(CouldNotParse::InvalidPocket(_))
+        ));
+    }
+
+    fn illegal(notation: &str) -> IllegalPosition {
+        match parse_valid_state(notation) {
+            Err(CouldNotParse::IllegalPosition(reason)) => reason,
+            other => panic!("{notation} should be illegal, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn it_accepts_legal_positions() {
+        for notation in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2",
+            "8/8/8/8/8/rrk5/8/K7 w - - 0 1",
+        ] {
+            assert!(parse_valid_state(notation).is_ok(), "{notation}");
+        }
+    }
+
+    #[test]
+    fn it_rejects_illegal_positions() {
+        assert_eq!(
+            IllegalPosition::MissingKing(Player::White),
+            illegal("4k3/8/8/8/8/8/8/8 w - - 0 1")
+        );
+        assert_eq!(
+            IllegalPosition::TooManyKings(Player::Black),
+            illegal("4k2k/8/8/8/8/8/8/4K3 w - - 0 1")
+        );
+        assert_eq!(
+            IllegalPosition::PawnOnBackRank(Coordinate::new(0, 7)),
+            illegal("4k3/8/8/8/8/8/8/P3K3 w - - 0 1")
+        );
+        assert_eq!(
+            IllegalPosition::OpponentInCheck,
+            illegal("4k3/8/8/8/8/8/8/4R1K1 w - - 0 1")
+        );
+        assert_eq!(
+            IllegalPosition::CastlingWithoutKingOrRook(Player::White, CastleSide::QueenSide),
+            illegal("4k3/8/8/8/8/8/8/4K2R w KQ - 0 1")
+        );
+        assert_eq!(
+            IllegalPosition::ImpossibleEnPassantTarget(Coordinate::new(3, 2)),
+            illegal("4k3/8/8/4p3/8/8/8/4K3 w - d6 0 1")
+        );
+        assert_eq!(
+            IllegalPosition::InvalidFullmoveNumber,
+            illegal("4k3/8/8/8/8/8/8/4K3 w - - 0 0")
+        );
+    }
 }
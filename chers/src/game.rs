@@ -2,14 +2,14 @@ use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 
 use crate::{
-    move_execution::{move_piece, CantMovePiece},
-    PromotedFigure,
+    chess960,
+    move_execution::{move_piece_by, CantMovePiece},
+    moves_available::autocomplete_to_by,
+    variants::{Variant, VariantKind},
+    History, PromotedFigure,
 };
 
-use super::{
-    moves_available::autocomplete_to, CastlingRights, Color::White, Coordinate, Move, Piece, State,
-    INITIAL_BOARD,
-};
+use super::{CastleSide, Coordinate, Figure, Move, Piece, Player, State};
 
 #[derive(Tsify, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Event {
@@ -23,36 +23,158 @@ pub enum Event {
         captured: Piece,
         by: Piece,
     },
+    /// A piece from the pocket was put onto the board in Crazyhouse.
+    Drop {
+        piece: Piece,
+        at: Coordinate,
+    },
     Promotion {
         to: PromotedFigure,
     },
+    Castle {
+        side: CastleSide,
+        rook_from: Coordinate,
+        rook_to: Coordinate,
+    },
     Check {
         by: Vec<(Coordinate, Piece)>,
     },
+    /// A capture in Atomic chess blew up the capturing piece and every piece
+    /// but pawns next to the cell [Event::Explosion::at].
+    Explosion {
+        at: Coordinate,
+        removed: Vec<(Coordinate, Piece)>,
+    },
     Mate,
+    /// The game was won by a rule of the variant other than mating.
+    Win {
+        winner: Player,
+        reason: WinReason,
+    },
+    /// The player to move is not in check, but has no legal moves left, so
+    /// the game ends in a draw.
+    Stalemate,
+    /// The game ended in a draw, without any of the players claiming it.
+    Draw {
+        reason: DrawReason,
+    },
+    /// The player to move may claim a draw instead of moving.
+    DrawClaimable {
+        reason: DrawReason,
+    },
+}
+
+#[derive(Tsify, Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum WinReason {
+    /// The third check was given in Three-check.
+    ThreeChecks,
+    /// The king reached one of the four center cells in King of the Hill.
+    KingOfTheHill,
+    /// The winner lost all of their pieces in Antichess.
+    NoPiecesLeft,
+    /// The winner has no legal moves left in Antichess.
+    Stalemated,
+}
+
+#[derive(Tsify, Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum DrawReason {
+    /// No capture or pawn move happened in the last fifty moves.
+    FiftyMoveRule,
+    /// No capture or pawn move happened in the last seventy-five moves.
+    SeventyFiveMoveRule,
+    /// The same position occurred three times.
+    ThreefoldRepetition,
+    /// The same position occurred five times.
+    FivefoldRepetition,
+    /// Neither player has enough pieces left to checkmate the other.
+    InsufficientMaterial,
 }
 
 #[derive(Default, Debug, Clone, Copy, Serialize, Deserialize)]
-pub struct Game {}
+pub struct Game {
+    /// The rules the game is played by.
+    #[serde(default)]
+    variant: VariantKind,
+    /// The index of the Chess960 start position, see [chess960::position].
+    #[serde(default)]
+    chess960: Option<u16>,
+}
 
 impl Game {
     pub fn new() -> Self {
-        Self {}
+        Self::of_variant(VariantKind::Standard)
+    }
+
+    /// A game played by the rules of [variant].
+    pub fn of_variant(variant: VariantKind) -> Self {
+        Self {
+            variant,
+            chess960: None,
+        }
+    }
+
+    /// A game of Chess960 starting from the position with the given [index],
+    /// if there is such a position.
+    pub fn chess960(index: u16) -> Option<Self> {
+        (index < chess960::POSITIONS).then_some(Self {
+            variant: VariantKind::Standard,
+            chess960: Some(index),
+        })
+    }
+
+    /// The variant in use.
+    pub fn variant(&self) -> VariantKind {
+        self.variant
     }
 
+    /// The rules of the variant in use.
+    pub fn rules(&self) -> &'static dyn Variant {
+        self.variant.rules()
+    }
+
+    /// The index of the Chess960 start position, if this is such a game.
+    pub fn chess960_position(&self) -> Option<u16> {
+        self.chess960
+    }
+
+    /// The position the game starts from, which is either the Chess960 one or
+    /// the one of the variant.
     pub fn start(&self) -> State {
-        State {
-            player: White,
-            board: INITIAL_BOARD,
-            castling_rights: CastlingRights::all(),
-            en_passant_target: None,
-            halfmove_clock: 0,
-            fullmove_number: 1,
+        match self.chess960.and_then(chess960::position) {
+            Some(state) => state,
+            None => self.rules().start(),
         }
     }
 
     pub fn available_moves(&self, state: &State, from: Coordinate) -> Vec<Coordinate> {
-        autocomplete_to(state, from)
+        autocomplete_to_by(self.rules(), state, from)
+    }
+
+    /// The cells a piece of [figure] may be dropped onto from the pocket of
+    /// the current player, see [Variant::legal_drops].
+    pub fn available_drops(&self, state: &State, figure: Figure) -> Vec<Coordinate> {
+        self.rules()
+            .legal_drops(state, figure)
+            .into_iter()
+            .collect()
+    }
+
+    /// Returns every legal move of the current player.
+    pub fn legal_moves(&self, state: &State) -> Vec<Move> {
+        self.rules().legal_moves(state)
+    }
+
+    /// Returns the reason the current player may claim a draw with, if any.
+    pub fn claimable_draw(&self, history: &History) -> Option<DrawReason> {
+        if history.current().can_claim_fifty_move_draw() {
+            return Some(DrawReason::FiftyMoveRule);
+        }
+
+        if history.is_threefold_repetition() {
+            return Some(DrawReason::ThreefoldRepetition);
+        }
+
+        None
     }
 
     pub fn move_piece(
@@ -60,6 +182,44 @@ impl Game {
         state: &State,
         r#move: Move,
     ) -> Result<(State, Vec<Event>), CantMovePiece> {
-        move_piece(state, r#move)
+        move_piece_by(self.rules(), state, r#move)
+    }
+
+    /// Executes [r#move] in the current position of the [history] and
+    /// records the resulting one.
+    ///
+    /// In addition to the events of [Game::move_piece], this also reports
+    /// draws by repetition, which require knowledge about earlier positions.
+    pub fn play(&self, history: &mut History, r#move: Move) -> Result<Vec<Event>, CantMovePiece> {
+        let (new_state, mut events) = self.move_piece(history.current(), r#move)?;
+        history.push(r#move, new_state);
+
+        let game_ended = events.iter().any(|event| {
+            matches!(
+                event,
+                Event::Mate
+                    | Event::Win {
+                        winner: _,
+                        reason: _
+                    }
+                    | Event::Stalemate
+                    | Event::Draw { reason: _ }
+            )
+        });
+        if game_ended {
+            return Ok(events);
+        }
+
+        if history.is_fivefold_repetition() {
+            events.push(Event::Draw {
+                reason: DrawReason::FivefoldRepetition,
+            });
+        } else if history.is_threefold_repetition() {
+            events.push(Event::DrawClaimable {
+                reason: DrawReason::ThreefoldRepetition,
+            });
+        }
+
+        Ok(events)
     }
 }
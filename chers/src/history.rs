@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{moves_available::autocomplete_to, piece_at, Coordinate, Figure, Move, Piece, State};
+
+/// After a position occurred this many times, the player to move may claim a
+/// draw.
+pub const THREEFOLD_REPETITION: usize = 3;
+
+/// After a position occurred this many times, the game is drawn automatically.
+pub const FIVEFOLD_REPETITION: usize = 5;
+
+/// All positions that occurred during a game, together with the moves that
+/// lead to them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct History {
+    positions: Vec<State>,
+    moves: Vec<Move>,
+}
+
+impl History {
+    pub fn new(initial: State) -> Self {
+        Self {
+            positions: vec![initial],
+            moves: Vec::new(),
+        }
+    }
+
+    /// The position the game is currently in.
+    pub fn current(&self) -> &State {
+        // There is always at least the initial position
+        self.positions.last().unwrap()
+    }
+
+    /// All positions, starting with the initial one.
+    pub fn positions(&self) -> &[State] {
+        &self.positions
+    }
+
+    /// All moves, where the move at index `i` lead from position `i` to `i + 1`.
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// Records that [the_move] lead to [state].
+    pub fn push(&mut self, the_move: Move, state: State) {
+        self.moves.push(the_move);
+        self.positions.push(state);
+    }
+
+    /// Counts how often the current position occurred, including itself.
+    pub fn repetitions(&self) -> usize {
+        let current = self.current();
+
+        // Captures and pawn moves can not be undone, so no position before
+        // the last one of them can be the same as the current one.
+        let considered = (current.halfmove_clock as usize + 1).min(self.positions.len());
+
+        self.positions
+            .iter()
+            .rev()
+            .take(considered)
+            .filter(|position| is_same_position(position, current))
+            .count()
+    }
+
+    pub fn is_threefold_repetition(&self) -> bool {
+        self.repetitions() >= THREEFOLD_REPETITION
+    }
+
+    pub fn is_fivefold_repetition(&self) -> bool {
+        self.repetitions() >= FIVEFOLD_REPETITION
+    }
+}
+
+/// Two positions are considered the same, if the same player is to move, all
+/// pieces reside on the same cells and both players have the same moves
+/// available to them.
+///
+/// That is the case if castling rights match and there either is no en
+/// passant capture possible or it is possible in both positions. The move
+/// counters on the other hand are not relevant.
+fn is_same_position(a: &State, b: &State) -> bool {
+    // The keys differ for unusable en passant targets, so they can only be
+    // used to tell positions apart quickly if there is none.
+    if a.en_passant_target.is_none()
+        && b.en_passant_target.is_none()
+        && a.zobrist_key() != b.zobrist_key()
+    {
+        return false;
+    }
+
+    a.player == b.player
+        && a.board == b.board
+        && a.castling_rights == b.castling_rights
+        && en_passant_capture(a) == en_passant_capture(b)
+}
+
+/// Returns the en passant target, if the current player could legally use it.
+fn en_passant_capture(state: &State) -> Option<Coordinate> {
+    let target = state.en_passant_target?;
+    let passed_pawn = target.forward(state.opponent(), 1)?;
+
+    let can_capture = [passed_pawn.left(1), passed_pawn.right(1)]
+        .into_iter()
+        .flatten()
+        .any(|from| {
+            piece_at(from, &state.board) == Some(Piece::new(state.player, Figure::Pawn))
+                && autocomplete_to(state, from).contains(&target)
+        });
+
+    match can_capture {
+        true => Some(target),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fen::parse_state, Cell, Game};
+
+    use super::*;
+
+    fn play(history: &mut History, moves: &[(Coordinate, Coordinate)]) {
+        for (from, to) in moves {
+            Game::new().play(history, Move::simple(*from, *to)).unwrap();
+        }
+    }
+
+    #[test]
+    fn it_counts_repetitions_of_the_current_position() {
+        let mut history = History::new(Game::new().start());
+        let knights_back_and_forth = [
+            (Cell::G1, Cell::F3),
+            (Cell::G8, Cell::F6),
+            (Cell::F3, Cell::G1),
+            (Cell::F6, Cell::G8),
+        ];
+
+        assert_eq!(1, history.repetitions());
+
+        play(&mut history, &knights_back_and_forth);
+        assert_eq!(2, history.repetitions());
+        assert!(!history.is_threefold_repetition());
+
+        play(&mut history, &knights_back_and_forth);
+        assert!(history.is_threefold_repetition());
+        assert!(!history.is_fivefold_repetition());
+
+        play(&mut history, &knights_back_and_forth);
+        play(&mut history, &knights_back_and_forth);
+        assert!(history.is_fivefold_repetition());
+        assert_eq!(17, history.positions().len());
+        assert_eq!(16, history.moves().len());
+    }
+
+    #[test]
+    fn lost_castling_rights_make_positions_differ() {
+        let state = parse_state("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        let mut history = History::new(state);
+
+        play(
+            &mut history,
+            &[
+                (Cell::E1, Cell::E2),
+                (Cell::E8, Cell::E7),
+                (Cell::E2, Cell::E1),
+                (Cell::E7, Cell::E8),
+            ],
+        );
+
+        assert_eq!(1, history.repetitions());
+    }
+
+    #[test]
+    fn unusable_en_passant_targets_are_ignored() {
+        // Nothing can capture the pawn on e4, so the target is irrelevant
+        let with_target = parse_state("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").unwrap();
+        let without_target = parse_state("4k3/8/8/8/4P3/8/8/4K3 b - - 0 1").unwrap();
+        assert!(is_same_position(&with_target, &without_target));
+
+        // The pawn on d4 could capture the one on e4
+        let with_target = parse_state("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1").unwrap();
+        let without_target = parse_state("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1").unwrap();
+        assert!(!is_same_position(&with_target, &without_target));
+    }
+}
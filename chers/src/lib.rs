@@ -4,6 +4,12 @@ mod structs;
 /// Responsible for the game loop and higher-level processes.
 mod game;
 
+/// Keeps track of earlier positions, e.g. to detect repetitions.
+mod history;
+
+/// Sets of cells stored as bits, used to compute moves and attacks quickly.
+mod bitboard;
+
 /// Low-level coordinate movements
 mod coordinates;
 
@@ -19,18 +25,42 @@ mod move_execution;
 /// Computes whether a given state represents check or even mate.
 mod check;
 
+/// Computes whether the remaining pieces still allow to checkmate.
+mod material;
+
+/// Start positions of Chess960, also known as Fischer Random Chess.
+pub mod chess960;
+
 /// Autonomusly generate moves and play the game.
 pub mod engine;
 
-/// Parses a description in Forsyth–Edwards Notation.
+/// Parses and serializes positions in Extended Position Description, which
+/// test suites are distributed in.
+pub mod epd;
+
+/// Parses and serializes descriptions in Forsyth–Edwards Notation.
 pub mod fen;
 
 pub mod moves;
 
+/// Counts reachable positions to verify move generation.
+pub mod perft;
+
+/// Reads and writes games in Portable Game Notation.
+pub mod pgn;
+
+/// Rules of chess variants, which a [Game] may be played by.
+pub mod variants;
+
+/// Hashes positions into 64 bit keys that can be updated incrementally.
+pub mod zobrist;
+
 /// WASM bindings
 pub mod wasm;
 
 pub use coordinates::*;
 pub use game::*;
+pub use history::*;
 pub use move_execution::*;
+pub use moves_available::legal_moves;
 pub use structs::*;
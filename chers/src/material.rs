@@ -0,0 +1,83 @@
+use crate::{pieces, Board, Coordinate, Figure};
+
+/// Computes whether neither player could ever checkmate the other, no matter
+/// how badly their opponent plays.
+///
+/// This is the case if only kings remain, if there is just a single knight or
+/// bishop left, or if all remaining bishops reside on cells of the same color.
+pub fn is_insufficient_material(board: &Board) -> bool {
+    let mut minor_pieces = 0;
+    let mut bishop_cell_colors = Vec::new();
+
+    for (coordinate, piece) in pieces(board) {
+        match piece.figure {
+            Figure::King => {}
+            Figure::Queen | Figure::Rook | Figure::Pawn => return false,
+            Figure::Archbishop | Figure::Chancellor => {
+                unreachable!("Only played on a CapablancaState")
+            }
+            Figure::Knight => minor_pieces += 1,
+            Figure::Bishop => {
+                minor_pieces += 1;
+                bishop_cell_colors.push(is_light_cell(coordinate));
+            }
+        }
+    }
+
+    if minor_pieces <= 1 {
+        return true;
+    }
+
+    // Bishops confined to cells of the same color can never attack a king on
+    // the other color. As soon as a knight is involved, a mate becomes
+    // possible again.
+    let only_bishops = bishop_cell_colors.len() == minor_pieces;
+    let same_color = bishop_cell_colors.windows(2).all(|pair| pair[0] == pair[1]);
+
+    only_bishops && same_color
+}
+
+fn is_light_cell(coordinate: Coordinate) -> bool {
+    (coordinate.x + coordinate.y).is_multiple_of(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fen::parse_board, Game};
+
+    use super::*;
+
+    #[test]
+    fn it_detects_insufficient_material() {
+        for board in [
+            "4k3/8/8/8/8/8/8/4K3",      // K vs K
+            "4k3/8/8/8/8/8/8/2B1K3",    // K+B vs K
+            "4k3/8/8/8/8/8/8/1N2K3",    // K+N vs K
+            "2b1k3/8/8/8/8/8/8/4KB2",   // K+B vs K+B, both on light cells
+            "4k3/8/8/8/8/8/8/B1B1K1B1", // Many bishops, all on dark cells
+        ] {
+            assert!(
+                is_insufficient_material(&parse_board(board).unwrap()),
+                "{board} should be a dead position"
+            );
+        }
+    }
+
+    #[test]
+    fn it_detects_sufficient_material() {
+        for board in [
+            "4k3/8/8/8/8/8/8/4K2R",   // K+R vs K
+            "4k3/8/8/8/8/8/4P3/4K3",  // K+P vs K
+            "3bk3/8/8/8/8/8/8/4KB2",  // K+B vs K+B, on differently colored cells
+            "4k3/8/8/8/8/8/8/1NB1K3", // K+B+N vs K
+            "4kn2/8/8/8/8/8/8/1N2K3", // K+N vs K+N
+        ] {
+            assert!(
+                !is_insufficient_material(&parse_board(board).unwrap()),
+                "{board} should not be a dead position"
+            );
+        }
+
+        assert!(!is_insufficient_material(&Game::new().start().board));
+    }
+}
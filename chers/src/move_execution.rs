@@ -1,7 +1,10 @@
+use std::any::Any;
+
 use crate::{
-    check::{check_by_opponent_is_mate, checking_pieces_of_opponent},
-    moves_available::autocomplete_to,
-    piece_at, Color, Coordinate, Event, Figure, Move, Piece, State, BOARD_SIZE,
+    bitboard::Bitboard,
+    piece_at,
+    variants::{Standard, Variant},
+    CastleSide, CastlingRights, Color, Coordinate, Event, Figure, Move, Piece, State, BOARD_SIZE,
 };
 
 #[derive(Debug)]
@@ -9,6 +12,9 @@ pub enum CantMovePiece {
     NoPieceToMove,
     ItBelongsToOtherPlayer,
     RequiresPromotion,
+    /// The pocket holds no such piece, or the cell to drop it onto is
+    /// occupied.
+    NoPieceToDrop,
     IllegalMove {
         attempted: Move,
         legal: Vec<Coordinate>,
@@ -16,17 +22,29 @@ pub enum CantMovePiece {
 }
 
 pub fn move_piece(state: &State, the_move: Move) -> Result<(State, Vec<Event>), CantMovePiece> {
-    inner_move_piece(state, the_move, true)
+    inner_move_piece(&Standard, state, the_move, true)
 }
 
 pub fn force_move_piece(
     state: &State,
     the_move: Move,
 ) -> Result<(State, Vec<Event>), CantMovePiece> {
-    inner_move_piece(state, the_move, false)
+    inner_move_piece(&Standard, state, the_move, false)
 }
 
+/// Like [move_piece], but by the rules of [variant].
+pub fn move_piece_by(
+    variant: &dyn Variant,
+    state: &State,
+    the_move: Move,
+) -> Result<(State, Vec<Event>), CantMovePiece> {
+    inner_move_piece(variant, state, the_move, true)
+}
+
+/// Without [check_legality], the move is executed even if it is not legal,
+/// and neither checks nor the end of the game are reported.
 fn inner_move_piece(
+    variant: &dyn Variant,
     state: &State,
     the_move: Move,
     check_legality: bool,
@@ -34,91 +52,262 @@ fn inner_move_piece(
     let from = the_move.from;
     let to = the_move.to;
 
-    let Some(moved) = state.board[from.y][from.x] else {
-        return Err(CantMovePiece::NoPieceToMove);
-    };
+    let moved = match the_move.drop {
+        Some(figure) => Piece::new(state.player, figure),
+        None => {
+            let Some(moved) = state.board[from.y][from.x] else {
+                return Err(CantMovePiece::NoPieceToMove);
+            };
 
-    if moved.color != state.player {
-        return Err(CantMovePiece::ItBelongsToOtherPlayer);
-    }
+            if moved.color != state.player {
+                return Err(CantMovePiece::ItBelongsToOtherPlayer);
+            }
+
+            moved
+        }
+    };
 
     if check_legality {
-        let legal = autocomplete_to(state, from);
-        if !legal.contains(&to) {
+        let legal = match the_move.drop {
+            Some(figure) => variant.legal_drops(state, figure),
+            None => variant.legal_targets(state, from),
+        };
+        let promotion = the_move
+            .promotion
+            .filter(|_| requires_promotion(state, moved, to));
+        let allowed_promotion =
+            promotion.is_none_or(|promotion| variant.promotions().contains(&promotion));
+        if !legal.contains(to) || !allowed_promotion {
             return Err(CantMovePiece::IllegalMove {
                 attempted: the_move,
-                legal,
+                legal: legal.into_iter().collect(),
             });
         }
     }
 
-    let mut events = vec![Event::Move {
-        piece: moved,
-        from,
-        to,
-    }];
-    let mut new_board = state.board;
+    let mut new_state = state.clone();
+    let undo = variant.make_move(&mut new_state, the_move)?;
 
-    let mut did_capture = false;
-    if let Some(captured) = piece_at(to, &state.board) {
-        did_capture = true;
+    // Castling onto the rook still only moves the king to its destination
+    let destination = match undo.castled {
+        Some(side) => side.king_destination(state.player),
+        None => to,
+    };
+    let mut events = match the_move.drop {
+        Some(_) => vec![Event::Drop {
+            piece: moved,
+            at: to,
+        }],
+        None => vec![Event::Move {
+            piece: moved,
+            from,
+            to: destination,
+        }],
+    };
+
+    if let Some((_, captured)) = undo.captured {
         events.push(Event::Capture {
+            // TODO: Maybe we need to introduce more fields here?
             at: to,
             captured,
             by: moved,
         });
-    } else if let Some(en_passant) = state.en_passant_target {
-        if en_passant == to {
-            let piece_location = en_passant.forward(state.player.other(), 1).unwrap();
-            new_board[piece_location.y][piece_location.x] = None;
-            did_capture = true;
-            events.push(Event::Capture {
-                // TODO: Maybe we need to introduce more fields here?
-                at: to,
-                // Save to unwrap, since en_passant target is present
-                captured: piece_at(en_passant, &state.board).unwrap(),
-                by: moved,
-            });
-        }
     }
 
-    new_board[from.y][from.x] = None;
-
-    if requires_promotion(state, moved, to) {
-        let Some(promoted) = the_move.promotion else {
-            return Err(CantMovePiece::RequiresPromotion);
-        };
-
+    if let Some(promoted) = the_move
+        .promotion
+        .filter(|_| requires_promotion(state, moved, to))
+    {
         events.push(Event::Promotion { to: promoted });
-        new_board[to.y][to.x] = Some(Piece {
-            color: state.player,
-            figure: promoted.to_figure(),
+    }
+
+    if let Some(side) = undo.castled {
+        events.push(Event::Castle {
+            side,
+            rook_from: state.castling_rights.rook_origin(state.player, side),
+            rook_to: side.rook_destination(state.player),
         });
-    } else {
-        new_board[to.y][to.x] = Some(moved);
     }
 
-    let new_state = state.new_turn(new_board, moved.figure, the_move, did_capture);
+    events.extend(variant.special_events(state, &undo, &new_state));
 
     if check_legality {
-        let checking_pieces = checking_pieces_of_opponent(&new_state);
-        if !checking_pieces.is_empty() {
-            events.push(Event::Check {
-                by: checking_pieces,
-            });
+        events.extend(variant.outcome_events(&new_state));
+    }
+
+    Ok((new_state, events))
+}
 
-            if check_by_opponent_is_mate(&new_state) {
-                events.push(Event::Mate);
+/// Everything [unmake_move] needs to revert a move made by [make_move].
+#[derive(Debug)]
+pub struct Undo {
+    pub the_move: Move,
+    pub moved: Piece,
+    /// The captured piece and the cell it resided on, which differs from the
+    /// destination of the move when capturing en passant.
+    pub captured: Option<(Coordinate, Piece)>,
+    /// Set if the move castled, i.e. the rook has been relocated as well.
+    pub castled: Option<CastleSide>,
+    pub castling_rights: CastlingRights,
+    pub en_passant_target: Option<Coordinate>,
+    pub halfmove_clock: u16,
+    pub fullmove_number: u16,
+    pub promoted: Bitboard,
+    pub zobrist_key: u64,
+    /// Whatever else a [Variant] needs to revert the move, e.g. the pieces
+    /// blown up in Atomic chess. Only the variant that recorded it knows its
+    /// type, see [Undo::extra].
+    pub extra: Option<Box<dyn Any>>,
+}
+
+impl Undo {
+    /// Records everything about [state] that [the_move] of [moved] changes.
+    fn before(state: &State, the_move: Move, moved: Piece) -> Self {
+        Self {
+            the_move,
+            moved,
+            captured: None,
+            castled: None,
+            castling_rights: state.castling_rights,
+            en_passant_target: state.en_passant_target,
+            halfmove_clock: state.halfmove_clock,
+            fullmove_number: state.fullmove_number,
+            promoted: state.promoted,
+            zobrist_key: state.zobrist_key,
+            extra: None,
+        }
+    }
+
+    /// The data recorded by a [Variant], if it is of type [T].
+    pub fn extra<T: 'static>(&self) -> Option<&T> {
+        self.extra.as_ref()?.downcast_ref()
+    }
+}
+
+/// Executes [the_move] in place, without copying the board.
+///
+/// Unlike [move_piece] this does not check whether the move is legal or
+/// report any events, so it should only be used with moves taken from
+/// [crate::legal_moves]. The returned [Undo] reverts the move when passed to
+/// [unmake_move].
+pub fn make_move(state: &mut State, the_move: Move) -> Result<Undo, CantMovePiece> {
+    if let Some(figure) = the_move.drop {
+        return drop_piece(state, the_move, figure);
+    }
+
+    let from = the_move.from;
+    let to = the_move.to;
+
+    let Some(moved) = piece_at(from, &state.board) else {
+        return Err(CantMovePiece::NoPieceToMove);
+    };
+
+    if moved.color != state.player {
+        return Err(CantMovePiece::ItBelongsToOtherPlayer);
+    }
+
+    let placed = match requires_promotion(state, moved, to) {
+        true => match the_move.promotion {
+            Some(promoted) => Piece::new(state.player, promoted.to_figure()),
+            None => return Err(CantMovePiece::RequiresPromotion),
+        },
+        false => moved,
+    };
+
+    let mut undo = Undo::before(state, the_move, moved);
+
+    let castled = match moved.figure {
+        Figure::King => state.castling_rights.castle_side(state.player, from, to),
+        _ => None,
+    };
+
+    if let Some(side) = castled {
+        // King and rook end up on the same cells as in standard chess, which
+        // in Chess960 might be where the other one started. Both are taken
+        // before placing them, so neither replaces the other.
+        let rook_origin = state.castling_rights.rook_origin(state.player, side);
+        let rook = state.take(rook_origin);
+        state.take(from);
+        state.place(moved, side.king_destination(state.player));
+        if let Some(rook) = rook {
+            state.place(rook, side.rook_destination(state.player));
+        }
+        undo.castled = Some(side);
+    } else {
+        let captured_at = match state.en_passant_target {
+            Some(en_passant) if en_passant == to && moved.figure == Figure::Pawn => {
+                en_passant.forward(state.opponent(), 1)
             }
+            _ => Some(to),
+        };
+        undo.captured = captured_at.and_then(|at| state.take(at).map(|captured| (at, captured)));
+
+        state.take(from);
+        state.place(placed, to);
+
+        // Promoted pieces keep being tracked wherever they move
+        let mut promoted = state.promoted & !(Bitboard::of(from) | Bitboard::of(to));
+        if state.promoted.contains(from) || placed != moved {
+            promoted |= Bitboard::of(to);
         }
+        state.set_promoted(promoted);
     }
 
-    // TODO: Enforce 50 turn limit using halfmove clock
+    state.advance_turn(moved.figure, the_move, undo.captured.is_some());
 
-    Ok((new_state, events))
+    Ok(undo)
 }
 
-fn requires_promotion(state: &State, piece: Piece, to: Coordinate) -> bool {
+/// Puts a piece from the pocket of the current player onto the board.
+fn drop_piece(state: &mut State, the_move: Move, figure: Figure) -> Result<Undo, CantMovePiece> {
+    let piece = Piece::new(state.player, figure);
+    let undo = Undo::before(state, the_move, piece);
+
+    if piece_at(the_move.to, &state.board).is_some() || !state.unpocket(piece) {
+        return Err(CantMovePiece::NoPieceToDrop);
+    }
+
+    state.place(piece, the_move.to);
+    state.advance_turn(figure, the_move, false);
+
+    Ok(undo)
+}
+
+/// Reverts the move described by [undo], which must be the last one made on
+/// [state] by [make_move].
+pub fn unmake_move(state: &mut State, undo: Undo) {
+    let from = undo.the_move.from;
+    let to = undo.the_move.to;
+    let player = undo.moved.color;
+
+    if undo.the_move.is_drop() {
+        state.take(to);
+        state.pocket(undo.moved);
+    } else if let Some(side) = undo.castled {
+        let rook = state.take(side.rook_destination(player));
+        state.take(side.king_destination(player));
+        state.place(undo.moved, from);
+        if let Some(rook) = rook {
+            state.place(rook, undo.castling_rights.rook_origin(player, side));
+        }
+    } else {
+        state.take(to);
+        state.place(undo.moved, from);
+        if let Some((at, captured)) = undo.captured {
+            state.place(captured, at);
+        }
+    }
+
+    state.player = player;
+    state.castling_rights = undo.castling_rights;
+    state.en_passant_target = undo.en_passant_target;
+    state.halfmove_clock = undo.halfmove_clock;
+    state.fullmove_number = undo.fullmove_number;
+    state.promoted = undo.promoted;
+    state.zobrist_key = undo.zobrist_key;
+}
+
+pub(crate) fn requires_promotion(state: &State, piece: Piece, to: Coordinate) -> bool {
     let board_end = match state.player {
         Color::White => 0,
         Color::Black => BOARD_SIZE - 1,
@@ -126,3 +315,225 @@ fn requires_promotion(state: &State, piece: Piece, to: Coordinate) -> bool {
 
     piece.figure == Figure::Pawn && to.y == board_end
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bitboard::Bitboards, fen::parse_state, legal_moves, zobrist, CastleSide, Cell, DrawReason,
+        Player,
+    };
+
+    use super::*;
+
+    #[test]
+    fn it_reports_stalemate() {
+        let state = parse_state("7k/8/8/8/8/8/8/K1q5 b - - 0 1").unwrap();
+
+        let (_, events) = move_piece(&state, Move::simple(Cell::C1, Cell::C2)).unwrap();
+        assert!(events.contains(&Event::Stalemate));
+        assert!(!events.contains(&Event::Mate));
+    }
+
+    #[test]
+    fn it_offers_and_enforces_draws_by_the_halfmove_clock() {
+        let state = parse_state("8/8/4k3/8/8/3K4/8/7R w - - 98 120").unwrap();
+
+        let (state, events) = move_piece(&state, Move::simple(Cell::H1, Cell::H2)).unwrap();
+        assert_eq!(99, state.halfmove_clock);
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, Event::DrawClaimable { .. })));
+
+        let (state, events) = move_piece(&state, Move::simple(Cell::E6, Cell::E7)).unwrap();
+        assert!(events.contains(&Event::DrawClaimable {
+            reason: DrawReason::FiftyMoveRule
+        }));
+
+        let state = State {
+            halfmove_clock: 149,
+            ..state
+        };
+        let (_, events) = move_piece(&state, Move::simple(Cell::H2, Cell::H1)).unwrap();
+        assert!(events.contains(&Event::Draw {
+            reason: DrawReason::SeventyFiveMoveRule
+        }));
+    }
+
+    #[test]
+    fn captures_and_pawn_moves_reset_the_halfmove_clock() {
+        let state = parse_state("4k3/8/8/3p4/8/8/4P3/4K2R w - - 140 120").unwrap();
+
+        let (state, _) = move_piece(&state, Move::simple(Cell::E2, Cell::E4)).unwrap();
+        assert_eq!(0, state.halfmove_clock);
+
+        let (state, _) = move_piece(&state, Move::simple(Cell::E8, Cell::E7)).unwrap();
+        assert_eq!(1, state.halfmove_clock);
+        assert_eq!(121, state.fullmove_number);
+    }
+
+    #[test]
+    fn capturing_the_last_piece_that_could_mate_is_a_draw() {
+        let state = parse_state("4k3/8/8/8/8/5n2/3K4/6R1 b - - 0 40").unwrap();
+
+        let (_, events) = move_piece(&state, Move::simple(Cell::F3, Cell::H4)).unwrap();
+        assert!(!events.contains(&Event::Draw {
+            reason: DrawReason::InsufficientMaterial
+        }));
+
+        let (_, events) = move_piece(&state, Move::simple(Cell::F3, Cell::G1)).unwrap();
+        assert!(events.contains(&Event::Draw {
+            reason: DrawReason::InsufficientMaterial
+        }));
+    }
+
+    #[test]
+    fn en_passant_removes_the_passed_pawn() {
+        let state = parse_state("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2").unwrap();
+
+        let (state, events) = move_piece(&state, Move::simple(Cell::E5, Cell::D6)).unwrap();
+        assert_eq!(None, piece_at(Cell::D5, &state.board));
+        assert_eq!(
+            Some(Piece::white(Figure::Pawn)),
+            piece_at(Cell::D6, &state.board)
+        );
+        assert!(events.contains(&Event::Capture {
+            at: Cell::D6,
+            captured: Piece::black(Figure::Pawn),
+            by: Piece::white(Figure::Pawn),
+        }));
+    }
+
+    #[test]
+    fn only_pawns_capture_en_passant() {
+        let state = parse_state("4k3/8/8/3pP3/4N3/8/8/4K3 w - d6 0 2").unwrap();
+
+        let (state, _) = move_piece(&state, Move::simple(Cell::E4, Cell::D6)).unwrap();
+        assert_eq!(
+            Some(Piece::black(Figure::Pawn)),
+            piece_at(Cell::D5, &state.board)
+        );
+    }
+
+    #[test]
+    fn castling_relocates_the_rook() {
+        let state = parse_state("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        let (king_side, events) = move_piece(&state, Move::simple(Cell::E1, Cell::G1)).unwrap();
+        assert_eq!(
+            Some(Piece::white(Figure::King)),
+            piece_at(Cell::G1, &king_side.board)
+        );
+        assert_eq!(
+            Some(Piece::white(Figure::Rook)),
+            piece_at(Cell::F1, &king_side.board)
+        );
+        assert_eq!(None, piece_at(Cell::H1, &king_side.board));
+        assert!(events.contains(&Event::Castle {
+            side: CastleSide::KingSide,
+            rook_from: Cell::H1,
+            rook_to: Cell::F1,
+        }));
+
+        let (queen_side, _) = move_piece(&king_side, Move::simple(Cell::E8, Cell::C8)).unwrap();
+        assert_eq!(
+            Some(Piece::black(Figure::King)),
+            piece_at(Cell::C8, &queen_side.board)
+        );
+        assert_eq!(
+            Some(Piece::black(Figure::Rook)),
+            piece_at(Cell::D8, &queen_side.board)
+        );
+        assert_eq!(None, piece_at(Cell::A8, &queen_side.board));
+    }
+
+    #[test]
+    fn moving_the_king_or_rooks_revokes_castling_rights() {
+        let state = parse_state("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        let (state, _) = move_piece(&state, Move::simple(Cell::H1, Cell::H2)).unwrap();
+        assert!(!state
+            .castling_rights
+            .allows(Player::White, CastleSide::KingSide));
+        assert!(state
+            .castling_rights
+            .allows(Player::White, CastleSide::QueenSide));
+
+        let (state, _) = move_piece(&state, Move::simple(Cell::E8, Cell::E7)).unwrap();
+        assert!(!state
+            .castling_rights
+            .allows(Player::Black, CastleSide::KingSide));
+        assert!(!state
+            .castling_rights
+            .allows(Player::Black, CastleSide::QueenSide));
+    }
+
+    #[test]
+    fn capturing_a_rook_revokes_castling_rights() {
+        let state = parse_state("r3k2r/8/8/8/8/8/6B1/R3K2R w KQkq - 0 1").unwrap();
+
+        let (state, _) = move_piece(&state, Move::simple(Cell::G2, Cell::A8)).unwrap();
+        assert!(!state
+            .castling_rights
+            .allows(Player::Black, CastleSide::QueenSide));
+        assert!(state
+            .castling_rights
+            .allows(Player::Black, CastleSide::KingSide));
+    }
+
+    /// Makes and unmakes every legal move, checking the result against
+    /// [move_piece] and the original position.
+    fn assert_reversible(notation: &str) {
+        let original = parse_state(notation).unwrap();
+        let mut state = original.clone();
+
+        for the_move in legal_moves(&original) {
+            let undo = make_move(&mut state, the_move).unwrap();
+
+            let (expected, _) = move_piece(&original, the_move).unwrap();
+            assert_eq!(expected, state, "after {the_move:?}");
+            assert_eq!(expected.zobrist_key(), state.zobrist_key());
+            assert_eq!(Bitboards::of(&state.board), state.bitboards);
+
+            unmake_move(&mut state, undo);
+            assert_eq!(original, state, "after taking back {the_move:?}");
+            assert_eq!(original.zobrist_key(), state.zobrist_key());
+            assert_eq!(original.bitboards, state.bitboards);
+        }
+    }
+
+    #[test]
+    fn moves_can_be_made_and_unmade_in_place() {
+        // Castling to both sides, captures and a double step
+        assert_reversible("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+        // En passant
+        assert_reversible("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3");
+        // Promotions with and without capture
+        assert_reversible("r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1");
+    }
+
+    #[test]
+    fn undo_records_describe_the_move() {
+        let mut state = parse_state("4k3/8/8/3pP3/8/8/8/4K2R w K d6 0 1").unwrap();
+
+        let undo = make_move(&mut state, Move::simple(Cell::E5, Cell::D6)).unwrap();
+        assert_eq!(Some((Cell::D5, Piece::black(Figure::Pawn))), undo.captured);
+        assert_eq!(Some(Cell::D6), undo.en_passant_target);
+        assert_eq!(None, state.en_passant_target);
+
+        make_move(&mut state, Move::simple(Cell::E8, Cell::D7)).unwrap();
+        let undo = make_move(&mut state, Move::simple(Cell::E1, Cell::G1)).unwrap();
+        assert_eq!(Some(CastleSide::KingSide), undo.castled);
+        assert!(undo
+            .castling_rights
+            .allows(Player::White, CastleSide::KingSide));
+        assert_eq!(
+            zobrist::key_of(
+                &state.board,
+                state.player,
+                state.castling_rights,
+                state.en_passant_target
+            ),
+            state.zobrist_key()
+        );
+    }
+}
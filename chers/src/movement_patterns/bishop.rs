@@ -1,21 +1,12 @@
-use crate::{Board, Coordinate, Piece};
-
-use super::expansion::expand_until_collides;
+use crate::{
+    bitboard::{attacks, Bitboard, Bitboards},
+    Coordinate, Piece,
+};
 
 /// Computes the movement patterns of a [piece_color] [crate::Figure::Bishop]
 /// residing on [from], given that [player] owns and wants to move it.
-pub fn moves(board: &Board, from: Coordinate, piece: Piece) -> Vec<Coordinate> {
-    expand_until_collides(
-        board,
-        from,
-        piece.color,
-        [
-            (1, 1),   // North West
-            (1, -1),  // North East
-            (-1, 1),  // South West
-            (-1, -1), // South East
-        ],
-    )
+pub fn moves(bitboards: &Bitboards, from: Coordinate, piece: Piece) -> Bitboard {
+    attacks::bishop(from, bitboards.occupied()) & !bitboards.color(piece.color)
 }
 
 #[cfg(test)]
@@ -37,7 +28,13 @@ mod tests {
         //      a   b   c   d   e   f   g
         let state = fen::parse_state("8/P5p1/8/8/3B4/8/8/8 w - - 0 1").unwrap();
         let from = Cell::D4;
-        let targets = moves(&state.board, from, piece_at(from, &state.board).unwrap());
+        let targets = moves(
+            &state.bitboards,
+            from,
+            piece_at(from, &state.board).unwrap(),
+        )
+        .into_iter()
+        .collect::<Vec<_>>();
 
         let expected = [
             // Top Left
@@ -1,27 +1,115 @@
-use crate::{can_be_moved_to_given, Board, Coordinate, Piece};
+use crate::{
+    bitboard::{attacks, Bitboard, Bitboards},
+    CastleSide, CastlingRights, Coordinate, Figure, Piece,
+};
 
 /// Computes the movement patterns of a [piece_color] [crate::Figure::King]
 /// residing on [from], given that [player] owns and wants to move it.
 ///
-/// TODO: Castling will also be implemented here.
-pub fn moves(board: &Board, from: Coordinate, piece: Piece) -> Vec<Coordinate> {
-    let potential_moves = [
-        from.up(1),
-        from.right(1),
-        from.down(1),
-        from.left(1),
-        from.diagonal(1, 1),
-        from.diagonal(-1, 1),
-        from.diagonal(1, -1),
-        from.diagonal(-1, -1),
-    ];
-
-    let mut moves = Vec::new();
-    for potential_move in potential_moves.into_iter().flatten() {
-        if can_be_moved_to_given(potential_move, piece.color, board) {
-            moves.push(potential_move);
+/// Castling moves are included as long as the [castling_rights] allow it and
+/// no pieces other than king and rook occupy the cells both of them pass, see
+/// [CastlingRights::castling_move] for their target. Whether the king would
+/// castle out of or through check is out of scope, since that requires
+/// knowledge about the moves of the opponent.
+pub fn moves(
+    bitboards: &Bitboards,
+    from: Coordinate,
+    piece: Piece,
+    castling_rights: CastlingRights,
+) -> Bitboard {
+    let moves = attacks::king(from) & !bitboards.color(piece.color);
+
+    moves | castling_moves(bitboards, from, piece, castling_rights)
+}
+
+fn castling_moves(
+    bitboards: &Bitboards,
+    from: Coordinate,
+    piece: Piece,
+    castling_rights: CastlingRights,
+) -> Bitboard {
+    let mut moves = Bitboard::EMPTY;
+
+    for side in [CastleSide::KingSide, CastleSide::QueenSide] {
+        if !castling_rights.allows(piece.color, side) {
+            continue;
+        }
+
+        // The rights are revoked once king or rook move, but positions parsed
+        // from FEN might still claim them, so we double-check the pieces are
+        // actually in place.
+        let rook = castling_rights.rook_origin(piece.color, side);
+        if from != castling_rights.king_origin(piece.color)
+            || !bitboards.pieces(piece.color, Figure::Rook).contains(rook)
+        {
+            continue;
+        }
+
+        let king_path = Bitboard::row_span(from, side.king_destination(piece.color));
+        let rook_path = Bitboard::row_span(rook, side.rook_destination(piece.color));
+        let others = bitboards.occupied() & !Bitboard::of(from) & !Bitboard::of(rook);
+        if ((king_path | rook_path) & others).is_empty() {
+            moves |= castling_rights.castling_move(piece.color, side).to.into();
         }
     }
 
     moves
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{fen, fmt_coordinates, piece_at, Cell};
+
+    use super::*;
+
+    fn king_moves(notation: &str, from: Coordinate) -> Vec<Coordinate> {
+        let state = fen::parse_state(notation).unwrap();
+        moves(
+            &state.bitboards,
+            from,
+            piece_at(from, &state.board).unwrap(),
+            state.castling_rights,
+        )
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn kings_can_castle_to_both_sides_if_the_path_is_free() {
+        let targets = king_moves("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", Cell::E1);
+
+        for expected in [Cell::G1, Cell::C1] {
+            assert!(
+                targets.contains(&expected),
+                "{expected} not in {}",
+                fmt_coordinates(&targets)
+            );
+        }
+
+        let targets = king_moves("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1", Cell::E8);
+
+        for expected in [Cell::G8, Cell::C8] {
+            assert!(
+                targets.contains(&expected),
+                "{expected} not in {}",
+                fmt_coordinates(&targets)
+            );
+        }
+    }
+
+    #[test]
+    fn kings_cant_castle_if_pieces_are_in_the_way() {
+        let targets = king_moves("r3k2r/8/8/8/8/8/8/RN2K1NR w KQkq - 0 1", Cell::E1);
+
+        assert!(!targets.contains(&Cell::G1));
+        assert!(!targets.contains(&Cell::C1));
+    }
+
+    #[test]
+    fn kings_cant_castle_without_the_rights_to_do_so() {
+        let targets = king_moves("r3k2r/8/8/8/8/8/8/R3K2R w Qkq - 0 1", Cell::E1);
+
+        assert!(!targets.contains(&Cell::G1));
+        assert!(targets.contains(&Cell::C1));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Coordinate, Figure, Piece, State};
+use crate::{bitboard::Bitboard, Coordinate, Figure, Piece, State};
 
 mod bishop;
 mod king;
@@ -7,17 +7,20 @@ mod pawn;
 mod queen;
 mod rook;
 
-mod expansion;
-
 /// Computes a list of possible moves for the piece, given the current [Player]
 /// owns and wants to move it.
-pub fn of(state: &State, from: Coordinate, piece: Piece) -> Vec<Coordinate> {
+pub fn of(state: &State, from: Coordinate, piece: Piece) -> Bitboard {
+    let bitboards = &state.bitboards;
+
     match piece.figure {
-        Figure::Pawn => pawn::moves(&state.board, from, piece, state.en_passant_target),
-        Figure::King => king::moves(&state.board, from, piece),
-        Figure::Rook => rook::moves(&state.board, from, piece),
-        Figure::Bishop => bishop::moves(&state.board, from, piece),
-        Figure::Queen => queen::moves(&state.board, from, piece),
-        Figure::Knight => knight::moves(&state.board, from, piece),
+        Figure::Pawn => pawn::moves(bitboards, from, piece, state.en_passant_target),
+        Figure::King => king::moves(bitboards, from, piece, state.castling_rights),
+        Figure::Rook => rook::moves(bitboards, from, piece),
+        Figure::Bishop => bishop::moves(bitboards, from, piece),
+        Figure::Queen => queen::moves(bitboards, from, piece),
+        Figure::Knight => knight::moves(bitboards, from, piece),
+        Figure::Archbishop | Figure::Chancellor => {
+            unreachable!("Only played on a CapablancaState")
+        }
     }
 }
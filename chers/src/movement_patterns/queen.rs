@@ -1,24 +1,16 @@
 use crate::{
-    movement_patterns::bishop::moves as bishop_moves, movement_patterns::rook::moves as rook_moves,
-    Board, Coordinate, Piece,
+    bitboard::{Bitboard, Bitboards},
+    movement_patterns::bishop::moves as bishop_moves,
+    movement_patterns::rook::moves as rook_moves,
+    Coordinate, Piece,
 };
 
 /// Computes the movement patterns of a [piece_color] [crate::Figure::Queen]
 /// residing on [from], given that [player] owns and wants to move it.
-pub fn moves(board: &Board, from: Coordinate, piece: Piece) -> Vec<Coordinate> {
-    let mut moves = Vec::new();
-
+pub fn moves(bitboards: &Bitboards, from: Coordinate, piece: Piece) -> Bitboard {
     // A queen can move diagonally like a bishop and straight like a rook,
     // so we just re-use those functions.
-    moves.append(&mut rook_moves(board, from, piece));
-    moves.append(&mut bishop_moves(board, from, piece));
-
-    // There might be some duplication, which we want to avoid
-    // TODO: Not sure if necessary?
-    moves.sort_by_key(|a| format!("{},{}", a.x, a.y));
-    moves.dedup();
-
-    moves
+    rook_moves(bitboards, from, piece) | bishop_moves(bitboards, from, piece)
 }
 
 #[cfg(test)]
@@ -40,7 +32,13 @@ mod tests {
         //      a   b   c   d   e   f   g   h
         let state = fen::parse_state("8/P7/8/4p3/3Q2p1/8/8/3p4 w - - 0 1").unwrap();
         let from = Cell::D4;
-        let targets = moves(&state.board, from, piece_at(from, &state.board).unwrap());
+        let targets = moves(
+            &state.bitboards,
+            from,
+            piece_at(from, &state.board).unwrap(),
+        )
+        .into_iter()
+        .collect::<Vec<_>>();
 
         let expected = [
             // Straight
@@ -74,7 +72,13 @@ mod tests {
             fen::parse_state("rnbqkbnr/ppp2ppp/3p4/4p3/3P4/3Q4/PPP1PPPP/RNB1KBNR w KQkq - 0 1")
                 .unwrap();
         let from = Cell::D3;
-        let targets = moves(&state.board, from, piece_at(from, &state.board).unwrap());
+        let targets = moves(
+            &state.bitboards,
+            from,
+            piece_at(from, &state.board).unwrap(),
+        )
+        .into_iter()
+        .collect::<Vec<_>>();
 
         assert!(
             targets.contains(&Cell::B5),
@@ -1,21 +1,12 @@
-use crate::{Board, Coordinate, Piece};
-
-use super::expansion::expand_until_collides;
+use crate::{
+    bitboard::{attacks, Bitboard, Bitboards},
+    Coordinate, Piece,
+};
 
 /// Computes the movement patterns of a [piece_color] [crate::Figure::Rook]
 /// residing on [from], given that [player] owns and wants to move it.
-pub fn moves(board: &Board, from: Coordinate, piece: Piece) -> Vec<Coordinate> {
-    expand_until_collides(
-        board,
-        from,
-        piece.color,
-        [
-            (0, -1), // Up
-            (0, 1),  // Down
-            (-1, 0), // Left
-            (1, 0),  // Right
-        ],
-    )
+pub fn moves(bitboards: &Bitboards, from: Coordinate, piece: Piece) -> Bitboard {
+    attacks::rook(from, bitboards.occupied()) & !bitboards.color(piece.color)
 }
 #[cfg(test)]
 mod tests {
@@ -36,7 +27,13 @@ mod tests {
         // a    b     c     d             h
         let state = fen::parse_state("8/3p4/3p4/8/3R3P/8/8/8 w - - 0 1").unwrap();
         let from = Cell::D4;
-        let targets = moves(&state.board, from, piece_at(from, &state.board).unwrap());
+        let targets = moves(
+            &state.bitboards,
+            from,
+            piece_at(from, &state.board).unwrap(),
+        )
+        .into_iter()
+        .collect::<Vec<_>>();
 
         let expected = [
             Cell::D5,
@@ -1,2 +1,4 @@
+pub mod san;
 pub mod serialization;
 pub mod transport;
+pub mod uci;
@@ -0,0 +1,490 @@
+use crate::{
+    fen::serialize_piece,
+    move_piece_by, piece_at,
+    variants::{Standard, Variant},
+    CastleSide, Coordinate, Event, Figure, Move, Piece, PromotedFigure, State,
+};
+
+use super::serialization::{ConversionError, Converter};
+
+/// Converts moves from and into Standard Algebraic Notation, e.g. `Nbd7`,
+/// `exd6`, `O-O-O`, `e8=Q+` or `Qxf7#`.
+///
+/// Unlike coordinates, SAN only names the destination and, if required, just
+/// enough of the origin to tell moves apart. Converting therefore requires
+/// the [State] the move is made in.
+pub struct SanConverter {
+    state: State,
+    mark_en_passant: bool,
+}
+
+impl SanConverter {
+    pub fn new(state: State) -> Self {
+        Self {
+            state,
+            mark_en_passant: false,
+        }
+    }
+
+    /// Appends ` e.p.` to en passant captures when serializing, which some
+    /// publications do, but PGN does not allow.
+    pub fn marking_en_passant(mut self) -> Self {
+        self.mark_en_passant = true;
+        self
+    }
+}
+
+impl Converter for SanConverter {
+    fn serialize(&self, a_move: &Move) -> String {
+        let mut san = to_san(&self.state, a_move);
+        if self.mark_en_passant && is_en_passant(&self.state, a_move) {
+            san = match san.find(['+', '#']) {
+                Some(suffix) => format!("{} e.p.{}", &san[..suffix], &san[suffix..]),
+                None => format!("{san} e.p."),
+            };
+        }
+
+        san
+    }
+
+    fn deserialize(&self, string: String) -> Result<Move, ConversionError> {
+        parse_san(&self.state, &string)
+    }
+}
+
+/// Describes [the_move], which is made in [state], in SAN.
+///
+/// Illegal moves are described as well as possible, but might be ambiguous
+/// and are never marked as check.
+pub fn to_san(state: &State, the_move: &Move) -> String {
+    to_san_by(&Standard, state, the_move)
+}
+
+/// Like [to_san], but by the rules of [variant], which decide about check
+/// markers. Drops of Crazyhouse are described like `N@f3`.
+pub fn to_san_by(variant: &dyn Variant, state: &State, the_move: &Move) -> String {
+    let mut san = match (the_move.drop, piece_at(the_move.from, &state.board)) {
+        (Some(figure), _) => format!("{}@{}", figure_letter(figure), the_move.to),
+        (None, Some(piece)) => {
+            let castled =
+                state
+                    .castling_rights
+                    .castle_side(piece.color, the_move.from, the_move.to);
+            match (piece.figure, castled) {
+                (Figure::King, Some(CastleSide::KingSide)) => String::from("O-O"),
+                (Figure::King, Some(CastleSide::QueenSide)) => String::from("O-O-O"),
+                _ => describe_move(variant, state, the_move, piece.figure),
+            }
+        }
+        (None, None) => return format!("{}{}", the_move.from, the_move.to),
+    };
+
+    if let Ok((_, events)) = move_piece_by(variant, state, *the_move) {
+        if events.contains(&Event::Mate) {
+            san.push('#');
+        } else if events
+            .iter()
+            .any(|event| matches!(event, Event::Check { .. }))
+        {
+            san.push('+');
+        }
+    }
+
+    san
+}
+
+/// Everything but castling and check markers.
+fn describe_move(variant: &dyn Variant, state: &State, the_move: &Move, figure: Figure) -> String {
+    let mut san = String::new();
+    let from = the_move.from.to_string();
+    let is_capture =
+        piece_at(the_move.to, &state.board).is_some() || is_en_passant(state, the_move);
+
+    match figure {
+        Figure::Pawn => {
+            // Pawns always capture diagonally, so the file is sufficient
+            if is_capture {
+                san.push_str(&from[..1]);
+            }
+        }
+        _ => {
+            san.push(figure_letter(figure));
+            san.push_str(&disambiguation(variant, state, the_move, figure, &from));
+        }
+    }
+
+    if is_capture {
+        san.push('x');
+    }
+    san.push_str(&the_move.to.to_string());
+
+    if let Some(promotion) = the_move.promotion.filter(|_| figure == Figure::Pawn) {
+        san.push('=');
+        san.push(figure_letter(promotion.to_figure()));
+    }
+
+    san
+}
+
+/// Returns as much of [from] as is needed to tell [the_move] apart from other
+/// moves of the same kind of piece to the same cell, preferring the file.
+fn disambiguation(
+    variant: &dyn Variant,
+    state: &State,
+    the_move: &Move,
+    figure: Figure,
+    from: &str,
+) -> String {
+    let rivals: Vec<Coordinate> = variant
+        .legal_moves(state)
+        .into_iter()
+        .filter(|other| {
+            other.to == the_move.to
+                && other.from != the_move.from
+                && piece_at(other.from, &state.board).map(|piece| piece.figure) == Some(figure)
+        })
+        .map(|other| other.from)
+        .collect();
+
+    if rivals.is_empty() {
+        String::new()
+    } else if rivals.iter().all(|rival| rival.x != the_move.from.x) {
+        from[..1].to_string()
+    } else if rivals.iter().all(|rival| rival.y != the_move.from.y) {
+        from[1..].to_string()
+    } else {
+        from.to_string()
+    }
+}
+
+fn is_en_passant(state: &State, the_move: &Move) -> bool {
+    state.en_passant_target == Some(the_move.to)
+        && piece_at(the_move.from, &state.board).map(|piece| piece.figure) == Some(Figure::Pawn)
+}
+
+/// The letters of figures are the uppercase ones of FEN.
+fn figure_letter(figure: Figure) -> char {
+    serialize_piece(Piece::white(figure))
+}
+
+/// Finds the legal move in [state] that [notation] describes.
+///
+/// Check markers, annotations like `!?` and an ` e.p.` suffix are accepted
+/// but ignored, as are castling written with zeros and promotions without
+/// `=`.
+pub fn parse_san(state: &State, notation: &str) -> Result<Move, ConversionError> {
+    parse_san_by(&Standard, state, notation)
+}
+
+/// Like [parse_san], but finds the move among the legal ones of [variant].
+/// Drops may omit the letter of pawns, e.g. `@e4`.
+pub fn parse_san_by(
+    variant: &dyn Variant,
+    state: &State,
+    notation: &str,
+) -> Result<Move, ConversionError> {
+    let invalid = || ConversionError::InvalidNotation(notation.to_string());
+
+    let mut san = notation.trim_matches(|c: char| c.is_whitespace() || c == '\0');
+    // Markers follow the ` e.p.` suffix, see [SanConverter::marking_en_passant]
+    san = san.trim_end_matches(['+', '#', '!', '?']);
+    san = san.strip_suffix("e.p.").unwrap_or(san).trim_end();
+
+    let candidates = variant.legal_moves(state);
+
+    if let Some((letter, at)) = san.split_once('@') {
+        let mut letters = letter.chars();
+        let figure = match (letters.next(), letters.next()) {
+            (None, _) | (Some('P'), None) => Figure::Pawn,
+            (Some(letter), None) => parse_figure_letter(letter).ok_or_else(invalid)?,
+            _ => return Err(invalid()),
+        };
+        let the_move = Move::drop(figure, Coordinate::algebraic(at).map_err(|_| invalid())?);
+
+        return match candidates.contains(&the_move) {
+            true => Ok(the_move),
+            false => Err(ConversionError::NoSuchMove(notation.to_string())),
+        };
+    }
+    let figure_at = |at: Coordinate| piece_at(at, &state.board).map(|piece| piece.figure);
+
+    let castling = match san {
+        "O-O" | "0-0" => Some(CastleSide::KingSide),
+        "O-O-O" | "0-0-0" => Some(CastleSide::QueenSide),
+        _ => None,
+    };
+    if let Some(side) = castling {
+        return candidates
+            .into_iter()
+            .find(|candidate| {
+                figure_at(candidate.from) == Some(Figure::King)
+                    && state
+                        .castling_rights
+                        .castle_side(state.player, candidate.from, candidate.to)
+                        == Some(side)
+            })
+            .ok_or_else(|| ConversionError::NoSuchMove(notation.to_string()));
+    }
+
+    let mut characters: Vec<char> = san.chars().collect();
+
+    let figure = match characters.first().copied().and_then(parse_figure_letter) {
+        Some(figure) => {
+            characters.remove(0);
+            figure
+        }
+        None => Figure::Pawn,
+    };
+
+    let promotion = match characters.last().copied().and_then(parse_figure_letter) {
+        Some(promoted) => {
+            characters.pop();
+            if characters.last() == Some(&'=') {
+                characters.pop();
+            }
+            Some(promoted_figure(promoted).ok_or_else(invalid)?)
+        }
+        None => None,
+    };
+
+    if characters.len() < 2 {
+        return Err(invalid());
+    }
+    let destination: String = characters
+        .split_off(characters.len() - 2)
+        .into_iter()
+        .collect();
+    let to = Coordinate::algebraic(&destination).map_err(|_| invalid())?;
+
+    // Whatever remains between figure and destination narrows down the origin
+    if characters.last() == Some(&'x') {
+        characters.pop();
+    }
+    let mut file = None;
+    let mut rank = None;
+    for character in characters {
+        match character {
+            'a'..='h' if file.is_none() && rank.is_none() => file = Some(character),
+            '1'..='8' if rank.is_none() => rank = Some(character),
+            _ => return Err(invalid()),
+        }
+    }
+
+    let matching: Vec<Move> = candidates
+        .into_iter()
+        .filter(|candidate| {
+            let from = candidate.from.to_string();
+
+            !candidate.is_drop()
+                && candidate.to == to
+                && candidate.promotion == promotion
+                && figure_at(candidate.from) == Some(figure)
+                && file.is_none_or(|file| from.starts_with(file))
+                && rank.is_none_or(|rank| from.ends_with(rank))
+        })
+        .collect();
+
+    match matching.as_slice() {
+        [the_move] => Ok(*the_move),
+        [] => Err(ConversionError::NoSuchMove(notation.to_string())),
+        _ => Err(ConversionError::AmbiguousMove(notation.to_string())),
+    }
+}
+
+fn parse_figure_letter(letter: char) -> Option<Figure> {
+    match letter {
+        'K' => Some(Figure::King),
+        'Q' => Some(Figure::Queen),
+        'R' => Some(Figure::Rook),
+        'B' => Some(Figure::Bishop),
+        'N' => Some(Figure::Knight),
+        _ => None,
+    }
+}
+
+fn promoted_figure(figure: Figure) -> Option<PromotedFigure> {
+    match figure {
+        Figure::Queen => Some(PromotedFigure::Queen),
+        Figure::Rook => Some(PromotedFigure::Rook),
+        Figure::Bishop => Some(PromotedFigure::Bishop),
+        Figure::Knight => Some(PromotedFigure::Knight),
+        Figure::King => Some(PromotedFigure::King),
+        Figure::Pawn | Figure::Archbishop | Figure::Chancellor => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fen::parse_state,
+        legal_moves,
+        variants::{Antichess, Crazyhouse},
+        Cell, Game,
+    };
+
+    use super::*;
+
+    fn san(notation: &str, the_move: Move) -> String {
+        to_san(&parse_state(notation).unwrap(), &the_move)
+    }
+
+    #[test]
+    fn it_describes_simple_moves_and_captures() {
+        let state = Game::new().start();
+
+        assert_eq!("e4", to_san(&state, &Move::simple(Cell::E2, Cell::E4)));
+        assert_eq!("Nf3", to_san(&state, &Move::simple(Cell::G1, Cell::F3)));
+
+        let notation = "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2";
+        assert_eq!("exd5", san(notation, Move::simple(Cell::E4, Cell::D5)));
+        assert_eq!("Bb5+", san(notation, Move::simple(Cell::F1, Cell::B5)));
+    }
+
+    #[test]
+    fn it_disambiguates_by_file_then_rank_then_both() {
+        // Knights on b8 and f6 can both reach d7
+        let notation = "rn2k3/8/5n2/8/8/8/8/4K3 b - - 0 1";
+        assert_eq!("Nbd7", san(notation, Move::simple(Cell::B8, Cell::D7)));
+
+        // Rooks on a1 and a5 share the file
+        let notation = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+        assert_eq!("R1a3", san(notation, Move::simple(Cell::A1, Cell::A3)));
+
+        // Queens on e4, h4 and h1 all reach e1
+        let notation = "2k5/8/8/8/4Q2Q/8/8/K6Q w - - 0 1";
+        assert_eq!("Qh4e1", san(notation, Move::simple(Cell::H4, Cell::E1)));
+    }
+
+    #[test]
+    fn it_disambiguates_by_the_moves_of_the_variant() {
+        // The knight on d2 is pinned, unless the king is an ordinary piece
+        let state = parse_state("4k3/8/8/6b1/8/8/3N3N/2K5 w - - 0 1").unwrap();
+        let the_move = Move::simple(Cell::H2, Cell::F3);
+
+        assert_eq!("Nf3", to_san(&state, &the_move));
+        assert_eq!("Nhf3", to_san_by(&Antichess, &state, &the_move));
+        assert_eq!(the_move, parse_san_by(&Antichess, &state, "Nhf3").unwrap());
+    }
+
+    #[test]
+    fn it_describes_special_moves() {
+        let notation = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+        assert_eq!("O-O", san(notation, Move::simple(Cell::E1, Cell::G1)));
+        assert_eq!("O-O-O", san(notation, Move::simple(Cell::E1, Cell::C1)));
+
+        let notation = "3qk3/4P3/8/8/8/8/8/4K3 w - - 0 1";
+        let promotion = Move::new(Cell::E7, Cell::D8, Some(PromotedFigure::Queen));
+        assert_eq!("exd8=Q+", san(notation, promotion));
+
+        let notation = "r1bqkbnr/pppp1ppp/2n5/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 2 4";
+        assert_eq!("Qxf7#", san(notation, Move::simple(Cell::H5, Cell::F7)));
+
+        let state = parse_state("4k3/8/8/4Pp2/8/8/8/4K3 w - f6 0 1").unwrap();
+        let en_passant = Move::simple(Cell::E5, Cell::F6);
+        assert_eq!(
+            "exf6",
+            SanConverter::new(state.clone()).serialize(&en_passant)
+        );
+        assert_eq!(
+            "exf6 e.p.",
+            SanConverter::new(state)
+                .marking_en_passant()
+                .serialize(&en_passant)
+        );
+
+        // The check marker follows the suffix
+        let state = parse_state("8/6k1/8/4Pp2/8/8/8/4K3 w - f6 0 1").unwrap();
+        let converter = SanConverter::new(state).marking_en_passant();
+        let notation = converter.serialize(&en_passant);
+        assert_eq!("exf6 e.p.+", notation);
+        assert_eq!(en_passant, converter.deserialize(notation).unwrap());
+    }
+
+    #[test]
+    fn it_describes_and_parses_drops() {
+        let state = parse_state("6k1/5ppp/8/8/8/8/8/R5K1[Np] w - - 0 1").unwrap();
+
+        let drop = Move::drop(Figure::Knight, Cell::F6);
+        assert_eq!("N@f6+", to_san_by(&Crazyhouse, &state, &drop));
+        assert_eq!(drop, parse_san_by(&Crazyhouse, &state, "N@f6+").unwrap());
+
+        // Drops only exist in variants that allow them
+        assert!(matches!(
+            parse_san(&state, "N@f6"),
+            Err(ConversionError::NoSuchMove(_))
+        ));
+
+        let state = parse_state("6k1/5ppp/8/8/8/8/8/R5K1[Np] b - - 0 1").unwrap();
+        let drop = Move::drop(Figure::Pawn, Cell::E2);
+        assert_eq!("P@e2", to_san_by(&Crazyhouse, &state, &drop));
+        assert_eq!(drop, parse_san_by(&Crazyhouse, &state, "P@e2").unwrap());
+        assert_eq!(drop, parse_san_by(&Crazyhouse, &state, "@e2").unwrap());
+    }
+
+    #[test]
+    fn it_parses_what_it_generates() {
+        for notation in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1",
+            "2k5/8/8/8/4Q2Q/8/8/K6Q w - - 0 1",
+        ] {
+            let state = parse_state(notation).unwrap();
+            for the_move in legal_moves(&state) {
+                let san = to_san(&state, &the_move);
+                assert_eq!(the_move, parse_san(&state, &san).unwrap(), "{san}");
+            }
+        }
+    }
+
+    #[test]
+    fn it_parses_common_variations() {
+        let state = parse_state("r3k2r/2P5/8/4Pp2/8/8/8/R3K2R w KQkq f6 0 1").unwrap();
+
+        let cases = [
+            ("0-0-0", Move::simple(Cell::E1, Cell::C1)),
+            ("O-O!", Move::simple(Cell::E1, Cell::G1)),
+            ("exf6 e.p.", Move::simple(Cell::E5, Cell::F6)),
+            ("ef6", Move::simple(Cell::E5, Cell::F6)),
+            (
+                "c8Q+",
+                Move::new(Cell::C7, Cell::C8, Some(PromotedFigure::Queen)),
+            ),
+            (
+                "c8=N",
+                Move::new(Cell::C7, Cell::C8, Some(PromotedFigure::Knight)),
+            ),
+            ("Rxa8+", Move::simple(Cell::A1, Cell::A8)),
+        ];
+
+        for (san, expected) in cases {
+            assert_eq!(expected, parse_san(&state, san).unwrap(), "{san}");
+        }
+    }
+
+    #[test]
+    fn it_rejects_illegal_and_ambiguous_moves() {
+        let state = parse_state("2k5/8/8/8/4Q2Q/8/8/K6Q w - - 0 1").unwrap();
+
+        assert!(matches!(
+            parse_san(&state, "Qe1"),
+            Err(ConversionError::AmbiguousMove(_))
+        ));
+        assert!(matches!(
+            parse_san(&state, "Qh1e1"),
+            Ok(Move { from: Cell::H1, .. })
+        ));
+        assert!(matches!(
+            parse_san(&state, "Kb3"),
+            Err(ConversionError::NoSuchMove(_))
+        ));
+        assert!(matches!(
+            parse_san(&state, "Zz9"),
+            Err(ConversionError::InvalidNotation(_))
+        ));
+        // The figures of Capablanca chess can't be played on a State
+        assert!(matches!(
+            parse_san(&state, "Ce1"),
+            Err(ConversionError::InvalidNotation(_))
+        ));
+    }
+}
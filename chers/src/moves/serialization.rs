@@ -5,8 +5,18 @@ use crate::{Coordinate, CoordinateParseError, Move, PromotedFigure, PromotionErr
 #[derive(Debug)]
 pub enum ConversionError {
     UnknownPromotionPiece(String),
-    NoCoordinateProvided { part: String },
-    FailedToParseCoordinate { cause: CoordinateParseError },
+    NoCoordinateProvided {
+        part: String,
+    },
+    FailedToParseCoordinate {
+        cause: CoordinateParseError,
+    },
+    /// The notation could not be understood at all.
+    InvalidNotation(String),
+    /// The notation is valid, but no legal move matches it.
+    NoSuchMove(String),
+    /// The notation is valid, but matches more than one legal move.
+    AmbiguousMove(String),
 }
 
 impl Display for ConversionError {
@@ -21,6 +31,15 @@ impl Display for ConversionError {
             ConversionError::FailedToParseCoordinate { cause } => {
                 write!(f, "Failed to parse Coordinate: '{:?}'", cause)
             }
+            ConversionError::InvalidNotation(notation) => {
+                write!(f, "Invalid notation '{}'", notation)
+            }
+            ConversionError::NoSuchMove(notation) => {
+                write!(f, "No legal move matches '{}'", notation)
+            }
+            ConversionError::AmbiguousMove(notation) => {
+                write!(f, "More than one legal move matches '{}'", notation)
+            }
         }
     }
 }
@@ -84,11 +103,7 @@ impl Converter for SimpleMoveConverter {
             None => None,
         };
 
-        Ok(Move {
-            from,
-            to,
-            promotion,
-        })
+        Ok(Move::new(from, to, promotion))
     }
 }
 
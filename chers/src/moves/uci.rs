@@ -0,0 +1,166 @@
+use crate::{fen::serialize_piece, Coordinate, Figure, Move, Piece, PromotedFigure};
+
+use super::serialization::{ConversionError, Converter};
+
+/// Converts moves from and into the long algebraic notation of the Universal
+/// Chess Interface, e.g. `e2e4` or `e7e8q`.
+///
+/// This is the format spoken by external engines and used by most opening
+/// books and datasets. Castling is written as the move of the king, e.g.
+/// `e1g1`, which matches how [Move] represents it. Drops of Crazyhouse are
+/// written as the figure and the cell, e.g. `N@f3`.
+pub struct UciConverter {}
+
+impl UciConverter {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for UciConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Converter for UciConverter {
+    fn serialize(&self, a_move: &Move) -> String {
+        to_uci(a_move)
+    }
+
+    fn deserialize(&self, string: String) -> Result<Move, ConversionError> {
+        parse_uci(&string)
+    }
+}
+
+pub fn to_uci(a_move: &Move) -> String {
+    if let Some(figure) = a_move.drop {
+        return format!("{}@{}", drop_letter(figure), a_move.to);
+    }
+
+    match a_move.promotion {
+        None => format!("{}{}", a_move.from, a_move.to),
+        Some(figure) => format!("{}{}{}", a_move.from, a_move.to, promotion_letter(figure)),
+    }
+}
+
+/// Parses a move like `e2e4`, `e7e8q` or `P@e4`.
+///
+/// Since UCI does not depend on the position, the move is not checked for
+/// legality. The null move `0000` is rejected, as [Move] can't express it.
+pub fn parse_uci(notation: &str) -> Result<Move, ConversionError> {
+    let invalid = || ConversionError::InvalidNotation(notation.to_string());
+
+    let uci = notation.trim_matches(|c: char| c.is_whitespace() || c == '\0');
+    if !uci.is_ascii() || !(4..=5).contains(&uci.len()) {
+        return Err(invalid());
+    }
+
+    if let Some((letter, at)) = uci.split_once('@') {
+        let mut letters = letter.chars();
+        let figure = match (letters.next(), letters.next()) {
+            (Some(letter), None) => parse_drop_letter(letter).ok_or_else(invalid)?,
+            _ => return Err(invalid()),
+        };
+        let at = Coordinate::algebraic(at).map_err(|_| invalid())?;
+
+        return Ok(Move::drop(figure, at));
+    }
+
+    let from = Coordinate::algebraic(&uci[0..2]).map_err(|_| invalid())?;
+    let to = Coordinate::algebraic(&uci[2..4]).map_err(|_| invalid())?;
+    let promotion = match uci[4..].chars().next() {
+        Some(letter) => Some(parse_promotion_letter(letter).ok_or_else(invalid)?),
+        None => None,
+    };
+
+    Ok(Move::new(from, to, promotion))
+}
+
+/// The letters of figures are the ones of FEN, in uppercase for drops and
+/// in lowercase for promotions.
+fn drop_letter(figure: Figure) -> char {
+    serialize_piece(Piece::white(figure))
+}
+
+/// Kings can never be dropped, since they are never captured.
+fn parse_drop_letter(letter: char) -> Option<Figure> {
+    match letter.to_ascii_uppercase() {
+        'Q' => Some(Figure::Queen),
+        'R' => Some(Figure::Rook),
+        'B' => Some(Figure::Bishop),
+        'N' => Some(Figure::Knight),
+        'P' => Some(Figure::Pawn),
+        _ => None,
+    }
+}
+
+fn promotion_letter(figure: PromotedFigure) -> char {
+    serialize_piece(Piece::black(figure.to_figure()))
+}
+
+/// Engines send lowercase letters, but some tools use uppercase ones.
+fn parse_promotion_letter(letter: char) -> Option<PromotedFigure> {
+    match letter.to_ascii_lowercase() {
+        'q' => Some(PromotedFigure::Queen),
+        'r' => Some(PromotedFigure::Rook),
+        'b' => Some(PromotedFigure::Bishop),
+        'n' => Some(PromotedFigure::Knight),
+        'k' => Some(PromotedFigure::King),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Cell;
+
+    use super::*;
+
+    #[test]
+    fn it_converts_moves_both_ways() {
+        let converter = UciConverter::new();
+
+        for (notation, the_move) in [
+            ("e2e4", Move::simple(Cell::E2, Cell::E4)),
+            ("e1g1", Move::simple(Cell::E1, Cell::G1)),
+            (
+                "e7e8q",
+                Move::new(Cell::E7, Cell::E8, Some(PromotedFigure::Queen)),
+            ),
+            (
+                "b2a1n",
+                Move::new(Cell::B2, Cell::A1, Some(PromotedFigure::Knight)),
+            ),
+            // Antichess allows promoting to a king
+            (
+                "h7h8k",
+                Move::new(Cell::H7, Cell::H8, Some(PromotedFigure::King)),
+            ),
+            ("N@f3", Move::drop(Figure::Knight, Cell::F3)),
+            ("P@e4", Move::drop(Figure::Pawn, Cell::E4)),
+        ] {
+            assert_eq!(notation, converter.serialize(&the_move));
+            assert_eq!(
+                the_move,
+                converter.deserialize(notation.to_string()).unwrap(),
+                "{notation}"
+            );
+        }
+    }
+
+    #[test]
+    fn it_rejects_invalid_moves() {
+        for notation in [
+            "", "0000", "e2", "e2e9", "i2e4", "e7e8p", "e7e8c", "e7e8qq", "é2e4", "K@e4", "A@e4",
+            "NN@e", "@e4",
+        ] {
+            assert!(parse_uci(notation).is_err(), "{notation}");
+        }
+
+        assert_eq!(
+            Move::new(Cell::A7, Cell::A8, Some(PromotedFigure::Rook)),
+            parse_uci(" a7a8R\n").unwrap()
+        );
+    }
+}
@@ -1,31 +1,75 @@
 use crate::{
-    check::checking_pieces_of_opponent, force_move_piece, movement_patterns, piece_at, Move,
-    Player, PromotedFigure,
+    bitboard::{attacks::is_attacked, Bitboard},
+    move_execution::requires_promotion,
+    movement_patterns,
+    variants::{Standard, Variant},
+    Figure, Move, Piece,
 };
 
 use super::{Coordinate, State};
 
 /// Returns all *legal* moves.
 pub fn autocomplete_to(state: &State, from: Coordinate) -> Vec<Coordinate> {
-    let possible = dbg!(possible_moves(state, from));
-    let without_chk = dbg!(without_checks(state, from, possible));
+    autocomplete_to_by(&Standard, state, from)
+}
 
-    without_chk
+/// Like [autocomplete_to], but by the rules of [variant].
+pub fn autocomplete_to_by(
+    variant: &dyn Variant,
+    state: &State,
+    from: Coordinate,
+) -> Vec<Coordinate> {
+    variant.legal_targets(state, from).into_iter().collect()
 }
 
-/// Returns all possible moves, also including ones that are not legal, e.g.
-/// because they would lead the current player to check themselves.
-pub fn possible_moves(state: &State, from: Coordinate) -> Vec<Coordinate> {
-    possible_moves_by(state, state.player, from)
+/// Returns every legal move of the current player.
+///
+/// Moves that promote a pawn are included once for every figure it can be
+/// promoted to.
+pub fn legal_moves(state: &State) -> Vec<Move> {
+    legal_moves_by(&Standard, state)
 }
 
-pub fn possible_moves_by(state: &State, player: Player, from: Coordinate) -> Vec<Coordinate> {
-    let Some(piece) = piece_at(from, &state.board) else {
-        return Vec::new();
+/// Like [legal_moves], but by the rules of [variant].
+pub fn legal_moves_by(variant: &dyn Variant, state: &State) -> Vec<Move> {
+    let mut moves = Vec::new();
+
+    for from in state.bitboards.color(state.player) {
+        let Some(piece) = state.bitboards.piece_at(from) else {
+            continue;
+        };
+
+        for to in variant.legal_targets(state, from) {
+            if requires_promotion(state, piece, to) {
+                for figure in variant.promotions() {
+                    moves.push(Move::new(from, to, Some(*figure)));
+                }
+            } else {
+                moves.push(Move::simple(from, to));
+            }
+        }
+    }
+
+    moves
+}
+
+/// Like [autocomplete_to], but returns the cells as a [Bitboard].
+pub fn legal_targets(state: &State, from: Coordinate) -> Bitboard {
+    let Some(piece) = state.bitboards.piece_at(from) else {
+        return Bitboard::EMPTY;
     };
 
-    if player != piece.color {
-        return Vec::new();
+    let possible = possible_moves(state, from, piece);
+    let without_chk = without_checks(state, from, piece, possible);
+
+    without_castling_through_check(state, from, piece, without_chk)
+}
+
+/// Returns all possible moves, also including ones that are not legal, e.g.
+/// because they would lead the current player to check themselves.
+fn possible_moves(state: &State, from: Coordinate, piece: Piece) -> Bitboard {
+    if state.player != piece.color {
+        return Bitboard::EMPTY;
     }
 
     movement_patterns::of(state, from, piece)
@@ -33,69 +77,95 @@ pub fn possible_moves_by(state: &State, player: Player, from: Coordinate) -> Vec
 
 /// Returns all moves without the ones allowing the opponent to directly take
 /// their king the next turn.
-fn without_checks(state: &State, from: Coordinate, targets: Vec<Coordinate>) -> Vec<Coordinate> {
-    let mut valid_targets = Vec::new();
+fn without_checks(state: &State, from: Coordinate, piece: Piece, targets: Bitboard) -> Bitboard {
+    let mut valid_targets = Bitboard::EMPTY;
 
     for target in targets {
-        let the_move = Move {
-            from,
-            to: target,
-            promotion: None,
-        };
-
-        let could_lead_to_check = match force_move_piece(state, the_move) {
-            Ok((resulting_state, _)) => would_check_opponent(resulting_state),
-            Err(err) => match err {
-                crate::CantMovePiece::RequiresPromotion => {
-                    would_check_opponent_after_promotion(state, the_move)
-                }
-                // Uninteresting cases we want to skip. Most of these should
-                // not happen anyways and we could (or even should) even panic.
-                // E.g. when we have no piece to move, something is definitely
-                // wrong here!
-                crate::CantMovePiece::NoPieceToMove => false,
-                crate::CantMovePiece::ItBelongsToOtherPlayer => false,
-                crate::CantMovePiece::IllegalMove { .. } => false,
-            },
-        };
-
-        if could_lead_to_check {
+        if would_check_opponent(state, from, piece, target) {
             continue;
         }
 
-        valid_targets.push(target);
+        valid_targets |= target.into();
     }
 
     valid_targets
 }
 
-fn would_check_opponent(state: State) -> bool {
-    let next_turn_state = state.reversed();
-    let has_checking_pieces = checking_pieces_of_opponent(&next_turn_state).is_empty();
-    !has_checking_pieces
+/// Returns all moves without castling moves that start in or pass through a
+/// cell that is attacked by the opponent.
+///
+/// Castling _into_ check is already prevented by [without_checks].
+fn without_castling_through_check(
+    state: &State,
+    from: Coordinate,
+    piece: Piece,
+    targets: Bitboard,
+) -> Bitboard {
+    if piece.figure != Figure::King {
+        return targets;
+    }
+
+    targets
+        .into_iter()
+        .filter(|to| {
+            let Some(side) = state.castling_rights.castle_side(piece.color, from, *to) else {
+                return true;
+            };
+
+            Bitboard::row_span(from, side.king_destination(piece.color))
+                .into_iter()
+                .all(|passed| !is_attacked(&state.bitboards, passed, state.opponent()))
+        })
+        .collect()
 }
 
-fn would_check_opponent_after_promotion(previous_state: &State, the_move: Move) -> bool {
-    // While we could check all cases, promoting to queen does also check for
-    // all moves of a bishop and rook, so we skip those. So queen and knight
-    // should be sufficient if there are no edge cases that I overlook here.
-    for figure in [PromotedFigure::Queen, PromotedFigure::Knight] {
-        let the_actual_move = the_move.with_promotion_to(figure);
-        let Ok((resulting_state, _)) = force_move_piece(previous_state, the_actual_move) else {
-            continue;
-        };
+/// Whether moving [piece] from [from] to [to] would leave the king of the
+/// current player attacked, allowing the opponent to take it the next turn.
+///
+/// Instead of executing the whole move, we only relocate the pieces on a copy
+/// of the bitboards. Which figure a pawn is promoted to does not matter here,
+/// since the promoted piece occupies the same cell either way.
+fn would_check_opponent(state: &State, from: Coordinate, piece: Piece, to: Coordinate) -> bool {
+    let mut bitboards = state.bitboards;
+
+    let captures_en_passant = piece.figure == Figure::Pawn
+        && state.en_passant_target == Some(to)
+        && !bitboards.occupied().contains(to);
 
-        if would_check_opponent(resulting_state) {
-            return true;
+    let castled = match piece.figure {
+        Figure::King => state.castling_rights.castle_side(piece.color, from, to),
+        _ => None,
+    };
+
+    match castled {
+        // The king might move onto its own rook, so both are lifted first
+        Some(side) => {
+            let rook = Piece::new(piece.color, Figure::Rook);
+            let rook_origin = state.castling_rights.rook_origin(piece.color, side);
+            bitboards.toggle(piece, from);
+            bitboards.toggle(rook, rook_origin);
+            bitboards.toggle(piece, side.king_destination(piece.color));
+            bitboards.toggle(rook, side.rook_destination(piece.color));
+        }
+        None => bitboards.relocate(from, to),
+    }
+
+    if captures_en_passant {
+        let passed_pawn = to.forward(state.opponent(), 1).unwrap();
+        if let Some(captured) = bitboards.piece_at(passed_pawn) {
+            bitboards.toggle(captured, passed_pawn);
         }
     }
 
-    false
+    match bitboards.king_of(state.player) {
+        Some(king) => is_attacked(&bitboards, king, state.opponent()),
+        None => false,
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{fen::parse_state, fmt_coordinates, Cell};
+    use crate::{fen::parse_state, fmt_coordinates, Cell, Game, PromotedFigure};
 
     use super::*;
 
@@ -160,6 +230,62 @@ mod tests {
         assert_eq!(2, moves.len());
     }
 
+    #[test]
+    fn kings_cant_castle_out_of_or_through_check() {
+        // The rook on e8 checks the king, so castling is not allowed at all
+        let notation = "4r1k1/8/8/8/8/8/8/R3K2R w KQ - 0 1";
+        let state = parse_state(notation).unwrap();
+        let moves = autocomplete_to(&state, Cell::E1);
+
+        assert!(!moves.contains(&Cell::G1), "{}", fmt_coordinates(&moves));
+        assert!(!moves.contains(&Cell::C1), "{}", fmt_coordinates(&moves));
+
+        // The rook on f8 attacks f1, which the king would need to pass
+        let notation = "5rk1/8/8/8/8/8/8/R3K2R w KQ - 0 1";
+        let state = parse_state(notation).unwrap();
+        let moves = autocomplete_to(&state, Cell::E1);
+
+        assert!(!moves.contains(&Cell::G1), "{}", fmt_coordinates(&moves));
+        assert!(moves.contains(&Cell::C1), "{}", fmt_coordinates(&moves));
+
+        // The rook on b8 only attacks b1, which the king never touches
+        let notation = "1r4k1/8/8/8/8/8/8/R3K2R w KQ - 0 1";
+        let state = parse_state(notation).unwrap();
+        let moves = autocomplete_to(&state, Cell::E1);
+
+        assert!(moves.contains(&Cell::G1), "{}", fmt_coordinates(&moves));
+        assert!(moves.contains(&Cell::C1), "{}", fmt_coordinates(&moves));
+    }
+
+    #[test]
+    fn it_lists_all_legal_moves() {
+        assert_eq!(20, legal_moves(&Game::new().start()).len());
+
+        // The queen covers every cell around the king, but is unprotected
+        let notation = "4k3/8/8/8/8/8/4q3/4K3 w - - 0 1";
+        let state = parse_state(notation).unwrap();
+        let moves = legal_moves(&state);
+
+        assert_eq!(1, moves.len(), "{moves:?}");
+        assert!(moves.contains(&Move::simple(Cell::E1, Cell::E2)));
+    }
+
+    #[test]
+    fn promotions_are_expanded_into_every_figure() {
+        let notation = "1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1";
+        let state = parse_state(notation).unwrap();
+        let moves = legal_moves(&state);
+
+        for figure in PromotedFigure::ALL {
+            assert!(moves.contains(&Move::new(Cell::A7, Cell::A8, Some(figure))));
+            assert!(moves.contains(&Move::new(Cell::A7, Cell::B8, Some(figure))));
+        }
+
+        let pawn_moves = moves.iter().filter(|m| m.from == Cell::A7).count();
+        assert_eq!(8, pawn_moves);
+        assert!(!moves.contains(&Move::simple(Cell::A7, Cell::A8)));
+    }
+
     // #[test]
     // fn king_cant_move_if_result_still_checks() {
     //     let notation = "rnb1kbnr/pppp1ppp/8/4P3/7q/8/PPPPP1PP/RNBQKBNR w KQkq - 0 1";
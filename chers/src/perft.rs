@@ -0,0 +1,270 @@
+use std::fmt::Display;
+
+use crate::{
+    moves::uci::to_uci,
+    variants::{Position, Standard, Variant},
+    Move, State,
+};
+
+/// Counts the positions reachable from [state] in exactly [depth] moves.
+///
+/// Comparing the result with numbers published for well-known positions is
+/// the standard way to verify a move generator, since every missing or
+/// superfluous move changes the count.
+pub fn perft(state: &State, depth: usize) -> u64 {
+    perft_by(&Standard, state, depth)
+}
+
+/// Like [perft], but by the rules of [variant].
+pub fn perft_by<P: Position>(variant: &dyn Variant<P>, state: &P, depth: usize) -> u64 {
+    count(variant, &mut state.clone(), depth)
+}
+
+/// Plays and takes back every move on the same [state], which avoids copying
+/// it for every node.
+fn count<P: Position>(variant: &dyn Variant<P>, state: &mut P, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let moves = variant.legal_moves(state);
+
+    // The leaves don't need to be played, counting them is enough.
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+
+    let mut nodes = 0;
+    for the_move in moves {
+        nodes += played(variant, state, the_move, |state| {
+            count(variant, state, depth - 1)
+        });
+    }
+
+    nodes
+}
+
+/// Like [perft], but splits the count by the first move.
+///
+/// When the total differs from the expected one, comparing the divided
+/// counts with another engine narrows down which move is handled wrongly.
+pub fn divide(state: &State, depth: usize) -> Divide {
+    divide_by(&Standard, state, depth)
+}
+
+/// Like [divide], but by the rules of [variant].
+pub fn divide_by<P: Position>(variant: &dyn Variant<P>, state: &P, depth: usize) -> Divide {
+    let mut state = state.clone();
+
+    let moves = match depth {
+        0 => Vec::new(),
+        _ => variant
+            .legal_moves(&state)
+            .into_iter()
+            .map(|the_move| {
+                let nodes = played(variant, &mut state, the_move, |state| {
+                    count(variant, state, depth - 1)
+                });
+                (the_move, nodes)
+            })
+            .collect(),
+    };
+
+    Divide { moves }
+}
+
+/// Evaluates [f] in the position after [the_move], then takes it back.
+fn played<P: Position>(
+    variant: &dyn Variant<P>,
+    state: &mut P,
+    the_move: Move,
+    f: impl FnOnce(&mut P) -> u64,
+) -> u64 {
+    // The moves were generated as legal ones, so they can't fail.
+    let undo = variant.make_move(state, the_move).unwrap();
+    let result = f(state);
+    variant.unmake_move(state, undo);
+
+    result
+}
+
+/// The node counts of [perft] per first move, see [divide].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divide {
+    pub moves: Vec<(Move, u64)>,
+}
+
+impl Divide {
+    /// The total number of nodes, i.e. the same as [perft] returns.
+    pub fn nodes(&self) -> u64 {
+        self.moves.iter().map(|(_, nodes)| nodes).sum()
+    }
+
+    /// The number of nodes below [the_move], if it is legal.
+    pub fn nodes_after(&self, the_move: Move) -> Option<u64> {
+        self.moves
+            .iter()
+            .find(|(candidate, _)| *candidate == the_move)
+            .map(|(_, nodes)| *nodes)
+    }
+}
+
+/// Lists one move per line, using the same format as most engines do, e.g.
+/// `e2e4: 20` or `a7a8q: 5`, followed by the total.
+impl Display for Divide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (the_move, nodes) in &self.moves {
+            writeln!(f, "{}: {nodes}", to_uci(the_move))?;
+        }
+
+        write!(f, "\nNodes searched: {}", self.nodes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fen::parse_state, Cell, Game};
+
+    use super::*;
+
+    /// Checks the node counts of [notation] for each depth, starting at 1.
+    fn assert_perft(notation: &str, expected: &[u64]) {
+        let state = parse_state(notation).unwrap();
+
+        for (depth, nodes) in expected.iter().enumerate() {
+            assert_eq!(
+                *nodes,
+                perft(&state, depth + 1),
+                "{notation} at depth {}",
+                depth + 1
+            );
+        }
+    }
+
+    fn assert_perft_at(notation: &str, depth: usize, expected: u64) {
+        let state = parse_state(notation).unwrap();
+
+        assert_eq!(
+            expected,
+            perft(&state, depth),
+            "{notation} at depth {depth}"
+        );
+    }
+
+    #[test]
+    fn initial_position() {
+        assert_perft(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            &[20, 400, 8_902, 197_281],
+        );
+    }
+
+    #[test]
+    fn kiwipete() {
+        // Full of castling, en passant, promotions and pins
+        assert_perft(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            &[48, 2_039, 97_862],
+        );
+    }
+
+    #[test]
+    fn en_passant_and_pins_in_the_endgame() {
+        assert_perft(
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            &[14, 191, 2_812, 43_238],
+        );
+    }
+
+    #[test]
+    fn promotions_and_castling_under_attack() {
+        assert_perft(
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            &[6, 264, 9_467],
+        );
+        // The same position with colors reversed
+        assert_perft(
+            "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1",
+            &[6, 264, 9_467],
+        );
+    }
+
+    #[test]
+    fn promotion_capturing_into_check() {
+        assert_perft(
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            &[44, 1_486, 62_379],
+        );
+    }
+
+    #[test]
+    fn symmetrical_middlegame() {
+        assert_perft(
+            "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+            &[46, 2_079, 89_890],
+        );
+    }
+
+    #[test]
+    fn chess960_castling() {
+        assert_perft(
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+            &[21, 528, 12_189, 326_672],
+        );
+    }
+
+    /// Positions by Martin Sedlak, which each focus on a single edge case.
+    #[test]
+    fn edge_cases() {
+        // Promoting a pawn stalemates the own king
+        assert_perft_at("K1k5/8/P7/8/8/8/8/8 w - - 0 1", 6, 2_217);
+        // Underpromoting gives check
+        assert_perft_at("8/P1k5/K7/8/8/8/8/8 w - - 0 1", 6, 92_683);
+        // Promoting gives check
+        assert_perft_at("4k3/1P6/8/8/8/8/K7/8 w - - 0 1", 6, 217_342);
+        // Forking queen and king
+        assert_perft_at("8/8/2k5/5q2/5n2/8/5K2/8 b - - 0 1", 4, 23_527);
+    }
+
+    /// The remaining positions of the same suite, which take too long for
+    /// debug builds. Run them using `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn expensive_edge_cases() {
+        // Capturing en passant would expose the own king
+        assert_perft_at("8/5bk1/8/2Pp4/8/1K6/8/8 w - d6 0 1", 6, 824_064);
+        assert_perft_at("8/8/1k6/8/2pP4/8/5BK1/8 b - d3 0 1", 6, 824_064);
+        // Capturing en passant gives check
+        assert_perft_at("8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1", 6, 1_440_467);
+        // Castling gives check
+        assert_perft_at("5k2/8/8/8/8/8/8/4K2R w K - 0 1", 6, 661_072);
+        assert_perft_at("3k4/8/8/8/8/8/8/R3K3 w Q - 0 1", 6, 803_711);
+        // Castling rights get lost by captures
+        assert_perft_at("r3k2r/1b4bq/8/8/8/8/7B/R3K2R w KQkq - 0 1", 4, 1_274_206);
+        // Castling is prevented by attacked cells
+        assert_perft_at("r3k2r/8/3Q4/8/8/5q2/8/R3K2R b KQkq - 0 1", 4, 1_720_476);
+        // Promoting is the only way out of check
+        assert_perft_at("2K2r2/4P3/8/8/8/8/8/3k4 w - - 0 1", 6, 3_821_001);
+        // Discovered checks
+        assert_perft_at("8/8/1P2K3/8/2n5/1q6/8/5k2 b - - 0 1", 5, 1_004_658);
+        // Stalemate and checkmate
+        assert_perft_at("8/k1P5/8/1K6/8/8/8/8 w - - 0 1", 7, 567_584);
+    }
+
+    #[test]
+    fn divide_splits_the_count_by_first_move() {
+        let state = Game::new().start();
+        let divide = divide(&state, 3);
+
+        assert_eq!(20, divide.moves.len());
+        assert_eq!(8_902, divide.nodes());
+        assert_eq!(
+            Some(600),
+            divide.nodes_after(Move::simple(Cell::E2, Cell::E4))
+        );
+
+        let output = divide.to_string();
+        assert!(output.contains("e2e4: 600\n"), "{output}");
+        assert!(output.ends_with("Nodes searched: 8902"), "{output}");
+    }
+}
@@ -0,0 +1,814 @@
+use std::{fmt::Display, iter::Peekable, str::Chars};
+
+use crate::{
+    fen::{parse_valid_state, to_fen, CouldNotParse},
+    move_piece_by,
+    moves::{
+        san::{parse_san_by, to_san_by},
+        serialization::ConversionError,
+    },
+    variants::{Variant, VariantKind},
+    CantMovePiece, Color, Game, History, Move, State,
+};
+
+/// The tags every game should have, in the order they are written in.
+pub const SEVEN_TAG_ROSTER: [&str; 7] =
+    ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+
+/// The values of the `Variant` tag that denote Chess960, of which the first
+/// one is written. It is not a [VariantKind] of its own, since only the start
+/// position differs from standard chess.
+const CHESS960_NAMES: [&str; 2] = ["Chess960", "Fischerandom"];
+
+/// Lines of exported games are kept below this length, as the standard
+/// recommends.
+const MAX_LINE_LENGTH: usize = 79;
+
+/// How a game ended, as written after its moves.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Outcome {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    /// The game is still in progress, was abandoned, or its result is not
+    /// known.
+    #[default]
+    Unknown,
+}
+
+impl Outcome {
+    pub fn parse(notation: &str) -> Option<Self> {
+        match notation {
+            "1-0" => Some(Outcome::WhiteWins),
+            "0-1" => Some(Outcome::BlackWins),
+            "1/2-1/2" => Some(Outcome::Draw),
+            "*" => Some(Outcome::Unknown),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::WhiteWins => write!(f, "1-0"),
+            Outcome::BlackWins => write!(f, "0-1"),
+            Outcome::Draw => write!(f, "1/2-1/2"),
+            Outcome::Unknown => write!(f, "*"),
+        }
+    }
+}
+
+/// A single move of a [GameRecord], together with its annotations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedMove {
+    pub the_move: Move,
+    /// Numeric Annotation Glyphs, e.g. `1` for a good move. Suffixes like
+    /// `!?` are converted into their glyph when reading.
+    pub nags: Vec<u8>,
+    /// Commentary preceding the move, e.g. at the start of a variation.
+    pub comment_before: Option<String>,
+    pub comment: Option<String>,
+    /// Alternatives to this move, each starting in the position before it.
+    pub variations: Vec<Vec<RecordedMove>>,
+}
+
+impl RecordedMove {
+    pub fn new(the_move: Move) -> Self {
+        Self {
+            the_move,
+            nags: Vec::new(),
+            comment_before: None,
+            comment: None,
+            variations: Vec::new(),
+        }
+    }
+}
+
+/// A game as described in Portable Game Notation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    /// All tags in the order they were read, except for `FEN`, `SetUp`,
+    /// `Variant` and `Result`, which are represented by [GameRecord::initial],
+    /// [GameRecord::variant], [GameRecord::chess960] and [GameRecord::result]
+    /// instead.
+    pub tags: Vec<(String, String)>,
+    pub variant: VariantKind,
+    /// Whether this is a game of Chess960, which is played by the rules of
+    /// standard chess, but from the castling origins of [GameRecord::initial].
+    pub chess960: bool,
+    pub initial: State,
+    /// The main line of the game.
+    pub moves: Vec<RecordedMove>,
+    pub result: Outcome,
+}
+
+impl GameRecord {
+    pub fn new(initial: State) -> Self {
+        Self {
+            tags: Vec::new(),
+            variant: VariantKind::Standard,
+            chess960: false,
+            initial,
+            moves: Vec::new(),
+            result: Outcome::Unknown,
+        }
+    }
+
+    /// Records the moves of [history] without any tags or annotations.
+    pub fn from_history(history: &History) -> Self {
+        let mut record = Self::new(history.positions()[0].clone());
+        record.moves = history
+            .moves()
+            .iter()
+            .map(|the_move| RecordedMove::new(*the_move))
+            .collect();
+
+        record
+    }
+
+    /// Replays the main line, which is expected to be legal.
+    pub fn history(&self) -> Result<History, CantMovePiece> {
+        let game = Game::of_variant(self.variant);
+        let mut history = History::new(self.initial.clone());
+        for recorded in &self.moves {
+            game.play(&mut history, recorded.the_move)?;
+        }
+
+        Ok(history)
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Replaces the value of the tag [name], or adds it if it is missing.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, existing)) => *existing = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PgnError {
+    /// The text does not follow the syntax of PGN, e.g. because a comment is
+    /// never closed.
+    Syntax { line: usize, reason: String },
+    /// The `FEN` tag does not describe a valid position.
+    InvalidSetUp(CouldNotParse),
+    /// The `Variant` tag names a variant that is not supported.
+    UnknownVariant(String),
+    /// The move at [ply], counted from 1 starting at the initial position,
+    /// can't be played.
+    IllegalMove {
+        ply: usize,
+        san: String,
+        reason: IllegalMoveReason,
+    },
+}
+
+#[derive(Debug)]
+pub enum IllegalMoveReason {
+    /// The notation is invalid or does not match exactly one legal move.
+    Notation(ConversionError),
+    Rejected(CantMovePiece),
+}
+
+impl Display for PgnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PgnError::Syntax { line, reason } => write!(f, "Invalid PGN in line {line}: {reason}"),
+            PgnError::InvalidSetUp(cause) => write!(f, "Invalid FEN tag: {cause:?}"),
+            PgnError::UnknownVariant(name) => write!(f, "Unknown variant '{name}'"),
+            PgnError::IllegalMove { ply, san, reason } => {
+                write!(f, "Illegal move '{san}' at ply {ply}: ")?;
+                match reason {
+                    IllegalMoveReason::Notation(cause) => write!(f, "{cause}"),
+                    IllegalMoveReason::Rejected(cause) => write!(f, "{cause:?}"),
+                }
+            }
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+/// Reads every game contained in [text].
+///
+/// Moves are replayed while reading, so the first illegal one is reported
+/// instead of returning a game that can't be played back.
+pub fn parse(text: &str) -> Result<Vec<GameRecord>, PgnError> {
+    let mut tokens = tokenize(text)?.into_iter().peekable();
+    let mut games = Vec::new();
+
+    while tokens.peek().is_some() {
+        games.push(parse_next_game(&mut tokens)?);
+    }
+
+    Ok(games)
+}
+
+/// Reads the first game contained in [text].
+pub fn parse_game(text: &str) -> Result<GameRecord, PgnError> {
+    parse(text)?.into_iter().next().ok_or(PgnError::Syntax {
+        line: 1,
+        reason: String::from("No game found"),
+    })
+}
+
+/// Describes [record] in the export format of PGN.
+///
+/// The Seven Tag Roster is always written, using placeholders for missing
+/// tags, followed by `Variant` for games that are not standard chess and
+/// `SetUp` and `FEN` if the game does not start from the initial position of
+/// its variant, which is always the case for Chess960. Moves after an illegal
+/// one are omitted.
+pub fn to_pgn(record: &GameRecord) -> String {
+    let mut pgn = String::new();
+
+    for name in SEVEN_TAG_ROSTER {
+        let value = match name {
+            "Result" => record.result.to_string(),
+            "Date" => record.tag(name).unwrap_or("????.??.??").to_string(),
+            _ => record.tag(name).unwrap_or("?").to_string(),
+        };
+        pgn += &format_tag(name, &value);
+    }
+    let rules = record.variant.rules();
+    if record.chess960 {
+        pgn += &format_tag("Variant", CHESS960_NAMES[0]);
+    } else if record.variant != VariantKind::Standard {
+        pgn += &format_tag("Variant", rules.name());
+    }
+    if record.chess960 || record.initial != rules.start() {
+        pgn += &format_tag("SetUp", "1");
+        pgn += &format_tag("FEN", &to_fen(&record.initial));
+    }
+    for (name, value) in &record.tags {
+        if !SEVEN_TAG_ROSTER.contains(&name.as_str()) {
+            pgn += &format_tag(name, value);
+        }
+    }
+    pgn.push('\n');
+
+    let mut tokens = Vec::new();
+    write_line(&mut tokens, rules, &record.initial, &record.moves);
+    tokens.push(record.result.to_string());
+
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() > MAX_LINE_LENGTH {
+            pgn += &line;
+            pgn.push('\n');
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line += &token;
+    }
+    pgn += &line;
+    pgn.push('\n');
+
+    pgn
+}
+
+fn format_tag(name: &str, value: &str) -> String {
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("[{name} \"{escaped}\"]\n")
+}
+
+fn write_line(
+    tokens: &mut Vec<String>,
+    variant: &dyn Variant,
+    state: &State,
+    moves: &[RecordedMove],
+) {
+    let mut state = state.clone();
+    // Black moves are only numbered at the start or after an interruption
+    let mut needs_number = true;
+
+    for recorded in moves {
+        if let Some(comment) = &recorded.comment_before {
+            tokens.push(format!("{{{comment}}}"));
+            needs_number = true;
+        }
+
+        match state.player {
+            Color::White => tokens.push(format!("{}.", state.fullmove_number)),
+            Color::Black if needs_number => tokens.push(format!("{}...", state.fullmove_number)),
+            Color::Black => {}
+        }
+        tokens.push(to_san_by(variant, &state, &recorded.the_move));
+        tokens.extend(recorded.nags.iter().map(|nag| format!("${nag}")));
+        needs_number = false;
+
+        if let Some(comment) = &recorded.comment {
+            tokens.push(format!("{{{comment}}}"));
+            needs_number = true;
+        }
+
+        for variation in &recorded.variations {
+            let mut alternative = Vec::new();
+            write_line(&mut alternative, variant, &state, variation);
+            if let Some(first) = alternative.first_mut() {
+                first.insert(0, '(');
+            }
+            if let Some(last) = alternative.last_mut() {
+                last.push(')');
+            }
+            tokens.append(&mut alternative);
+            needs_number = true;
+        }
+
+        match move_piece_by(variant, &state, recorded.the_move) {
+            Ok((next, _)) => state = next,
+            Err(_) => return,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Tag(String, String),
+    Comment(String),
+    Nag(u8),
+    VariationStart,
+    VariationEnd,
+    Move(String),
+    Result(Outcome),
+}
+
+type Tokens = Peekable<std::vec::IntoIter<(usize, Token)>>;
+
+fn parse_next_game(tokens: &mut Tokens) -> Result<GameRecord, PgnError> {
+    let mut tags = Vec::new();
+    while let Some((_, Token::Tag(..))) = tokens.peek() {
+        if let Some((_, Token::Tag(name, value))) = tokens.next() {
+            tags.push((name, value));
+        }
+    }
+
+    let variant_tag = tags
+        .iter()
+        .find(|(name, _)| name == "Variant")
+        .map(|(_, value)| value);
+    let chess960 = variant_tag.is_some_and(|name| {
+        CHESS960_NAMES
+            .iter()
+            .any(|chess960| chess960.eq_ignore_ascii_case(name))
+    });
+    let variant = match variant_tag {
+        Some(name) if !chess960 => {
+            VariantKind::from_name(name).ok_or_else(|| PgnError::UnknownVariant(name.clone()))?
+        }
+        _ => VariantKind::Standard,
+    };
+    let initial = match tags.iter().find(|(name, _)| name == "FEN") {
+        Some((_, fen)) => parse_valid_state(fen).map_err(PgnError::InvalidSetUp)?,
+        None => variant.rules().start(),
+    };
+    tags.retain(|(name, _)| !matches!(name.as_str(), "FEN" | "SetUp" | "Variant" | "Result"));
+
+    let mut record = GameRecord::new(initial);
+    record.variant = variant;
+    record.chess960 = chess960;
+    let (moves, result) = parse_line(tokens, variant.rules(), &record.initial, 1, false)?;
+    record.tags = tags;
+    record.moves = moves;
+    record.result = result;
+
+    Ok(record)
+}
+
+/// Reads moves starting in [state] until the end of the game or, if
+/// [is_variation], until the end of the variation.
+fn parse_line(
+    tokens: &mut Tokens,
+    variant: &dyn Variant,
+    state: &State,
+    first_ply: usize,
+    is_variation: bool,
+) -> Result<(Vec<RecordedMove>, Outcome), PgnError> {
+    let mut moves: Vec<RecordedMove> = Vec::new();
+    let mut state = state.clone();
+    let mut previous = state.clone();
+    let mut comment_before = None;
+
+    let syntax_error = |line, reason: &str| PgnError::Syntax {
+        line,
+        reason: reason.to_string(),
+    };
+
+    loop {
+        // Tolerates games without a result, which end where the next begins
+        if !is_variation && matches!(tokens.peek(), Some((_, Token::Tag(..)))) {
+            return Ok((moves, Outcome::Unknown));
+        }
+
+        let Some((line, token)) = tokens.next() else {
+            return match is_variation {
+                true => Err(syntax_error(0, "Variation is never closed")),
+                false => Ok((moves, Outcome::Unknown)),
+            };
+        };
+
+        match token {
+            Token::Move(san) => {
+                let ply = first_ply + moves.len();
+                let illegal = |reason| PgnError::IllegalMove {
+                    ply,
+                    san: san.clone(),
+                    reason,
+                };
+
+                let the_move = parse_san_by(variant, &state, &san)
+                    .map_err(|cause| illegal(IllegalMoveReason::Notation(cause)))?;
+                let (next, _) = move_piece_by(variant, &state, the_move)
+                    .map_err(|cause| illegal(IllegalMoveReason::Rejected(cause)))?;
+
+                let mut recorded = RecordedMove::new(the_move);
+                recorded.comment_before = comment_before.take();
+                moves.push(recorded);
+                previous = std::mem::replace(&mut state, next);
+            }
+            Token::Nag(nag) => {
+                if let Some(last) = moves.last_mut() {
+                    last.nags.push(nag);
+                }
+            }
+            Token::Comment(text) => {
+                let target = match moves.last_mut() {
+                    Some(last) if comment_before.is_none() => &mut last.comment,
+                    _ => &mut comment_before,
+                };
+                *target = Some(match target.take() {
+                    Some(existing) => format!("{existing} {text}"),
+                    None => text,
+                });
+            }
+            Token::VariationStart => {
+                let ply = first_ply + moves.len();
+                let Some(last) = moves.last_mut() else {
+                    return Err(syntax_error(line, "Variation without a preceding move"));
+                };
+                // The variation replaces the last move, so it starts at its ply
+                let ply = ply - 1;
+                let (variation, _) = parse_line(tokens, variant, &previous, ply, true)?;
+                last.variations.push(variation);
+            }
+            Token::VariationEnd if is_variation => return Ok((moves, Outcome::Unknown)),
+            Token::VariationEnd => return Err(syntax_error(line, "Unexpected ')'")),
+            Token::Result(_) if is_variation => {
+                return Err(syntax_error(line, "Game ends inside a variation"))
+            }
+            Token::Result(result) => return Ok((moves, result)),
+            Token::Tag(..) => return Err(syntax_error(line, "Tag inside a variation")),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, PgnError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    let mut at_line_start = true;
+
+    let syntax_error = |line, reason: &str| PgnError::Syntax {
+        line,
+        reason: reason.to_string(),
+    };
+
+    while let Some(character) = chars.next() {
+        let starts_line = at_line_start;
+        at_line_start = character == '\n';
+
+        match character {
+            '\n' => line += 1,
+            _ if character.is_whitespace() => {}
+            // Escaped lines are meant for other software and are ignored
+            '%' if starts_line => {
+                read_until(&mut chars, '\n');
+                line += 1;
+                at_line_start = true;
+            }
+            ';' => {
+                let comment = read_until(&mut chars, '\n');
+                tokens.push((line, Token::Comment(comment.trim().to_string())));
+                line += 1;
+                at_line_start = true;
+            }
+            '{' => {
+                let start = line;
+                let comment = read_until(&mut chars, '}');
+                if !comment.ends_with('}') {
+                    return Err(syntax_error(start, "Comment is never closed"));
+                }
+                line += comment.matches('\n').count();
+                let comment = comment[..comment.len() - 1].split_whitespace();
+                tokens.push((start, Token::Comment(comment.collect::<Vec<_>>().join(" "))));
+            }
+            '[' => {
+                let tag = read_tag(&mut chars).ok_or_else(|| syntax_error(line, "Invalid tag"))?;
+                tokens.push((line, tag));
+            }
+            '(' => tokens.push((line, Token::VariationStart)),
+            ')' => tokens.push((line, Token::VariationEnd)),
+            '$' => {
+                let nag = read_symbol(&mut chars, String::new());
+                let nag = nag.parse().map_err(|_| syntax_error(line, "Invalid NAG"))?;
+                tokens.push((line, Token::Nag(nag)));
+            }
+            _ => {
+                let symbol = read_symbol(&mut chars, character.to_string());
+                for token in parse_symbol(&symbol) {
+                    let token = token.ok_or_else(|| syntax_error(line, "Invalid move suffix"))?;
+                    tokens.push((line, token));
+                }
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Reads up to and including [end], or the end of the text.
+fn read_until(chars: &mut Peekable<Chars>, end: char) -> String {
+    let mut read = String::new();
+    for character in chars.by_ref() {
+        read.push(character);
+        if character == end {
+            break;
+        }
+    }
+
+    read
+}
+
+fn read_symbol(chars: &mut Peekable<Chars>, mut symbol: String) -> String {
+    while let Some(character) = chars.next_if(|c| !c.is_whitespace() && !"{}()[];$\"".contains(*c))
+    {
+        symbol.push(character);
+    }
+
+    symbol
+}
+
+/// Reads a tag like `[Event "Casual Game"]`, where the `[` is already read.
+fn read_tag(chars: &mut Peekable<Chars>) -> Option<Token> {
+    let skip_spaces = |chars: &mut Peekable<Chars>| while chars.next_if(|c| *c == ' ').is_some() {};
+
+    skip_spaces(chars);
+    let mut name = String::new();
+    while let Some(character) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+        name.push(character);
+    }
+    skip_spaces(chars);
+
+    if name.is_empty() || chars.next() != Some('"') {
+        return None;
+    }
+
+    let mut value = String::new();
+    loop {
+        match chars.next()? {
+            '\\' => value.push(chars.next()?),
+            '"' => break,
+            '\n' => return None,
+            character => value.push(character),
+        }
+    }
+
+    skip_spaces(chars);
+    match chars.next() {
+        Some(']') => Some(Token::Tag(name, value)),
+        _ => None,
+    }
+}
+
+/// Splits a symbol like `12.Nf3!?` into a move and its annotation, dropping
+/// the move number. Returns [None] for unknown suffixes.
+fn parse_symbol(symbol: &str) -> Vec<Option<Token>> {
+    if let Some(result) = Outcome::parse(symbol) {
+        return vec![Some(Token::Result(result))];
+    }
+
+    // Move numbers like `12.` or `12...`, possibly without a space before
+    // the move. Castling with zeros has no dot, so it is left untouched.
+    let mut san = symbol;
+    let digits = san.trim_start_matches(|c: char| c.is_ascii_digit());
+    if digits.starts_with('.') {
+        san = digits;
+    }
+    san = san.trim_start_matches('.');
+    if san.is_empty() {
+        return Vec::new();
+    }
+
+    let move_part = san.trim_end_matches(['!', '?']);
+    let suffix = &san[move_part.len()..];
+    let mut tokens = vec![Some(Token::Move(move_part.to_string()))];
+    let nag = match suffix {
+        "" => return tokens,
+        "!" => 1,
+        "?" => 2,
+        "!!" => 3,
+        "??" => 4,
+        "!?" => 5,
+        "?!" => 6,
+        _ => return vec![None],
+    };
+    tokens.push(Some(Token::Nag(nag)));
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{piece_at, Cell, Figure, Piece, PromotedFigure};
+
+    use super::*;
+
+    const OPERA_GAME: &str = r#"[Event "Paris"]
+[Site "Paris FRA"]
+[Date "1858.??.??"]
+[Round "?"]
+[White "Paul Morphy"]
+[Black "Duke Karl / Count Isouard"]
+[Result "1-0"]
+[ECO "C41"]
+
+1. e4 e5 2. Nf3 d6 3. d4 Bg4 {This is a weak move already.} 4. dxe5 Bxf3 5.
+Qxf3 dxe5 6. Bc4 Nf6 7. Qb3 qe7 8. Nc3 c6 9. Bg5 b5 10. Nxb5 cxb5 11. Bxb5+
+Nbd7 12. O-O-O Rd8 13. Rxd7 Rxd7 14. Rd1 Qe6 15. Bxd7+ Nxd7 16. Qb8+ Nxb8 17.
+Rd8# 1-0
+"#;
+
+    #[test]
+    fn it_reads_tags_moves_and_the_result() {
+        let pgn = OPERA_GAME.replace("qe7", "Qe7");
+        let record = parse_game(&pgn).unwrap();
+
+        assert_eq!(Some("Paul Morphy"), record.tag("White"));
+        assert_eq!(Some("C41"), record.tag("ECO"));
+        assert_eq!(None, record.tag("Result"));
+        assert_eq!(Outcome::WhiteWins, record.result);
+        assert_eq!(33, record.moves.len());
+        assert_eq!(
+            Some("This is a weak move already."),
+            record.moves[5].comment.as_deref()
+        );
+        assert_eq!(
+            Move::simple(Cell::D1, Cell::D8),
+            record.moves.last().unwrap().the_move
+        );
+
+        let history = record.history().unwrap();
+        assert_eq!(34, history.positions().len());
+    }
+
+    #[test]
+    fn it_writes_what_it_reads() {
+        let pgn = OPERA_GAME.replace("qe7", "Qe7");
+        let record = parse_game(&pgn).unwrap();
+        let written = to_pgn(&record);
+
+        // The game is already in export format
+        assert_eq!(pgn, written);
+        assert_eq!(record, parse_game(&written).unwrap());
+    }
+
+    #[test]
+    fn it_reads_annotations_and_variations() {
+        let pgn = "1. e4 $1 e5 {Open game} (1... c5!? {Sicilian} 2. Nf3 (2. c3) d6) \
+                   2. Nf3?! ; Rest of line\n%escaped\n2... Nc6 *";
+        let record = parse_game(pgn).unwrap();
+
+        assert_eq!(vec![1], record.moves[0].nags);
+        assert_eq!(Some("Open game"), record.moves[1].comment.as_deref());
+        assert_eq!(vec![6], record.moves[2].nags);
+        assert_eq!(Some("Rest of line"), record.moves[2].comment.as_deref());
+        assert_eq!(Outcome::Unknown, record.result);
+
+        let sicilian = &record.moves[1].variations[0];
+        assert_eq!(Move::simple(Cell::C7, Cell::C5), sicilian[0].the_move);
+        assert_eq!(vec![5], sicilian[0].nags);
+        assert_eq!(
+            Move::simple(Cell::C2, Cell::C3),
+            sicilian[1].variations[0][0].the_move
+        );
+
+        let written = to_pgn(&record);
+        let unwrapped = written.split_whitespace().collect::<Vec<_>>().join(" ");
+        assert!(
+            unwrapped.contains(
+                "1. e4 $1 e5 {Open game} (1... c5 $5 {Sicilian} 2. Nf3 (2. c3) 2... d6) 2. Nf3 $6"
+            ),
+            "{written}"
+        );
+        assert_eq!(record.moves, parse_game(&written).unwrap().moves);
+    }
+
+    #[test]
+    fn it_supports_custom_starting_positions() {
+        let pgn = r#"[SetUp "1"]
+[FEN "4k3/1P6/8/8/8/8/8/4K3 w - - 0 40"]
+
+40. b8=N Kf7 1/2-1/2"#;
+        let record = parse_game(pgn).unwrap();
+
+        assert_eq!(
+            Move::new(Cell::B7, Cell::B8, Some(PromotedFigure::Knight)),
+            record.moves[0].the_move
+        );
+        assert!(record.tags.is_empty());
+
+        let written = to_pgn(&record);
+        assert!(written.contains("[FEN \"4k3/1P6/8/8/8/8/8/4K3 w - - 0 40\"]"));
+        assert!(written.ends_with("40. b8=N Kf7 1/2-1/2\n"), "{written}");
+    }
+
+    #[test]
+    fn it_reports_where_a_game_becomes_illegal() {
+        let games = parse(&format!("{OPERA_GAME}\n\n1. e4 e5 *")).map(|games| games.len());
+        let Err(PgnError::IllegalMove { ply, san, .. }) = games else {
+            panic!("Expected an illegal move, got {games:?}");
+        };
+        assert_eq!(14, ply);
+        assert_eq!("qe7", san);
+
+        let variation = parse_game("1. e4 (1. d4 d5 2. Ke3) e5");
+        assert!(matches!(
+            variation,
+            Err(PgnError::IllegalMove { ply: 3, .. })
+        ));
+
+        let syntax = parse_game("[Event \"Unclosed\"]\n\n1. e4 {Never closed");
+        assert!(matches!(syntax, Err(PgnError::Syntax { line: 3, .. })));
+
+        let variant = parse_game("[Variant \"Suicide Bughouse\"]\n\n1. e4 *");
+        assert!(matches!(variant, Err(PgnError::UnknownVariant(_))));
+    }
+
+    #[test]
+    fn it_reads_and_writes_the_variant() {
+        let pgn = r#"[Event "?"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "?"]
+[Black "?"]
+[Result "*"]
+[Variant "Crazyhouse"]
+
+1. e4 d5 2. exd5 Qxd5 3. Nc3 Qa5 4. d4 P@e4 *
+"#;
+        let record = parse_game(pgn).unwrap();
+
+        assert_eq!(VariantKind::Crazyhouse, record.variant);
+        assert_eq!(None, record.tag("Variant"));
+        assert_eq!(Some(Figure::Pawn), record.moves[7].the_move.drop);
+        assert_eq!(9, record.history().unwrap().positions().len());
+
+        assert_eq!(pgn, to_pgn(&record));
+        assert_eq!(record, parse_game(&to_pgn(&record)).unwrap());
+    }
+
+    #[test]
+    fn it_reads_and_writes_chess960() {
+        let pgn = r#"[Event "?"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "?"]
+[Black "?"]
+[Result "*"]
+[Variant "Chess960"]
+[SetUp "1"]
+[FEN "nrkbbqrn/pppppppp/8/8/8/8/PPPPPPPP/NRKBBQRN w KQkq - 0 1"]
+
+1. e3 e6 2. Be2 Be7 3. O-O-O O-O-O *
+"#;
+        let record = parse_game(pgn).unwrap();
+
+        assert_eq!(VariantKind::Standard, record.variant);
+        assert!(record.chess960);
+        assert_eq!(None, record.tag("Variant"));
+        // The king stays on c1, while the rook moves from b1 to d1
+        let history = record.history().unwrap();
+        let board = history.current().board();
+        assert_eq!(Some(Piece::white(Figure::King)), piece_at(Cell::C1, board));
+        assert_eq!(Some(Piece::white(Figure::Rook)), piece_at(Cell::D1, board));
+
+        assert_eq!(pgn, to_pgn(&record));
+        assert_eq!(record, parse_game(&to_pgn(&record)).unwrap());
+
+        let older = pgn.replace("Chess960", "Fischerandom");
+        assert_eq!(record, parse_game(&older).unwrap());
+    }
+}
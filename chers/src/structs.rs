@@ -4,6 +4,10 @@ use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 
 use super::Coordinate;
+use crate::{
+    bitboard::{Bitboard, Bitboards},
+    zobrist,
+};
 
 #[derive(Tsify, Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum Color {
@@ -26,6 +30,15 @@ impl Color {
     pub fn owns(self, piece: Piece) -> bool {
         self == piece.color
     }
+
+    /// The row on which the pieces of this color start, e.g. where the king
+    /// and rooks reside before castling.
+    pub fn back_rank(self) -> usize {
+        match self {
+            Color::White => BOARD_SIZE - 1,
+            Color::Black => 0,
+        }
+    }
 }
 
 pub type Player = Color;
@@ -38,6 +51,25 @@ pub enum Figure {
     Bishop,
     Knight,
     Pawn,
+    /// Moves like a bishop or a knight. Only played in Capablanca chess, see
+    /// [crate::variants::CapablancaState].
+    Archbishop,
+    /// Moves like a rook or a knight. Only played in Capablanca chess, see
+    /// [crate::variants::CapablancaState].
+    Chancellor,
+}
+
+impl Figure {
+    /// The figures of standard chess, which are the only ones a [State] can
+    /// hold. The others are only played on a [crate::variants::CapablancaState].
+    pub const STANDARD: [Figure; 6] = [
+        Figure::King,
+        Figure::Queen,
+        Figure::Rook,
+        Figure::Bishop,
+        Figure::Knight,
+        Figure::Pawn,
+    ];
 }
 
 #[derive(Tsify, Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
@@ -46,6 +78,12 @@ pub enum PromotedFigure {
     Rook,
     Bishop,
     Knight,
+    /// Only allowed in Antichess, where the king is an ordinary piece.
+    King,
+    /// Only allowed in Capablanca chess, see [crate::variants::Capablanca].
+    Archbishop,
+    /// Only allowed in Capablanca chess, see [crate::variants::Capablanca].
+    Chancellor,
 }
 
 pub enum PromotionError {
@@ -53,21 +91,35 @@ pub enum PromotionError {
 }
 
 impl PromotedFigure {
+    /// The figures a pawn may be promoted to in standard chess.
+    pub const ALL: [PromotedFigure; 4] = [
+        PromotedFigure::Queen,
+        PromotedFigure::Rook,
+        PromotedFigure::Bishop,
+        PromotedFigure::Knight,
+    ];
+
     pub fn to_figure(&self) -> Figure {
         match self {
             PromotedFigure::Queen => Figure::Queen,
             PromotedFigure::Rook => Figure::Rook,
             PromotedFigure::Bishop => Figure::Bishop,
             PromotedFigure::Knight => Figure::Knight,
+            PromotedFigure::King => Figure::King,
+            PromotedFigure::Archbishop => Figure::Archbishop,
+            PromotedFigure::Chancellor => Figure::Chancellor,
         }
     }
 
+    /// Parses the name of a figure, except for the ones of Capablanca chess,
+    /// which can't be played on a [State].
     pub fn parse(string: &str) -> Result<Self, PromotionError> {
         match string {
             "Queen" => Ok(PromotedFigure::Queen),
             "Rook" => Ok(PromotedFigure::Rook),
             "Bishop" => Ok(PromotedFigure::Bishop),
             "Knight" => Ok(PromotedFigure::Knight),
+            "King" => Ok(PromotedFigure::King),
             _ => Err(PromotionError::UnknownPiece {
                 piece: String::from(string),
             }),
@@ -82,6 +134,9 @@ impl Display for PromotedFigure {
             PromotedFigure::Rook => write!(f, "Rook "),
             PromotedFigure::Bishop => write!(f, "Bishop "),
             PromotedFigure::Knight => write!(f, "Knight "),
+            PromotedFigure::King => write!(f, "King "),
+            PromotedFigure::Archbishop => write!(f, "Archbishop "),
+            PromotedFigure::Chancellor => write!(f, "Chancellor "),
         }
     }
 }
@@ -91,6 +146,12 @@ pub struct Move {
     pub from: Coordinate,
     pub to: Coordinate,
     pub promotion: Option<PromotedFigure>,
+    /// The figure taken from the pocket of the current player and put onto
+    /// the empty cell [Move::to] in Crazyhouse, in which case [Move::from]
+    /// is the same cell.
+    #[serde(default)]
+    #[tsify(optional)]
+    pub drop: Option<Figure>,
 }
 
 impl Move {
@@ -99,22 +160,30 @@ impl Move {
             from,
             to,
             promotion,
+            drop: None,
         }
     }
 
     pub fn simple(from: Coordinate, to: Coordinate) -> Self {
+        Self::new(from, to, None)
+    }
+
+    /// Drops a piece of [figure] from the pocket onto [at].
+    pub fn drop(figure: Figure, at: Coordinate) -> Self {
         Self {
-            from,
-            to,
-            promotion: None,
+            drop: Some(figure),
+            ..Self::simple(at, at)
         }
     }
 
+    pub fn is_drop(&self) -> bool {
+        self.drop.is_some()
+    }
+
     pub fn with_promotion_to(&self, figure: PromotedFigure) -> Self {
         Self {
-            from: self.from,
-            to: self.to,
             promotion: Some(figure),
+            ..*self
         }
     }
 }
@@ -175,6 +244,11 @@ pub const fn empty_board() -> Board {
 pub struct CastlingRights {
     white: CastleDirections,
     black: CastleDirections,
+    /// Where king and rooks start, which only differs between games of
+    /// Chess960.
+    #[serde(default)]
+    #[tsify(optional)]
+    origins: CastlingOrigins,
 }
 
 impl CastlingRights {
@@ -182,8 +256,153 @@ impl CastlingRights {
         Self {
             white: CastleDirections::both(),
             black: CastleDirections::both(),
+            origins: CastlingOrigins::STANDARD,
+        }
+    }
+
+    pub const fn none() -> Self {
+        Self {
+            white: CastleDirections::none(),
+            black: CastleDirections::none(),
+            origins: CastlingOrigins::STANDARD,
+        }
+    }
+
+    /// The same rights, but with king and rooks starting on the given
+    /// [origins].
+    pub const fn with_origins(self, origins: CastlingOrigins) -> Self {
+        Self { origins, ..self }
+    }
+
+    pub fn origins(&self) -> CastlingOrigins {
+        self.origins
+    }
+
+    /// The cell the king of [player] resides on as long as it may castle.
+    pub fn king_origin(&self, player: Player) -> Coordinate {
+        Coordinate::new(self.origins.king, player.back_rank())
+    }
+
+    /// The cell the rook of [player] castling towards [side] resides on as
+    /// long as it may castle.
+    pub fn rook_origin(&self, player: Player, side: CastleSide) -> Coordinate {
+        let x = match side {
+            CastleSide::KingSide => self.origins.king_side_rook,
+            CastleSide::QueenSide => self.origins.queen_side_rook,
+        };
+
+        Coordinate::new(x, player.back_rank())
+    }
+
+    /// Determines the side [player] castles towards, if moving their king
+    /// from [from] to [to] represents castling.
+    ///
+    /// Castling can be expressed in two ways: by moving the king two cells
+    /// towards the rook, as in standard chess, or by moving it onto the
+    /// rook, which is unambiguous in Chess960 as well.
+    pub fn castle_side(
+        &self,
+        player: Player,
+        from: Coordinate,
+        to: Coordinate,
+    ) -> Option<CastleSide> {
+        if from != self.king_origin(player) {
+            return None;
+        }
+
+        [CastleSide::KingSide, CastleSide::QueenSide]
+            .into_iter()
+            .filter(|side| self.allows(player, *side))
+            .find(|side| {
+                let destination = side.king_destination(player);
+                let is_two_cell_move = to == destination && from.x.abs_diff(to.x) == 2;
+
+                is_two_cell_move || to == self.rook_origin(player, *side)
+            })
+    }
+
+    /// The move [player] castles towards [side] with, see [Self::castle_side].
+    ///
+    /// The king moves two cells whenever that is possible, so standard chess
+    /// keeps its usual notation. Otherwise, e.g. if the king is already on
+    /// its destination in Chess960, it moves onto the rook.
+    pub fn castling_move(&self, player: Player, side: CastleSide) -> Move {
+        let from = self.king_origin(player);
+        let destination = side.king_destination(player);
+
+        match from.x.abs_diff(destination.x) == 2 {
+            true => Move::simple(from, destination),
+            false => Move::simple(from, self.rook_origin(player, side)),
+        }
+    }
+
+    pub fn of(&self, player: Player) -> CastleDirections {
+        match player {
+            Color::White => self.white,
+            Color::Black => self.black,
+        }
+    }
+
+    fn of_mut(&mut self, player: Player) -> &mut CastleDirections {
+        match player {
+            Color::White => &mut self.white,
+            Color::Black => &mut self.black,
+        }
+    }
+
+    /// Whether [player] may still castle towards [side].
+    ///
+    /// Note that this only reflects whether the king or the rook have been
+    /// moved. Whether the path is free or attacked is checked separately.
+    pub fn allows(&self, player: Player, side: CastleSide) -> bool {
+        let directions = self.of(player);
+        match side {
+            CastleSide::KingSide => directions.king_side,
+            CastleSide::QueenSide => directions.queen_side,
+        }
+    }
+
+    pub fn grant(&mut self, player: Player, side: CastleSide) {
+        let directions = self.of_mut(player);
+        match side {
+            CastleSide::KingSide => directions.king_side = true,
+            CastleSide::QueenSide => directions.queen_side = true,
+        }
+    }
+
+    pub fn revoke(&mut self, player: Player, side: CastleSide) {
+        let directions = self.of_mut(player);
+        match side {
+            CastleSide::KingSide => directions.king_side = false,
+            CastleSide::QueenSide => directions.queen_side = false,
         }
     }
+
+    /// Computes the rights that remain after a piece moved from [from] to [to].
+    ///
+    /// Moving the king away revokes both directions, while moving a rook away
+    /// from or capturing a rook on its initial cell only revokes the side the
+    /// rook belongs to.
+    pub fn after_move(&self, from: Coordinate, to: Coordinate) -> Self {
+        let mut rights = *self;
+
+        for player in [Color::White, Color::Black] {
+            let king = self.king_origin(player);
+            if from == king || to == king {
+                rights.revoke(player, CastleSide::KingSide);
+                rights.revoke(player, CastleSide::QueenSide);
+            }
+
+            for side in [CastleSide::KingSide, CastleSide::QueenSide] {
+                let rook = self.rook_origin(player, side);
+                if from == rook || to == rook {
+                    rights.revoke(player, side);
+                }
+            }
+        }
+
+        rights
+    }
 }
 
 #[derive(Tsify, Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
@@ -199,21 +418,277 @@ impl CastleDirections {
             king_side: true,
         }
     }
+
+    pub const fn none() -> Self {
+        Self {
+            queen_side: false,
+            king_side: false,
+        }
+    }
+}
+
+/// The files king and rooks start on, which are the same for both players.
+#[derive(Tsify, Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct CastlingOrigins {
+    pub king: usize,
+    pub king_side_rook: usize,
+    pub queen_side_rook: usize,
+}
+
+impl CastlingOrigins {
+    pub const STANDARD: Self = Self {
+        king: 4,
+        king_side_rook: BOARD_SIZE - 1,
+        queen_side_rook: 0,
+    };
+}
+
+impl Default for CastlingOrigins {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+/// The direction a king castles towards.
+#[derive(Tsify, Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum CastleSide {
+    /// Also known as "short" castling (O-O).
+    KingSide,
+    /// Also known as "long" castling (O-O-O).
+    QueenSide,
+}
+
+impl CastleSide {
+    /// The cell the king of [player] ends up on after castling, which is the
+    /// same in Chess960.
+    pub fn king_destination(self, player: Player) -> Coordinate {
+        match self {
+            CastleSide::KingSide => Coordinate::new(6, player.back_rank()),
+            CastleSide::QueenSide => Coordinate::new(2, player.back_rank()),
+        }
+    }
+
+    /// The cell the rook of [player] ends up on after castling.
+    pub fn rook_destination(self, player: Player) -> Coordinate {
+        match self {
+            CastleSide::KingSide => Coordinate::new(5, player.back_rank()),
+            CastleSide::QueenSide => Coordinate::new(3, player.back_rank()),
+        }
+    }
+}
+
+/// The pieces a player captured in Crazyhouse, which they may drop back onto
+/// the board instead of moving.
+#[derive(Tsify, Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct Pocket {
+    /// The number of pieces per figure, in the order of [Figure::STANDARD].
+    counts: [u8; 6],
+}
+
+impl Pocket {
+    pub fn count(&self, figure: Figure) -> u8 {
+        self.counts.get(figure as usize).copied().unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(|count| *count == 0)
+    }
+
+    /// The figures of which at least one piece is in the pocket.
+    pub fn figures(&self) -> impl Iterator<Item = Figure> + '_ {
+        [
+            Figure::Queen,
+            Figure::Rook,
+            Figure::Bishop,
+            Figure::Knight,
+            Figure::Pawn,
+        ]
+        .into_iter()
+        .filter(|figure| self.count(*figure) > 0)
+    }
+
+    /// Puts a piece of [figure] into the pocket.
+    ///
+    /// Panics for figures outside of [Figure::STANDARD], which never reside
+    /// on a [State] and thus can't be captured.
+    pub fn add(&mut self, figure: Figure) {
+        self.counts[figure as usize] += 1;
+    }
+
+    /// Takes a piece of [figure] out of the pocket, if there is one.
+    pub fn remove(&mut self, figure: Figure) -> bool {
+        match self.counts.get_mut(figure as usize) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The [Pocket]s of both players.
+#[derive(Tsify, Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct Pockets {
+    pub white: Pocket,
+    pub black: Pocket,
+}
+
+impl Pockets {
+    pub fn of(&self, player: Player) -> &Pocket {
+        match player {
+            Color::White => &self.white,
+            Color::Black => &self.black,
+        }
+    }
+
+    pub fn of_mut(&mut self, player: Player) -> &mut Pocket {
+        match player {
+            Color::White => &mut self.white,
+            Color::Black => &mut self.black,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.white.is_empty() && self.black.is_empty()
+    }
+}
+
+/// The number of checks each player gave, which decides Three-check.
+#[derive(Tsify, Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct Checks {
+    pub white: u8,
+    pub black: u8,
 }
 
+impl Checks {
+    /// The number of checks [player] gave.
+    pub fn of(&self, player: Player) -> u8 {
+        match player {
+            Color::White => self.white,
+            Color::Black => self.black,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.white == 0 && self.black == 0
+    }
+
+    pub fn add(&mut self, player: Player) {
+        match player {
+            Color::White => self.white += 1,
+            Color::Black => self.black += 1,
+        }
+    }
+
+    /// Takes back a check given by [player].
+    pub fn remove(&mut self, player: Player) {
+        match player {
+            Color::White => self.white -= 1,
+            Color::Black => self.black -= 1,
+        }
+    }
+}
+
+/// A position of a game.
+///
+/// Everything that identifies the position is only readable from outside the
+/// crate, since it is cached in the [State::zobrist_key] and the bitboards,
+/// which changing it directly would leave out of sync.
 #[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "SerializedState")]
 pub struct State {
-    pub player: Player,
+    pub(crate) player: Player,
     #[tsify(type = "(Piece | null)[][]")]
-    pub board: Board,
-    pub castling_rights: CastlingRights,
+    pub(crate) board: Board,
+    pub(crate) castling_rights: CastlingRights,
     /// The cell that a pawn could move to, in order to capture the passing
     /// piece using the "en passant" special rule.
-    pub en_passant_target: Option<Coordinate>,
-    pub halfmove_clock: u8,
-    pub fullmove_number: u8,
+    pub(crate) en_passant_target: Option<Coordinate>,
+    /// The number of halfmoves since the last capture or pawn move.
+    pub halfmove_clock: u16,
+    pub fullmove_number: u16,
+    /// The pieces each player may drop onto the board in Crazyhouse.
+    #[serde(default)]
+    #[tsify(optional)]
+    pub(crate) pockets: Pockets,
+    /// The cells of pieces that were promoted from pawns, which turn back
+    /// into pawns when captured in Crazyhouse.
+    #[serde(default)]
+    #[tsify(optional, type = "Coordinate[]")]
+    pub(crate) promoted: Bitboard,
+    /// The checks each player gave so far in Three-check.
+    #[serde(default)]
+    #[tsify(optional)]
+    pub(crate) checks: Checks,
+    /// Identifies the position, see [State::zobrist_key]. It is not exposed
+    /// to JavaScript, since its numbers can't represent all 64 bit values.
+    #[serde(skip)]
+    pub(crate) zobrist_key: u64,
+    /// The same pieces as on [State::board], but in a form that allows to
+    /// compute moves and attacks quickly.
+    #[serde(skip)]
+    pub(crate) bitboards: Bitboards,
+}
+
+/// The serialized form of a [State], whose derived fields like the Zobrist
+/// key are recomputed when deserializing it.
+#[derive(Deserialize)]
+struct SerializedState {
+    player: Player,
+    board: Board,
+    castling_rights: CastlingRights,
+    en_passant_target: Option<Coordinate>,
+    halfmove_clock: u16,
+    fullmove_number: u16,
+    #[serde(default)]
+    pockets: Pockets,
+    #[serde(default)]
+    promoted: Bitboard,
+    #[serde(default)]
+    checks: Checks,
 }
 
+impl TryFrom<SerializedState> for State {
+    type Error = &'static str;
+
+    fn try_from(value: SerializedState) -> Result<Self, Self::Error> {
+        if !holds_only_standard_figures(&value.board) {
+            return Err(ONLY_STANDARD_FIGURES);
+        }
+
+        Ok(State::new(
+            value.player,
+            value.board,
+            value.castling_rights,
+            value.en_passant_target,
+            value.halfmove_clock,
+            value.fullmove_number,
+        )
+        .with_pockets(value.pockets)
+        .with_promoted(value.promoted)
+        .with_checks(value.checks))
+    }
+}
+
+const ONLY_STANDARD_FIGURES: &str = "A State only holds the figures of standard chess";
+
+fn holds_only_standard_figures(board: &Board) -> bool {
+    board
+        .iter()
+        .flatten()
+        .flatten()
+        .all(|piece| Figure::STANDARD.contains(&piece.figure))
+}
+
+/// After this many halfmoves without a capture or pawn move, the player to
+/// move may claim a draw.
+pub const FIFTY_MOVE_RULE: u16 = 100;
+
+/// After this many halfmoves without a capture or pawn move, the game is
+/// drawn automatically.
+pub const SEVENTY_FIVE_MOVE_RULE: u16 = 150;
+
 impl PartialEq for State {
     fn eq(&self, other: &Self) -> bool {
         if self.player != other.player {
@@ -236,6 +711,13 @@ impl PartialEq for State {
             return false;
         }
 
+        if self.pockets != other.pockets
+            || self.promoted != other.promoted
+            || self.checks != other.checks
+        {
+            return false;
+        }
+
         if self.board.len() != other.board.len() {
             return false;
         }
@@ -259,33 +741,236 @@ impl PartialEq for State {
 }
 
 impl State {
-    pub fn new_turn(
-        &self,
-        new_board: Board,
-        moved: Figure,
-        r#move: Move,
-        did_capture: bool,
+    /// Panics if [board] holds figures outside of [Figure::STANDARD], which
+    /// are only played on a [crate::variants::CapablancaState].
+    pub fn new(
+        player: Player,
+        board: Board,
+        castling_rights: CastlingRights,
+        en_passant_target: Option<Coordinate>,
+        halfmove_clock: u16,
+        fullmove_number: u16,
     ) -> Self {
+        assert!(
+            holds_only_standard_figures(&board),
+            "{ONLY_STANDARD_FIGURES}"
+        );
+
+        Self {
+            player,
+            board,
+            castling_rights,
+            en_passant_target,
+            halfmove_clock,
+            fullmove_number,
+            pockets: Pockets::default(),
+            promoted: Bitboard::EMPTY,
+            checks: Checks::default(),
+            zobrist_key: zobrist::key_of(&board, player, castling_rights, en_passant_target),
+            bitboards: Bitboards::of(&board),
+        }
+    }
+
+    /// The same position, but with the given pieces in the pockets of the
+    /// players.
+    pub fn with_pockets(self, pockets: Pockets) -> Self {
+        let zobrist_key =
+            self.zobrist_key ^ zobrist::pockets_key(self.pockets) ^ zobrist::pockets_key(pockets);
+
+        Self {
+            pockets,
+            zobrist_key,
+            ..self
+        }
+    }
+
+    /// The same position, but with the pieces on [promoted] having been
+    /// promoted from pawns.
+    pub fn with_promoted(self, promoted: Bitboard) -> Self {
+        let zobrist_key = self.zobrist_key
+            ^ zobrist::promoted_key(self.promoted)
+            ^ zobrist::promoted_key(promoted);
+
+        Self {
+            promoted,
+            zobrist_key,
+            ..self
+        }
+    }
+
+    /// The same position, but with the given number of checks given by the
+    /// players.
+    pub fn with_checks(self, checks: Checks) -> Self {
+        let zobrist_key =
+            self.zobrist_key ^ zobrist::checks_key(self.checks) ^ zobrist::checks_key(checks);
+
+        Self {
+            checks,
+            zobrist_key,
+            ..self
+        }
+    }
+
+    /// Hands the turn to the opponent, after a [moved] piece made [r#move].
+    ///
+    /// This only updates the castling rights, en passant target and clocks,
+    /// the pieces on the board are expected to already be in place.
+    pub(crate) fn advance_turn(&mut self, moved: Figure, r#move: Move, did_capture: bool) {
         let from = r#move.from;
         let to = r#move.to;
 
-        Self {
-            player: self.player.switch(),
-            board: new_board,
-            castling_rights: self.castling_rights, // TODO
-            en_passant_target: match moved == Figure::Pawn && from.y.abs_diff(to.y) == 2 {
-                true => Some(r#move.to.backward(self.player, 1).unwrap()),
-                false => None,
-            },
-            halfmove_clock: match did_capture || moved == Figure::Pawn {
-                true => 0,
-                false => self.halfmove_clock + 1,
-            },
-            fullmove_number: match self.player {
-                Color::White => self.fullmove_number,
-                Color::Black => self.fullmove_number + 1,
-            },
+        let castling_rights = self.castling_rights.after_move(from, to);
+        let en_passant_target = match moved == Figure::Pawn && from.y.abs_diff(to.y) == 2 {
+            true => Some(r#move.to.backward(self.player, 1).unwrap()),
+            false => None,
+        };
+
+        self.zobrist_key ^= zobrist::player_key(Color::Black)
+            ^ zobrist::castling_key(self.castling_rights)
+            ^ zobrist::castling_key(castling_rights)
+            ^ zobrist::en_passant_key(self.en_passant_target)
+            ^ zobrist::en_passant_key(en_passant_target);
+
+        self.halfmove_clock = match did_capture || moved == Figure::Pawn {
+            true => 0,
+            false => self.halfmove_clock + 1,
+        };
+        if self.player == Color::Black {
+            self.fullmove_number += 1;
+        }
+        self.player = self.player.switch();
+        self.castling_rights = castling_rights;
+        self.en_passant_target = en_passant_target;
+    }
+
+    /// Replaces the castling rights, keeping the Zobrist key in sync.
+    pub(crate) fn set_castling_rights(&mut self, castling_rights: CastlingRights) {
+        self.zobrist_key ^=
+            zobrist::castling_key(self.castling_rights) ^ zobrist::castling_key(castling_rights);
+        self.castling_rights = castling_rights;
+    }
+
+    /// Replaces the cells of promoted pieces, keeping the Zobrist key in sync.
+    pub(crate) fn set_promoted(&mut self, promoted: Bitboard) {
+        self.zobrist_key ^= zobrist::promoted_key(self.promoted) ^ zobrist::promoted_key(promoted);
+        self.promoted = promoted;
+    }
+
+    /// Counts another check given by [player], keeping the Zobrist key in
+    /// sync.
+    pub(crate) fn count_check(&mut self, player: Player) {
+        let mut checks = self.checks;
+        checks.add(player);
+        self.zobrist_key ^= zobrist::checks_key(self.checks) ^ zobrist::checks_key(checks);
+        self.checks = checks;
+    }
+
+    /// Takes back a check counted by [State::count_check], keeping the
+    /// Zobrist key in sync.
+    pub(crate) fn uncount_check(&mut self, player: Player) {
+        let mut checks = self.checks;
+        checks.remove(player);
+        self.zobrist_key ^= zobrist::checks_key(self.checks) ^ zobrist::checks_key(checks);
+        self.checks = checks;
+    }
+
+    /// Puts [piece] onto the empty cell [at], keeping the derived
+    /// representations of the board in sync.
+    pub(crate) fn place(&mut self, piece: Piece, at: Coordinate) {
+        debug_assert!(self.board[at.y][at.x].is_none(), "{at:?} is occupied");
+        self.board[at.y][at.x] = Some(piece);
+        self.bitboards.toggle(piece, at);
+        self.zobrist_key ^= zobrist::piece_key(piece, at);
+    }
+
+    /// Removes the piece residing on [at], if any, keeping the derived
+    /// representations of the board in sync.
+    pub(crate) fn take(&mut self, at: Coordinate) -> Option<Piece> {
+        let piece = self.board[at.y][at.x].take()?;
+        self.bitboards.toggle(piece, at);
+        self.zobrist_key ^= zobrist::piece_key(piece, at);
+
+        Some(piece)
+    }
+
+    /// Puts [piece] into the pocket of its owner, keeping the Zobrist key in
+    /// sync.
+    pub(crate) fn pocket(&mut self, piece: Piece) {
+        let pocket = self.pockets.of_mut(piece.color);
+        let count = pocket.count(piece.figure);
+        pocket.add(piece.figure);
+        self.zobrist_key ^=
+            zobrist::pocket_key(piece, count) ^ zobrist::pocket_key(piece, count + 1);
+    }
+
+    /// Takes [piece] out of the pocket of its owner, if it is in there.
+    pub(crate) fn unpocket(&mut self, piece: Piece) -> bool {
+        let pocket = self.pockets.of_mut(piece.color);
+        let count = pocket.count(piece.figure);
+        if !pocket.remove(piece.figure) {
+            return false;
         }
+
+        self.zobrist_key ^=
+            zobrist::pocket_key(piece, count) ^ zobrist::pocket_key(piece, count - 1);
+        true
+    }
+
+    /// The player to move.
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn castling_rights(&self) -> CastlingRights {
+        self.castling_rights
+    }
+
+    /// The cell that a pawn could move to, in order to capture the passing
+    /// piece using the "en passant" special rule.
+    pub fn en_passant_target(&self) -> Option<Coordinate> {
+        self.en_passant_target
+    }
+
+    /// The pieces each player may drop onto the board in Crazyhouse.
+    pub fn pockets(&self) -> &Pockets {
+        &self.pockets
+    }
+
+    /// The cells of pieces that were promoted from pawns, which turn back
+    /// into pawns when captured in Crazyhouse.
+    pub fn promoted(&self) -> Bitboard {
+        self.promoted
+    }
+
+    /// The checks each player gave so far in Three-check.
+    pub fn checks(&self) -> Checks {
+        self.checks
+    }
+
+    /// A 64 bit hash of the position, which is updated incrementally as moves
+    /// are made.
+    ///
+    /// It covers the pieces on the board, the player to move, the castling
+    /// rights, the column of the en passant target, the pockets and promoted
+    /// pieces of Crazyhouse and the checks given in Three-check, but not the
+    /// move counters. Equal positions therefore always share a key, while
+    /// different positions only do so with negligible probability.
+    pub fn zobrist_key(&self) -> u64 {
+        self.zobrist_key
+    }
+
+    /// Whether the current player may claim a draw by the fifty-move rule.
+    pub fn can_claim_fifty_move_draw(&self) -> bool {
+        self.halfmove_clock >= FIFTY_MOVE_RULE
+    }
+
+    /// Whether the game is drawn by the seventy-five-move rule.
+    pub fn is_seventy_five_move_draw(&self) -> bool {
+        self.halfmove_clock >= SEVENTY_FIVE_MOVE_RULE
     }
 
     pub fn opponent(&self) -> Player {
@@ -295,6 +980,7 @@ impl State {
     pub fn reversed(&self) -> State {
         State {
             player: self.opponent(),
+            zobrist_key: self.zobrist_key ^ zobrist::player_key(Color::Black),
             ..*self
         }
     }
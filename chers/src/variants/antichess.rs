@@ -0,0 +1,193 @@
+use crate::{
+    bitboard::Bitboard, movement_patterns, moves_available::legal_moves_by, CastlingRights, Color,
+    Coordinate, DrawReason, Event, Figure, Move, Piece, PromotedFigure, State, WinReason,
+    INITIAL_BOARD,
+};
+
+use super::Variant;
+
+/// Pawns may be promoted to kings as well, since they are ordinary pieces.
+const PROMOTIONS: [PromotedFigure; 5] = [
+    PromotedFigure::Queen,
+    PromotedFigure::Rook,
+    PromotedFigure::Bishop,
+    PromotedFigure::Knight,
+    PromotedFigure::King,
+];
+
+/// Also known as losing chess: whoever loses all of their pieces or can't
+/// move anymore wins.
+///
+/// Capturing is compulsory whenever possible. There is no check, so the king
+/// is an ordinary piece which may be captured, and there is no castling.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Antichess;
+
+impl Variant for Antichess {
+    fn name(&self) -> &'static str {
+        "Antichess"
+    }
+
+    fn start(&self) -> State {
+        State::new(
+            Color::White,
+            INITIAL_BOARD,
+            CastlingRights::none(),
+            None,
+            0,
+            1,
+        )
+    }
+
+    fn legal_moves(&self, state: &State) -> Vec<Move> {
+        legal_moves_by(self, state)
+    }
+
+    fn legal_targets(&self, state: &State, from: Coordinate) -> Bitboard {
+        let Some(piece) = state.bitboards.piece_at(from) else {
+            return Bitboard::EMPTY;
+        };
+        if piece.color != state.player {
+            return Bitboard::EMPTY;
+        }
+
+        let targets = movement_patterns::of(state, from, piece);
+        match must_capture(state) {
+            true => captures(state, piece, targets),
+            false => targets,
+        }
+    }
+
+    fn promotions(&self) -> &'static [PromotedFigure] {
+        &PROMOTIONS
+    }
+
+    fn outcome_events(&self, state: &State) -> Vec<Event> {
+        if state.bitboards.color(state.player).is_empty() {
+            return vec![Event::Win {
+                winner: state.player,
+                reason: WinReason::NoPiecesLeft,
+            }];
+        }
+
+        if self.legal_moves(state).is_empty() {
+            return vec![Event::Win {
+                winner: state.player,
+                reason: WinReason::Stalemated,
+            }];
+        }
+
+        // Even a lone king can still win by being captured, so there is no
+        // such thing as insufficient material.
+        if state.is_seventy_five_move_draw() {
+            vec![Event::Draw {
+                reason: DrawReason::SeventyFiveMoveRule,
+            }]
+        } else if state.can_claim_fifty_move_draw() {
+            vec![Event::DrawClaimable {
+                reason: DrawReason::FiftyMoveRule,
+            }]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Losing all pieces also leaves no legal moves.
+    fn has_ended(&self, state: &State, legal_moves: &[Move]) -> bool {
+        legal_moves.is_empty() || state.is_seventy_five_move_draw()
+    }
+}
+
+/// Whether any piece of the current player can capture, which then is the
+/// only kind of move allowed.
+fn must_capture(state: &State) -> bool {
+    state.bitboards.color(state.player).into_iter().any(|from| {
+        let Some(piece) = state.bitboards.piece_at(from) else {
+            return false;
+        };
+
+        !captures(state, piece, movement_patterns::of(state, from, piece)).is_empty()
+    })
+}
+
+/// The [targets] of [piece] on which it would capture.
+fn captures(state: &State, piece: Piece, targets: Bitboard) -> Bitboard {
+    let mut capturable = state.bitboards.color(state.opponent());
+    if let Some(en_passant) = state
+        .en_passant_target
+        .filter(|_| piece.figure == Figure::Pawn)
+    {
+        capturable |= Bitboard::of(en_passant);
+    }
+
+    targets & capturable
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fen::parse_state, move_piece_by, perft::perft_by, Cell};
+
+    use super::*;
+
+    #[test]
+    fn it_generates_the_published_node_counts() {
+        let state = Antichess.start();
+
+        for (depth, nodes) in [20, 400, 8_067, 153_299].into_iter().enumerate() {
+            assert_eq!(nodes, perft_by(&Antichess, &state, depth + 1));
+        }
+    }
+
+    #[test]
+    fn captures_are_compulsory() {
+        let state = parse_state("4k3/8/8/3p4/4P3/8/8/R3K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            vec![Move::simple(Cell::E4, Cell::D5)],
+            Antichess.legal_moves(&state)
+        );
+
+        // Kings may be captured, and may capture themselves
+        let state = parse_state("8/8/8/8/8/8/3k4/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            vec![Move::simple(Cell::E1, Cell::D2)],
+            Antichess.legal_moves(&state)
+        );
+    }
+
+    #[test]
+    fn pawns_may_be_promoted_to_kings() {
+        let state = parse_state("8/P7/8/8/8/8/8/k7 w - - 0 1").unwrap();
+        let promotion = Move::new(Cell::A7, Cell::A8, Some(PromotedFigure::King));
+
+        assert!(Antichess.legal_moves(&state).contains(&promotion));
+        assert!(move_piece_by(&Antichess, &state, promotion).is_ok());
+        assert!(move_piece_by(&crate::variants::Standard, &state, promotion).is_err());
+    }
+
+    #[test]
+    fn losing_all_pieces_or_being_stalemated_wins() {
+        let state = parse_state("8/8/8/8/8/8/3k4/4K3 w - - 0 1").unwrap();
+        let (_, events) =
+            move_piece_by(&Antichess, &state, Move::simple(Cell::E1, Cell::D2)).unwrap();
+        assert_eq!(
+            Some(&Event::Win {
+                winner: Color::Black,
+                reason: WinReason::NoPiecesLeft
+            }),
+            events.last()
+        );
+
+        // The black pawn is blocked, so black can't move anymore
+        let state = parse_state("8/8/8/8/8/p7/P7/7R w - - 0 1").unwrap();
+        let (_, events) =
+            move_piece_by(&Antichess, &state, Move::simple(Cell::H1, Cell::H2)).unwrap();
+        assert_eq!(
+            Some(&Event::Win {
+                winner: Color::Black,
+                reason: WinReason::Stalemated
+            }),
+            events.last()
+        );
+    }
+}
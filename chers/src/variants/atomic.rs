@@ -0,0 +1,328 @@
+use crate::{
+    bitboard::{attacks, attacks::is_attacked, Bitboard, Bitboards},
+    check::attackers_of,
+    move_execution::{self, requires_promotion, CantMovePiece, Undo},
+    movement_patterns,
+    moves_available::legal_moves_by,
+    piece_at, Coordinate, DrawReason, Event, Figure, Move, Piece, Player, PromotedFigure, State,
+};
+
+use super::Variant;
+
+/// Every capture blows up the capturing piece together with every piece but
+/// pawns on the cells around the captured one. Blowing up the king of the
+/// opponent wins the game.
+///
+/// Kings can't capture, since they would blow themselves up, and a king next
+/// to the other one can't be in check, since capturing it would blow up both.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Atomic;
+
+impl Variant for Atomic {
+    fn name(&self) -> &'static str {
+        "Atomic"
+    }
+
+    fn legal_moves(&self, state: &State) -> Vec<Move> {
+        legal_moves_by(self, state)
+    }
+
+    fn legal_targets(&self, state: &State, from: Coordinate) -> Bitboard {
+        let Some(piece) = state.bitboards.piece_at(from) else {
+            return Bitboard::EMPTY;
+        };
+        if piece.color != state.player {
+            return Bitboard::EMPTY;
+        }
+
+        movement_patterns::of(state, from, piece)
+            .into_iter()
+            .filter(|to| self.is_legal(state, from, piece, *to))
+            .collect()
+    }
+
+    fn make_move(&self, state: &mut State, the_move: Move) -> Result<Undo, CantMovePiece> {
+        let mut undo = move_execution::make_move(state, the_move)?;
+        if undo.captured.is_none() {
+            return Ok(undo);
+        }
+
+        let to = the_move.to;
+        let mut exploded = Exploded::default();
+        let mut castling_rights = state.castling_rights;
+        for at in attacks::king(to) | Bitboard::of(to) {
+            let Some(piece) = piece_at(at, &state.board) else {
+                continue;
+            };
+            if at != to && piece.figure == Figure::Pawn {
+                continue;
+            }
+
+            state.take(at);
+            exploded.push(at, piece);
+            // Blowing up a king or rook is just like capturing it
+            castling_rights = castling_rights.after_move(at, at);
+        }
+
+        state.set_promoted(state.promoted & !exploded.cells());
+        state.set_castling_rights(castling_rights);
+        undo.extra = Some(Box::new(exploded));
+
+        Ok(undo)
+    }
+
+    fn unmake_move(&self, state: &mut State, undo: Undo) {
+        if let Some(exploded) = undo.extra::<Exploded>() {
+            for (at, piece) in exploded.pieces() {
+                state.place(piece, at);
+            }
+        }
+
+        move_execution::unmake_move(state, undo);
+    }
+
+    fn special_events(&self, _before: &State, undo: &Undo, _after: &State) -> Vec<Event> {
+        let Some(exploded) = undo.extra::<Exploded>() else {
+            return Vec::new();
+        };
+
+        vec![Event::Explosion {
+            at: undo.the_move.to,
+            removed: exploded.pieces().collect(),
+        }]
+    }
+
+    fn outcome_events(&self, state: &State) -> Vec<Event> {
+        // Blowing up the king ends the game just like mating it
+        let Some(king) = state.bitboards.king_of(state.player) else {
+            return vec![Event::Mate];
+        };
+
+        let mut events = Vec::new();
+
+        let in_check = is_in_check(&state.bitboards, king, state.player);
+        if in_check {
+            events.push(Event::Check {
+                by: attackers_of(state, king, state.opponent()),
+            });
+        }
+
+        if self.legal_moves(state).is_empty() {
+            events.push(match in_check {
+                true => Event::Mate,
+                false => Event::Stalemate,
+            });
+            return events;
+        }
+
+        // Kings can't capture, so two lone kings can't ever win.
+        if state.bitboards.occupied().len() == 2 {
+            events.push(Event::Draw {
+                reason: DrawReason::InsufficientMaterial,
+            });
+        } else if state.is_seventy_five_move_draw() {
+            events.push(Event::Draw {
+                reason: DrawReason::SeventyFiveMoveRule,
+            });
+        } else if state.can_claim_fifty_move_draw() {
+            events.push(Event::DrawClaimable {
+                reason: DrawReason::FiftyMoveRule,
+            });
+        }
+
+        events
+    }
+
+    fn has_ended(&self, state: &State, legal_moves: &[Move]) -> bool {
+        state.bitboards.king_of(state.player).is_none()
+            || legal_moves.is_empty()
+            || state.bitboards.occupied().len() == 2
+            || state.is_seventy_five_move_draw()
+    }
+}
+
+impl Atomic {
+    /// Whether [piece] may move from [from] to one of the cells its movement
+    /// pattern allows.
+    ///
+    /// Explosions may remove any piece around the target, so instead of only
+    /// relocating pieces like the standard rules do, the move is executed on a
+    /// copy of the state.
+    fn is_legal(&self, state: &State, from: Coordinate, piece: Piece, to: Coordinate) -> bool {
+        let castled = match piece.figure {
+            Figure::King => state.castling_rights.castle_side(piece.color, from, to),
+            _ => None,
+        };
+        if let Some(side) = castled {
+            let passes_check = Bitboard::row_span(from, side.king_destination(piece.color))
+                .into_iter()
+                .any(|passed| is_in_check(&state.bitboards, passed, state.player));
+            if passes_check {
+                return false;
+            }
+        }
+
+        // Which figure a pawn is promoted to does not matter, since every one
+        // of them blows up the same way.
+        let promotion = requires_promotion(state, piece, to).then_some(PromotedFigure::Queen);
+        let mut after = state.clone();
+        if self
+            .make_move(&mut after, Move::new(from, to, promotion))
+            .is_err()
+        {
+            return false;
+        }
+
+        let Some(king) = after.bitboards.king_of(state.player) else {
+            return false;
+        };
+        if after.bitboards.king_of(state.opponent()).is_none() {
+            return true;
+        }
+
+        !is_in_check(&after.bitboards, king, state.player)
+    }
+}
+
+/// Whether a king of [player] residing on [king] would be in check, which it
+/// never is next to the king of the opponent.
+fn is_in_check(bitboards: &Bitboards, king: Coordinate, player: Player) -> bool {
+    let touches_other_king = bitboards
+        .king_of(player.other())
+        .is_some_and(|other| attacks::king(king).contains(other));
+
+    !touches_other_king && is_attacked(bitboards, king, player.other())
+}
+
+/// The pieces an explosion removed, which [Atomic::make_move] records in
+/// [Undo::extra] for [Atomic::unmake_move] to put back.
+///
+/// An explosion removes at most the capturing piece and its eight neighbours,
+/// so this fits into a fixed array.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Exploded {
+    cells: Bitboard,
+    /// The pieces in the order of [Exploded::cells].
+    pieces: [Option<Piece>; 9],
+}
+
+impl Exploded {
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn cells(&self) -> Bitboard {
+        self.cells
+    }
+
+    /// The removed pieces and the cells they resided on.
+    pub fn pieces(&self) -> impl Iterator<Item = (Coordinate, Piece)> + '_ {
+        self.cells
+            .into_iter()
+            .zip(self.pieces.iter().flatten().copied())
+    }
+
+    /// Records [piece] as removed from [at], which must come after every
+    /// cell recorded so far in the order of [Bitboard]s.
+    fn push(&mut self, at: Coordinate, piece: Piece) {
+        self.pieces[self.cells.len()] = Some(piece);
+        self.cells |= Bitboard::of(at);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fen::parse_state,
+        move_piece_by,
+        moves_available::{autocomplete_to, autocomplete_to_by},
+        zobrist, Cell, Color,
+    };
+
+    use super::*;
+
+    #[test]
+    fn captures_blow_up_everything_around_but_pawns() {
+        let state = parse_state("4k3/8/3b1p2/4p3/3P1R2/5N2/8/4K3 w - - 0 1").unwrap();
+
+        let (after, events) =
+            move_piece_by(&Atomic, &state, Move::simple(Cell::F3, Cell::E5)).unwrap();
+
+        assert!(events.contains(&Event::Explosion {
+            at: Cell::E5,
+            removed: vec![
+                (Cell::D6, Piece::black(Figure::Bishop)),
+                (Cell::E5, Piece::white(Figure::Knight)),
+                (Cell::F4, Piece::white(Figure::Rook)),
+            ],
+        }));
+        for cell in [Cell::D6, Cell::E5, Cell::F4] {
+            assert_eq!(None, piece_at(cell, &after.board), "{cell:?}");
+        }
+        assert_eq!(
+            Some(Piece::black(Figure::Pawn)),
+            piece_at(Cell::F6, &after.board)
+        );
+        assert_eq!(
+            Some(Piece::white(Figure::Pawn)),
+            piece_at(Cell::D4, &after.board)
+        );
+    }
+
+    #[test]
+    fn blowing_up_the_king_wins_even_in_check() {
+        let state = parse_state("4k3/3p4/8/8/8/8/8/3QK2r w - - 0 1").unwrap();
+
+        let (after, events) =
+            move_piece_by(&Atomic, &state, Move::simple(Cell::D1, Cell::D7)).unwrap();
+
+        assert_eq!(None, after.bitboards.king_of(Color::Black));
+        assert!(events.contains(&Event::Mate));
+    }
+
+    #[test]
+    fn kings_may_touch_but_never_blow_themselves_up() {
+        // The rook attacks the king, but capturing it would blow up both kings
+        let state = parse_state("8/8/8/8/8/8/4k3/r3K3 w - - 0 1").unwrap();
+        assert!(Atomic.outcome_events(&state).is_empty());
+
+        let moves = autocomplete_to_by(&Atomic, &state, Cell::E1);
+        assert!(moves.contains(&Cell::D1));
+        assert!(!moves.contains(&Cell::E2));
+
+        // Capturing next to the own king, or with it, is not allowed
+        let state = parse_state("4k3/8/8/8/8/8/3n4/3RK3 w - - 0 1").unwrap();
+        assert!(autocomplete_to(&state, Cell::D1).contains(&Cell::D2));
+        assert!(!autocomplete_to_by(&Atomic, &state, Cell::D1).contains(&Cell::D2));
+        assert!(!autocomplete_to_by(&Atomic, &state, Cell::E1).contains(&Cell::D2));
+    }
+
+    #[test]
+    fn explosions_can_be_made_and_unmade_in_place() {
+        let original =
+            parse_state("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let mut state = original.clone();
+
+        for the_move in Atomic.legal_moves(&original) {
+            let undo = Atomic.make_move(&mut state, the_move).unwrap();
+
+            let (expected, _) = move_piece_by(&Atomic, &original, the_move).unwrap();
+            assert_eq!(expected, state, "after {the_move:?}");
+            assert_eq!(
+                zobrist::key_of(
+                    &state.board,
+                    state.player,
+                    state.castling_rights,
+                    state.en_passant_target
+                ),
+                state.zobrist_key(),
+                "after {the_move:?}"
+            );
+
+            Atomic.unmake_move(&mut state, undo);
+            assert_eq!(original, state, "after taking back {the_move:?}");
+            assert_eq!(original.zobrist_key(), state.zobrist_key());
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use crate::{CantMovePiece, Event, Move, PromotedFigure};
+
+use super::{Position, Variant};
+
+mod moves;
+mod state;
+
+pub use moves::CapablancaUndo;
+pub use state::CapablancaState;
+
+/// The figures a pawn may be promoted to in Capablanca chess.
+pub const CAPABLANCA_PROMOTIONS: [PromotedFigure; 6] = [
+    PromotedFigure::Queen,
+    PromotedFigure::Chancellor,
+    PromotedFigure::Archbishop,
+    PromotedFigure::Rook,
+    PromotedFigure::Bishop,
+    PromotedFigure::Knight,
+];
+
+/// Standard chess on a 10x8 board, which adds the Archbishop, moving like a
+/// bishop or a knight, and the Chancellor, moving like a rook or a knight.
+///
+/// Its positions are [CapablancaState]s rather than [crate::State]s, which
+/// only cover 8x8 boards. It is thus not one of the [super::VariantKind]s and
+/// can't be played through [crate::Game], PGN or the server. Moves are made
+/// using [Capablanca::move_piece] instead.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Capablanca;
+
+impl Capablanca {
+    /// Executes [the_move] if it is legal, and reports what happened like
+    /// [crate::move_piece_by] does for a [crate::State].
+    pub fn move_piece(
+        &self,
+        state: &CapablancaState,
+        the_move: Move,
+    ) -> Result<(CapablancaState, Vec<Event>), CantMovePiece> {
+        let moved = state.check_move(the_move)?;
+        let legal = state.legal_targets(the_move.from);
+        if !legal.contains(&the_move.to) {
+            return Err(CantMovePiece::IllegalMove {
+                attempted: the_move,
+                legal,
+            });
+        }
+
+        let mut new_state = state.clone();
+        let undo = new_state.apply(the_move);
+
+        let mut events = vec![Event::Move {
+            piece: moved,
+            from: the_move.from,
+            to: the_move.to,
+        }];
+        if let Some((_, captured)) = undo.captured {
+            events.push(Event::Capture {
+                at: the_move.to,
+                captured,
+                by: moved,
+            });
+        }
+        if let Some(promoted) = the_move
+            .promotion
+            .filter(|_| state.requires_promotion(moved, the_move.to))
+        {
+            events.push(Event::Promotion { to: promoted });
+        }
+        if let Some(side) = undo.castled {
+            events.push(Event::Castle {
+                side,
+                rook_from: state.rook_origin(state.player, side),
+                rook_to: state.rook_destination(state.player, side),
+            });
+        }
+        events.extend(new_state.outcome_events());
+
+        Ok((new_state, events))
+    }
+}
+
+impl Variant<CapablancaState> for Capablanca {
+    fn name(&self) -> &'static str {
+        "Capablanca"
+    }
+
+    fn promotions(&self) -> &'static [PromotedFigure] {
+        &CAPABLANCA_PROMOTIONS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fen::parse_state, move_piece, perft::perft_by, CantMovePiece, Cell, Color, Coordinate,
+        Event, Figure, Move, Piece,
+    };
+
+    use super::{state::START, *};
+
+    fn assert_perft(notation: &str, expected: &[u64]) {
+        let state = CapablancaState::parse(notation).unwrap();
+
+        for (depth, nodes) in expected.iter().enumerate() {
+            let actual = perft_by(&Capablanca, &state, depth + 1);
+            assert_eq!(*nodes, actual, "depth {}", depth + 1);
+        }
+    }
+
+    #[test]
+    fn capablanca_start_position() {
+        assert_eq!(CapablancaState::parse(START).unwrap(), Capablanca.start());
+        assert_perft(START, &[28, 784, 25_228]);
+    }
+
+    /// Too slow for debug builds. Run it using
+    /// `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn expensive_capablanca_start_position() {
+        assert_perft(START, &[28, 784, 25_228, 805_128]);
+    }
+
+    #[test]
+    fn kings_castle_across_the_wider_board() {
+        let state = CapablancaState::parse("r4k3r/10/10/10/10/10/10/R4K3R w KQkq - 0 1").unwrap();
+        let king = state.king_origin(Color::White);
+        let moves = Capablanca.legal_moves(&state);
+
+        let king_side = Move::simple(king, Coordinate::new(8, 7));
+        let queen_side = Move::simple(king, Cell::C1);
+        assert!(moves.contains(&king_side));
+        assert!(moves.contains(&queen_side));
+
+        let mut after = state.clone();
+        Capablanca.make_move(&mut after, king_side).unwrap();
+        assert_eq!("r4k3r/10/10/10/10/10/10/R6RK1 b kq - 1 1", after.to_fen());
+        let mut after = state.clone();
+        Capablanca.make_move(&mut after, queen_side).unwrap();
+        assert_eq!("r4k3r/10/10/10/10/10/10/2KR5R b kq - 1 1", after.to_fen());
+
+        // The king may not pass the cell the bishop attacks
+        let state = CapablancaState::parse("r4k3r/10/10/10/b9/10/10/R4K3R w KQkq - 0 1").unwrap();
+        let moves = Capablanca.legal_moves(&state);
+        assert!(!moves.contains(&queen_side));
+        assert!(moves.contains(&king_side));
+    }
+
+    #[test]
+    fn pawns_are_promoted_to_fairy_pieces() {
+        let state = CapablancaState::parse("5k4/P9/10/10/10/10/10/5K4 w - - 0 1").unwrap();
+        let a8 = Coordinate::new(0, 0);
+        let promotion = Move::new(Coordinate::new(0, 1), a8, Some(PromotedFigure::Chancellor));
+
+        assert_eq!(
+            5 + CAPABLANCA_PROMOTIONS.len(),
+            Capablanca.legal_moves(&state).len()
+        );
+        let mut after = state.clone();
+        Capablanca.make_move(&mut after, promotion).unwrap();
+        assert_eq!(Some(Piece::white(Figure::Chancellor)), after.piece_at(a8));
+        // The chancellor gives check like a rook
+        assert!(after.is_in_check(Color::Black));
+
+        // Other variants don't know the figure
+        let standard = parse_state("5k2/P7/8/8/8/8/8/5K2 w - - 0 1").unwrap();
+        let promotion = Move::new(Cell::A7, Cell::A8, Some(PromotedFigure::Chancellor));
+        assert!(matches!(
+            move_piece(&standard, promotion),
+            Err(CantMovePiece::IllegalMove { .. })
+        ));
+    }
+
+    #[test]
+    fn only_legal_moves_and_promotions_are_made() {
+        let state = Capablanca.start();
+        let f2 = Coordinate::new(5, 6);
+        let f4 = Coordinate::new(5, 4);
+
+        // Moves that don't reach the last rank ignore the promotion
+        let (after, events) = Capablanca
+            .move_piece(&state, Move::new(f2, f4, Some(PromotedFigure::King)))
+            .unwrap();
+        assert_eq!(Some(Piece::white(Figure::Pawn)), after.piece_at(f4));
+        assert_eq!(
+            vec![Event::Move {
+                piece: Piece::white(Figure::Pawn),
+                from: f2,
+                to: f4
+            }],
+            events
+        );
+        assert!(matches!(
+            Capablanca.move_piece(&state, Move::simple(f2, Coordinate::new(5, 3))),
+            Err(CantMovePiece::IllegalMove { .. })
+        ));
+
+        let state = CapablancaState::parse("5k4/P9/10/10/10/10/10/5K4 w - - 0 1").unwrap();
+        let a7 = Coordinate::new(0, 1);
+        let a8 = Coordinate::new(0, 0);
+        for promotion in [None, Some(PromotedFigure::King)] {
+            let mut after = state.clone();
+            assert!(Capablanca
+                .make_move(&mut after, Move::new(a7, a8, promotion))
+                .is_err());
+            assert_eq!(state, after);
+        }
+
+        let (after, events) = Capablanca
+            .move_piece(&state, Move::new(a7, a8, Some(PromotedFigure::Archbishop)))
+            .unwrap();
+        assert_eq!(Some(Piece::white(Figure::Archbishop)), after.piece_at(a8));
+        assert!(events.contains(&Event::Promotion {
+            to: PromotedFigure::Archbishop
+        }));
+    }
+
+    #[test]
+    fn fairy_pieces_mate() {
+        let mut state = CapablancaState::parse("5k4/10/5K4/10/10/10/10/C9 w - - 0 1").unwrap();
+        Capablanca
+            .make_move(&mut state, Move::simple(Cell::A1, Cell::A8))
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                Event::Check {
+                    by: vec![(Cell::A8, Piece::white(Figure::Chancellor))]
+                },
+                Event::Mate
+            ],
+            Capablanca.outcome_events(&state)
+        );
+    }
+
+    #[test]
+    fn moves_can_be_made_and_unmade_in_place() {
+        let original = CapablancaState::parse(
+            "r4k3r/p1pp1pbc1p/bn2pnp3/3PN5/1p2P5/2N2Q1p2/PPPBBPPPA1/R4K3R w KQkq - 0 1",
+        )
+        .unwrap();
+        let mut state = original.clone();
+
+        for the_move in Capablanca.legal_moves(&original) {
+            let undo = Capablanca.make_move(&mut state, the_move).unwrap();
+            assert_ne!(original, state);
+
+            Capablanca.unmake_move(&mut state, undo);
+            assert_eq!(original, state, "after taking back {the_move:?}");
+        }
+    }
+}
@@ -0,0 +1,514 @@
+use crate::{
+    variants::Position, CantMovePiece, CastleSide, CastlingRights, Color, Coordinate, DrawReason,
+    Event, Figure, Move, Piece, Player, FIFTY_MOVE_RULE, SEVENTY_FIVE_MOVE_RULE,
+};
+
+use super::{
+    state::{offset, START},
+    CapablancaState, CAPABLANCA_PROMOTIONS,
+};
+
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const DIAGONAL: [(isize, isize); 4] = [(1, -1), (1, 1), (-1, 1), (-1, -1)];
+const KNIGHT: [(isize, isize); 8] = [
+    (1, -2),
+    (2, -1),
+    (2, 1),
+    (1, 2),
+    (-1, 2),
+    (-2, 1),
+    (-2, -1),
+    (-1, -2),
+];
+
+/// The figures that slide along ranks and files, along diagonals, or jump
+/// like a knight. A figure may move in several of these ways.
+const ORTHOGONAL_SLIDERS: [Figure; 3] = [Figure::Rook, Figure::Queen, Figure::Chancellor];
+const DIAGONAL_SLIDERS: [Figure; 3] = [Figure::Bishop, Figure::Queen, Figure::Archbishop];
+const JUMPERS: [Figure; 3] = [Figure::Knight, Figure::Archbishop, Figure::Chancellor];
+
+/// What [Position::unmake_move] needs to revert a move made on a
+/// [CapablancaState], like [crate::Undo] does for [crate::State].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CapablancaUndo {
+    pub the_move: Move,
+    pub moved: Piece,
+    /// The captured piece and where it resided, which differs from
+    /// [Move::to] when capturing en passant.
+    pub captured: Option<(Coordinate, Piece)>,
+    pub castled: Option<CastleSide>,
+    castling_rights: CastlingRights,
+    en_passant_target: Option<Coordinate>,
+    halfmove_clock: u16,
+    fullmove_number: u16,
+}
+
+impl Position for CapablancaState {
+    type Cells = Vec<Coordinate>;
+    type Undo = CapablancaUndo;
+
+    fn start() -> Self {
+        CapablancaState::parse(START).expect("the start position is valid")
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        let mut state = self.clone();
+
+        self.pseudo_legal_moves()
+            .into_iter()
+            .filter(|candidate| {
+                let undo = state.apply(*candidate);
+                let is_legal = !state.is_in_check(self.player);
+                state.unmake_move(undo);
+
+                is_legal
+            })
+            .collect()
+    }
+
+    fn legal_targets(&self, from: Coordinate) -> Vec<Coordinate> {
+        let mut targets: Vec<Coordinate> = self
+            .legal_moves()
+            .into_iter()
+            .filter(|the_move| the_move.from == from)
+            .map(|the_move| the_move.to)
+            .collect();
+        // Each promotion is a move of its own
+        targets.dedup();
+
+        targets
+    }
+
+    fn make_move(&mut self, the_move: Move) -> Result<CapablancaUndo, CantMovePiece> {
+        self.check_move(the_move)?;
+
+        Ok(self.apply(the_move))
+    }
+
+    fn unmake_move(&mut self, undo: CapablancaUndo) {
+        let player = undo.moved.color;
+
+        if let Some(side) = undo.castled {
+            let rook = self.take(self.rook_destination(player, side));
+            self.take(self.king_destination(player, side));
+            if let Some(rook) = rook {
+                self.place(rook, self.rook_origin(player, side));
+            }
+        } else {
+            self.take(undo.the_move.to);
+            if let Some((at, captured)) = undo.captured {
+                self.place(captured, at);
+            }
+        }
+        self.place(undo.moved, undo.the_move.from);
+
+        self.player = player;
+        self.castling_rights = undo.castling_rights;
+        self.en_passant_target = undo.en_passant_target;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.fullmove_number = undo.fullmove_number;
+    }
+
+    fn outcome_events(&self) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        let checking_pieces = self
+            .king_of(self.player)
+            .map(|king| self.attackers_of(king, self.opponent()))
+            .unwrap_or_default();
+        let has_moves = !self.legal_moves().is_empty();
+        if !checking_pieces.is_empty() {
+            events.push(Event::Check {
+                by: checking_pieces,
+            });
+
+            if !has_moves {
+                events.push(Event::Mate);
+                return events;
+            }
+        } else if !has_moves {
+            events.push(Event::Stalemate);
+            return events;
+        }
+
+        if self.is_insufficient_material() {
+            events.push(Event::Draw {
+                reason: DrawReason::InsufficientMaterial,
+            });
+        } else if self.halfmove_clock >= SEVENTY_FIVE_MOVE_RULE {
+            events.push(Event::Draw {
+                reason: DrawReason::SeventyFiveMoveRule,
+            });
+        } else if self.halfmove_clock >= FIFTY_MOVE_RULE {
+            events.push(Event::DrawClaimable {
+                reason: DrawReason::FiftyMoveRule,
+            });
+        }
+
+        events
+    }
+
+    fn is_drawn(&self) -> bool {
+        self.is_insufficient_material() || self.halfmove_clock >= SEVENTY_FIVE_MOVE_RULE
+    }
+}
+
+impl CapablancaState {
+    /// The pieces of [attacker] that could capture a piece residing on
+    /// [target], regardless of whether the cell is currently occupied.
+    pub fn attackers_of(&self, target: Coordinate, attacker: Player) -> Vec<(Coordinate, Piece)> {
+        self.attackers(target, attacker).collect()
+    }
+
+    pub fn is_attacked(&self, target: Coordinate, attacker: Player) -> bool {
+        self.attackers(target, attacker).next().is_some()
+    }
+
+    fn attackers(
+        &self,
+        target: Coordinate,
+        attacker: Player,
+    ) -> impl Iterator<Item = (Coordinate, Piece)> + '_ {
+        let of = move |at: Option<Coordinate>, figures: &[Figure]| {
+            at.and_then(|at| self.piece_at(at).map(|piece| (at, piece)))
+                .filter(|(_, piece)| piece.color == attacker && figures.contains(&piece.figure))
+        };
+
+        // Attacks are symmetric, so we look from the target towards the
+        // attackers. Pawns of white attack upwards, so they reside below.
+        let pawn_row = match attacker {
+            Color::White => 1,
+            Color::Black => -1,
+        };
+        let pawns = [-1, 1]
+            .into_iter()
+            .filter_map(move |x| of(offset(target, x, pawn_row), &[Figure::Pawn]));
+        let jumpers = KNIGHT
+            .into_iter()
+            .filter_map(move |(x, y)| of(offset(target, x, y), &JUMPERS));
+        let kings = ORTHOGONAL
+            .into_iter()
+            .chain(DIAGONAL)
+            .filter_map(move |(x, y)| of(offset(target, x, y), &[Figure::King]));
+        let sliders = move |directions: [(isize, isize); 4], figures: &'static [Figure]| {
+            directions
+                .into_iter()
+                .filter_map(move |direction| of(self.first_occupied(target, direction), figures))
+        };
+
+        pawns
+            .chain(jumpers)
+            .chain(kings)
+            .chain(sliders(ORTHOGONAL, &ORTHOGONAL_SLIDERS))
+            .chain(sliders(DIAGONAL, &DIAGONAL_SLIDERS))
+    }
+
+    /// Rejects [the_move] if there is no piece of the current player to
+    /// move, or if it promotes a pawn to a figure outside of
+    /// [CAPABLANCA_PROMOTIONS]. Whether the piece may move to the target is
+    /// left to [crate::variants::Capablanca::move_piece].
+    pub(super) fn check_move(&self, the_move: Move) -> Result<Piece, CantMovePiece> {
+        if the_move.is_drop() {
+            return Err(CantMovePiece::NoPieceToDrop);
+        }
+
+        let Some(moved) = self.piece_at(the_move.from) else {
+            return Err(CantMovePiece::NoPieceToMove);
+        };
+        if moved.color != self.player {
+            return Err(CantMovePiece::ItBelongsToOtherPlayer);
+        }
+
+        if self.requires_promotion(moved, the_move.to) {
+            match the_move.promotion {
+                None => return Err(CantMovePiece::RequiresPromotion),
+                Some(promotion) if !CAPABLANCA_PROMOTIONS.contains(&promotion) => {
+                    return Err(CantMovePiece::IllegalMove {
+                        attempted: the_move,
+                        legal: self.legal_targets(the_move.from),
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(moved)
+    }
+
+    /// Whether the king of [player] is attacked.
+    pub fn is_in_check(&self, player: Player) -> bool {
+        self.king_of(player)
+            .is_some_and(|king| self.is_attacked(king, player.other()))
+    }
+
+    /// Whether only kings and at most a single knight or bishop remain, which
+    /// can't mate on the wider board either.
+    fn is_insufficient_material(&self) -> bool {
+        let mut others = self
+            .pieces()
+            .filter(|(_, piece)| piece.figure != Figure::King);
+
+        match (others.next(), others.next()) {
+            (None, _) => true,
+            (Some((_, piece)), None) => [Figure::Knight, Figure::Bishop].contains(&piece.figure),
+            _ => false,
+        }
+    }
+
+    /// Executes a move that is at least pseudo-legal.
+    pub(super) fn apply(&mut self, the_move: Move) -> CapablancaUndo {
+        let from = the_move.from;
+        let to = the_move.to;
+        let moved = self.take(from).expect("a piece resides on the origin");
+
+        let mut undo = CapablancaUndo {
+            the_move,
+            moved,
+            captured: None,
+            castled: None,
+            castling_rights: self.castling_rights,
+            en_passant_target: self.en_passant_target,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+        };
+
+        // The king moves more than one cell only when castling
+        let castled = match moved.figure == Figure::King && from.x.abs_diff(to.x) > 1 {
+            true if to.x > from.x => Some(CastleSide::KingSide),
+            true => Some(CastleSide::QueenSide),
+            false => None,
+        };
+
+        if let Some(side) = castled {
+            let rook = self.take(self.rook_origin(self.player, side));
+            self.place(moved, to);
+            if let Some(rook) = rook {
+                self.place(rook, self.rook_destination(self.player, side));
+            }
+            undo.castled = Some(side);
+        } else {
+            let captured_at = match self.en_passant_target {
+                Some(target) if target == to && moved.figure == Figure::Pawn => {
+                    Coordinate::new(to.x, from.y)
+                }
+                _ => to,
+            };
+            undo.captured = self
+                .take(captured_at)
+                .map(|captured| (captured_at, captured));
+
+            let placed = match the_move.promotion {
+                Some(promoted) if self.requires_promotion(moved, to) => {
+                    Piece::new(self.player, promoted.to_figure())
+                }
+                _ => moved,
+            };
+            self.place(placed, to);
+        }
+
+        self.castling_rights = self.castling_rights_after(from, to);
+        self.en_passant_target = match moved.figure == Figure::Pawn && from.y.abs_diff(to.y) == 2 {
+            true => Some(Coordinate::new(from.x, (from.y + to.y) / 2)),
+            false => None,
+        };
+        self.halfmove_clock = match undo.captured.is_some() || moved.figure == Figure::Pawn {
+            true => 0,
+            false => self.halfmove_clock + 1,
+        };
+        if self.player == Color::Black {
+            self.fullmove_number += 1;
+        }
+        self.player = self.opponent();
+
+        undo
+    }
+
+    /// Moving the king away revokes both directions, while moving a rook
+    /// away from or capturing a rook on its initial cell only revokes the
+    /// side the rook belongs to.
+    fn castling_rights_after(&self, from: Coordinate, to: Coordinate) -> CastlingRights {
+        let mut rights = self.castling_rights;
+
+        for player in [Color::White, Color::Black] {
+            for side in [CastleSide::KingSide, CastleSide::QueenSide] {
+                let king = self.king_origin(player);
+                let rook = self.rook_origin(player, side);
+                if [king, rook].contains(&from) || [king, rook].contains(&to) {
+                    rights.revoke(player, side);
+                }
+            }
+        }
+
+        rights
+    }
+
+    pub(super) fn requires_promotion(&self, piece: Piece, to: Coordinate) -> bool {
+        piece.figure == Figure::Pawn && to.y == piece.color.other().back_rank()
+    }
+
+    /// The moves of the current player, including ones that leave their own
+    /// king in check.
+    fn pseudo_legal_moves(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+
+        for (from, piece) in self
+            .pieces()
+            .filter(|(_, piece)| piece.color == self.player)
+        {
+            match piece.figure {
+                Figure::Pawn => self.add_pawn_moves(&mut moves, from, piece),
+                Figure::King => {
+                    self.add_steps(&mut moves, from, ORTHOGONAL.iter().chain(&DIAGONAL));
+                    self.add_castling_moves(&mut moves, from);
+                }
+                figure => {
+                    if ORTHOGONAL_SLIDERS.contains(&figure) {
+                        self.add_slides(&mut moves, from, ORTHOGONAL);
+                    }
+                    if DIAGONAL_SLIDERS.contains(&figure) {
+                        self.add_slides(&mut moves, from, DIAGONAL);
+                    }
+                    if JUMPERS.contains(&figure) {
+                        self.add_steps(&mut moves, from, KNIGHT.iter());
+                    }
+                }
+            }
+        }
+
+        moves
+    }
+
+    fn add_steps<'a>(
+        &self,
+        moves: &mut Vec<Move>,
+        from: Coordinate,
+        offsets: impl Iterator<Item = &'a (isize, isize)>,
+    ) {
+        for (x, y) in offsets {
+            let Some(to) = offset(from, *x, *y) else {
+                continue;
+            };
+            if self
+                .piece_at(to)
+                .is_none_or(|piece| piece.color != self.player)
+            {
+                moves.push(Move::simple(from, to));
+            }
+        }
+    }
+
+    fn add_slides(&self, moves: &mut Vec<Move>, from: Coordinate, directions: [(isize, isize); 4]) {
+        for (x, y) in directions {
+            let mut at = from;
+            while let Some(to) = offset(at, x, y) {
+                match self.piece_at(to) {
+                    None => moves.push(Move::simple(from, to)),
+                    Some(piece) => {
+                        if piece.color != self.player {
+                            moves.push(Move::simple(from, to));
+                        }
+                        break;
+                    }
+                }
+                at = to;
+            }
+        }
+    }
+
+    fn add_pawn_moves(&self, moves: &mut Vec<Move>, from: Coordinate, pawn: Piece) {
+        let forward = match pawn.color {
+            Color::White => -1,
+            Color::Black => 1,
+        };
+        let mut targets = Vec::new();
+
+        if let Some(single) = offset(from, 0, forward) {
+            if self.piece_at(single).is_none() {
+                targets.push(single);
+
+                let start_row = pawn.color.back_rank().checked_add_signed(forward);
+                let double = offset(single, 0, forward);
+                if let Some(double) = double.filter(|_| start_row == Some(from.y)) {
+                    if self.piece_at(double).is_none() {
+                        targets.push(double);
+                    }
+                }
+            }
+        }
+
+        for x in [-1, 1] {
+            let Some(to) = offset(from, x, forward) else {
+                continue;
+            };
+            let captures = self
+                .piece_at(to)
+                .is_some_and(|piece| piece.color != pawn.color);
+            if captures || self.en_passant_target == Some(to) {
+                targets.push(to);
+            }
+        }
+
+        for to in targets {
+            match self.requires_promotion(pawn, to) {
+                true => moves.extend(
+                    CAPABLANCA_PROMOTIONS
+                        .iter()
+                        .map(|promotion| Move::new(from, to, Some(*promotion))),
+                ),
+                false => moves.push(Move::simple(from, to)),
+            }
+        }
+    }
+
+    /// Castling requires every cell between king and rook and their
+    /// destinations to be empty, and the king to not be in check on any cell
+    /// it passes.
+    fn add_castling_moves(&self, moves: &mut Vec<Move>, from: Coordinate) {
+        let player = self.player;
+        if from != self.king_origin(player) {
+            return;
+        }
+
+        for side in [CastleSide::KingSide, CastleSide::QueenSide] {
+            if !self.castling_rights.allows(player, side) {
+                continue;
+            }
+
+            let rook = self.rook_origin(player, side);
+            let king_destination = self.king_destination(player, side);
+            let rook_destination = self.rook_destination(player, side);
+            if self.piece_at(rook) != Some(Piece::new(player, Figure::Rook)) {
+                continue;
+            }
+
+            let cells = [from, rook, king_destination, rook_destination];
+            let left = cells.iter().map(|cell| cell.x).min().unwrap_or(from.x);
+            let right = cells.iter().map(|cell| cell.x).max().unwrap_or(from.x);
+            let is_free = (left..=right)
+                .map(|x| Coordinate::new(x, from.y))
+                .all(|at| at == from || at == rook || self.piece_at(at).is_none());
+
+            let passed = from.x.min(king_destination.x)..=from.x.max(king_destination.x);
+            let passes_check = passed
+                .map(|x| Coordinate::new(x, from.y))
+                .any(|at| self.is_attacked(at, player.other()));
+
+            if is_free && !passes_check {
+                moves.push(Move::simple(from, king_destination));
+            }
+        }
+    }
+
+    /// The first piece found when looking from [from] towards [direction].
+    fn first_occupied(&self, from: Coordinate, direction: (isize, isize)) -> Option<Coordinate> {
+        let mut at = from;
+        while let Some(next) = offset(at, direction.0, direction.1) {
+            if self.piece_at(next).is_some() {
+                return Some(next);
+            }
+            at = next;
+        }
+
+        None
+    }
+}
@@ -0,0 +1,406 @@
+use crate::{
+    fen::{
+        parse_fullmove_number, parse_halfmove_clock, parse_piece, parse_player, serialize_piece,
+        serialize_player, CouldNotParse, IllegalPosition,
+    },
+    CastleSide, CastlingRights, Color, Coordinate, Figure, Piece, Player,
+};
+
+/// The number of files (columns) of the board.
+pub const FILES: usize = 10;
+
+/// The number of ranks (rows) of the board.
+pub const RANKS: usize = 8;
+
+/// The start position of Capablanca chess, which places an Archbishop and a
+/// Chancellor between the bishops and knights of standard chess.
+pub const START: &str = "rnabqkbcnr/pppppppppp/10/10/10/10/PPPPPPPPPP/RNABQKBCNR w KQkq - 0 1";
+
+type Row = [Option<Piece>; FILES];
+
+/// A position of Capablanca chess, like [crate::State] is for the 8x8 board.
+///
+/// [Coordinate]s keep their meaning: `x` counts files from the left and `y`
+/// counts ranks from the top, i.e. from the back rank of black. The board is
+/// stored as a plain array, since [crate::bitboard::Bitboard]s only cover 64
+/// cells.
+///
+/// King and rooks castle from the corners and the center of the back rank,
+/// and end up on the same files relative to the edges of the board as in
+/// standard chess.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapablancaState {
+    pub(crate) board: [Row; RANKS],
+    pub(crate) player: Player,
+    pub(crate) castling_rights: CastlingRights,
+    /// The cell that a pawn could move to, in order to capture the passing
+    /// piece using the "en passant" special rule.
+    pub(crate) en_passant_target: Option<Coordinate>,
+    /// The number of halfmoves since the last capture or pawn move.
+    pub halfmove_clock: u16,
+    pub fullmove_number: u16,
+}
+
+impl CapablancaState {
+    /// A board without any pieces, on which no one may castle.
+    fn empty() -> Self {
+        Self {
+            board: [[None; FILES]; RANKS],
+            player: Color::White,
+            castling_rights: CastlingRights::none(),
+            en_passant_target: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+        }
+    }
+
+    /// The player to move.
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    pub fn castling_rights(&self) -> CastlingRights {
+        self.castling_rights
+    }
+
+    /// The cell that a pawn could move to, in order to capture the passing
+    /// piece using the "en passant" special rule.
+    pub fn en_passant_target(&self) -> Option<Coordinate> {
+        self.en_passant_target
+    }
+
+    pub fn opponent(&self) -> Player {
+        self.player.other()
+    }
+
+    pub fn piece_at(&self, at: Coordinate) -> Option<Piece> {
+        self.board[at.y][at.x]
+    }
+
+    /// Every piece on the board and the cell it resides on.
+    pub fn pieces(&self) -> impl Iterator<Item = (Coordinate, Piece)> + '_ {
+        self.board.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter_map(move |(x, cell)| cell.map(|piece| (Coordinate::new(x, y), piece)))
+        })
+    }
+
+    pub fn king_of(&self, player: Player) -> Option<Coordinate> {
+        self.pieces()
+            .find(|(_, piece)| *piece == Piece::new(player, Figure::King))
+            .map(|(at, _)| at)
+    }
+
+    /// The cell the king of [player] resides on as long as it may castle.
+    pub fn king_origin(&self, player: Player) -> Coordinate {
+        Coordinate::new(FILES / 2, player.back_rank())
+    }
+
+    /// The cell the rook of [player] castling towards [side] resides on as
+    /// long as it may castle.
+    pub fn rook_origin(&self, player: Player, side: CastleSide) -> Coordinate {
+        let x = match side {
+            CastleSide::KingSide => FILES - 1,
+            CastleSide::QueenSide => 0,
+        };
+
+        Coordinate::new(x, player.back_rank())
+    }
+
+    /// The cell the king of [player] ends up on after castling, which is
+    /// as far from the edge as in standard chess.
+    pub fn king_destination(&self, player: Player, side: CastleSide) -> Coordinate {
+        let x = match side {
+            CastleSide::KingSide => FILES - 2,
+            CastleSide::QueenSide => 2,
+        };
+
+        Coordinate::new(x, player.back_rank())
+    }
+
+    /// The cell the rook of [player] ends up on after castling.
+    pub fn rook_destination(&self, player: Player, side: CastleSide) -> Coordinate {
+        let x = match side {
+            CastleSide::KingSide => FILES - 3,
+            CastleSide::QueenSide => 3,
+        };
+
+        Coordinate::new(x, player.back_rank())
+    }
+
+    pub(super) fn place(&mut self, piece: Piece, at: Coordinate) {
+        self.board[at.y][at.x] = Some(piece);
+    }
+
+    pub(super) fn take(&mut self, at: Coordinate) -> Option<Piece> {
+        self.board[at.y][at.x].take()
+    }
+}
+
+/// The cell [horizontal] files to the right and [vertical] ranks down from
+/// [at], if it is still on the board.
+pub(super) fn offset(at: Coordinate, horizontal: isize, vertical: isize) -> Option<Coordinate> {
+    let x = at.x.checked_add_signed(horizontal)?;
+    let y = at.y.checked_add_signed(vertical)?;
+
+    (x < FILES && y < RANKS).then_some(Coordinate::new(x, y))
+}
+
+/// Describes [at] in algebraic notation, which unlike the [std::fmt::Display]
+/// of [Coordinate] covers the files `i` and `j`.
+pub fn format_cell(at: Coordinate) -> String {
+    let file = (b'a' + at.x as u8) as char;
+
+    format!("{}{}", file, RANKS - at.y)
+}
+
+/// The inverse of [format_cell].
+pub fn parse_cell(notation: &str) -> Option<Coordinate> {
+    let mut characters = notation.chars();
+    let file = characters.next().filter(char::is_ascii_lowercase)?;
+    let rank = characters.next()?.to_digit(10)? as usize;
+    if characters.next().is_some() {
+        return None;
+    }
+
+    let x = (file as u8 - b'a') as usize;
+    (x < FILES && (1..=RANKS).contains(&rank)).then(|| Coordinate::new(x, RANKS - rank))
+}
+
+impl CapablancaState {
+    /// Parses a position in Forsyth–Edwards Notation.
+    ///
+    /// Runs of ten empty cells are written as `10`, and Archbishops and
+    /// Chancellors as `a` and `c`. Castling rights are given as `KQkq`, since
+    /// king and rooks always start on the same files.
+    pub fn parse(notation: &str) -> Result<Self, CouldNotParse> {
+        let parts: Vec<&str> = notation.split(' ').collect();
+        if parts.len() != 6 {
+            return Err(CouldNotParse::InvalidNumberOfParts);
+        }
+
+        let rows: Vec<&str> = parts[0].split('/').collect();
+        if rows.len() != RANKS {
+            return Err(CouldNotParse::InvalidNumberOfRows);
+        }
+
+        let mut state = Self::empty();
+        for (y, row) in rows.into_iter().enumerate() {
+            state.board[y] = parse_row(row)?;
+        }
+        state.player = parse_player(parts[1])?;
+        state.castling_rights = state.parse_castling_rights(parts[2])?;
+        state.en_passant_target = match parts[3] {
+            "-" => None,
+            target => Some(
+                parse_cell(target)
+                    .ok_or_else(|| CouldNotParse::InvalidEnPassantTarget(target.to_owned()))?,
+            ),
+        };
+        state.halfmove_clock = parse_halfmove_clock(parts[4])?;
+        state.fullmove_number = parse_fullmove_number(parts[5])?;
+
+        Ok(state)
+    }
+
+    fn parse_castling_rights(&self, notation: &str) -> Result<CastlingRights, CouldNotParse> {
+        let mut rights = CastlingRights::none();
+        if notation == "-" {
+            return Ok(rights);
+        }
+
+        for character in notation.chars() {
+            let (player, side) = match character {
+                'K' => (Color::White, CastleSide::KingSide),
+                'Q' => (Color::White, CastleSide::QueenSide),
+                'k' => (Color::Black, CastleSide::KingSide),
+                'q' => (Color::Black, CastleSide::QueenSide),
+                _ => return Err(CouldNotParse::InvalidCastlingRights(notation.to_owned())),
+            };
+
+            let king = self.piece_at(self.king_origin(player));
+            let rook = self.piece_at(self.rook_origin(player, side));
+            if king != Some(Piece::new(player, Figure::King))
+                || rook != Some(Piece::new(player, Figure::Rook))
+            {
+                return Err(IllegalPosition::CastlingWithoutKingOrRook(player, side).into());
+            }
+
+            rights.grant(player, side);
+        }
+
+        Ok(rights)
+    }
+
+    /// Describes the position in the notation [CapablancaState::parse]
+    /// reads.
+    pub fn to_fen(&self) -> String {
+        let rows = self
+            .board
+            .iter()
+            .map(serialize_row)
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let mut castling = String::new();
+        for (character, player, side) in [
+            ('K', Color::White, CastleSide::KingSide),
+            ('Q', Color::White, CastleSide::QueenSide),
+            ('k', Color::Black, CastleSide::KingSide),
+            ('q', Color::Black, CastleSide::QueenSide),
+        ] {
+            if self.castling_rights.allows(player, side) {
+                castling.push(character);
+            }
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        let en_passant = match self.en_passant_target {
+            Some(target) => format_cell(target),
+            None => String::from("-"),
+        };
+
+        format!(
+            "{} {} {} {} {} {}",
+            rows,
+            serialize_player(self.player),
+            castling,
+            en_passant,
+            self.halfmove_clock,
+            self.fullmove_number
+        )
+    }
+}
+
+fn parse_row(row: &str) -> Result<Row, CouldNotParse> {
+    let invalid = || CouldNotParse::InvalidNumberOfColumns(row.to_owned());
+    let mut cells = Vec::new();
+    let mut empty_cells = 0;
+
+    for character in row.chars() {
+        if let Some(digit) = character.to_digit(10) {
+            // A leading zero would allow the same row to be written in
+            // different ways.
+            if empty_cells == 0 && digit == 0 {
+                return Err(invalid());
+            }
+            empty_cells = empty_cells * 10 + digit as usize;
+            if empty_cells > FILES {
+                return Err(invalid());
+            }
+            continue;
+        }
+
+        cells.extend(std::iter::repeat_n(None, empty_cells));
+        empty_cells = 0;
+        cells.push(Some(parse_capablanca_piece(character)?));
+    }
+    cells.extend(std::iter::repeat_n(None, empty_cells));
+
+    cells.try_into().map_err(|_| invalid())
+}
+
+/// Like [parse_piece], but also accepts the figures of Capablanca chess.
+fn parse_capablanca_piece(character: char) -> Result<Piece, CouldNotParse> {
+    let figure = match character.to_ascii_lowercase() {
+        'a' => Figure::Archbishop,
+        'c' => Figure::Chancellor,
+        _ => return parse_piece(character),
+    };
+
+    match character.is_ascii_uppercase() {
+        true => Ok(Piece::white(figure)),
+        false => Ok(Piece::black(figure)),
+    }
+}
+
+fn serialize_row(row: &Row) -> String {
+    let mut notation = String::new();
+    let mut empty_cells = 0;
+
+    for cell in row {
+        let Some(piece) = cell else {
+            empty_cells += 1;
+            continue;
+        };
+
+        if empty_cells > 0 {
+            notation += &empty_cells.to_string();
+            empty_cells = 0;
+        }
+        notation.push(serialize_piece(*piece));
+    }
+
+    if empty_cells > 0 {
+        notation += &empty_cells.to_string();
+    }
+
+    notation
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Cell;
+
+    use super::*;
+
+    #[test]
+    fn it_reads_and_writes_capablanca_positions() {
+        for notation in [
+            START,
+            "r4k3r/10/10/4pP4/10/10/10/R4K3R w Kq e6 0 9",
+            "10/10/4k5/10/10/10/9P/K9 w - - 3 41",
+        ] {
+            assert_eq!(notation, CapablancaState::parse(notation).unwrap().to_fen());
+        }
+
+        let state = CapablancaState::parse(START).unwrap();
+        assert_eq!(
+            Some(Piece::white(Figure::Archbishop)),
+            state.piece_at(Cell::C1)
+        );
+        assert_eq!(
+            Some(Piece::black(Figure::Chancellor)),
+            state.piece_at(Coordinate::new(7, 0))
+        );
+        assert_eq!(Some(Coordinate::new(5, 7)), state.king_of(Color::White));
+    }
+
+    #[test]
+    fn it_formats_and_parses_cells_of_the_wider_board() {
+        for (notation, at) in [
+            ("a1", Cell::A1),
+            ("h8", Cell::H8),
+            ("j1", Coordinate::new(9, 7)),
+            ("i5", Coordinate::new(8, 3)),
+        ] {
+            assert_eq!(Some(at), parse_cell(notation), "{notation}");
+            assert_eq!(notation, format_cell(at));
+        }
+
+        for invalid in ["", "a", "a0", "a9", "k1", "A1", "1a", "a10"] {
+            assert_eq!(None, parse_cell(invalid), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn it_rejects_invalid_positions() {
+        for notation in [
+            "",
+            "10/10 w - - 0",
+            // Rows of the standard board
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnabqkbcnr/9/10/10/10/10/PPPPPPPPPP/RNABQKBCNR w KQkq - 0 1",
+            "rnabqkbcnr/010/10/10/10/10/PPPPPPPPPP/RNABQKBCNR w KQkq - 0 1",
+            "rnabqkbcnr/pppppppppp/11/10/10/10/PPPPPPPPPP/RNABQKBCNR w KQkq - 0 1",
+            "rnabqkbcnx/pppppppppp/10/10/10/10/PPPPPPPPPP/RNABQKBCNR w KQkq - 0 1",
+            "rnabqkbcnr/pppppppppp/10/10/10/10/PPPPPPPPPP/RNABQKBCN1 w KQkq - 0 1",
+            "rnabqkbcnr/pppppppppp/10/10/10/10/PPPPPPPPPP/RNABQKBCNR w KQkq k3 0 1",
+        ] {
+            assert!(CapablancaState::parse(notation).is_err(), "{notation}");
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use crate::{
+    bitboard::{attacks::is_attacked, Bitboard},
+    check::checking_pieces_of_opponent,
+    move_execution::{self, CantMovePiece, Undo},
+    Coordinate, DrawReason, Event, Figure, Move, Piece, State, BOARD_SIZE,
+};
+
+use super::{Standard, Variant};
+
+/// Captured pieces change sides and go into the pocket of the capturing
+/// player, who may drop them back onto any empty cell instead of moving.
+///
+/// Pawns can't be dropped onto the first or last row, and promoted pieces
+/// turn back into pawns when they are captured.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Crazyhouse;
+
+impl Variant for Crazyhouse {
+    fn name(&self) -> &'static str {
+        "Crazyhouse"
+    }
+
+    fn legal_moves(&self, state: &State) -> Vec<Move> {
+        let mut moves = Standard.legal_moves(state);
+
+        for figure in state.pockets.of(state.player).figures() {
+            let drops = self.legal_drops(state, figure).into_iter();
+            moves.extend(drops.map(|at| Move::drop(figure, at)));
+        }
+
+        moves
+    }
+
+    fn legal_drops(&self, state: &State, figure: Figure) -> Bitboard {
+        if state.pockets.of(state.player).count(figure) == 0 {
+            return Bitboard::EMPTY;
+        }
+
+        let mut cells = !state.bitboards.occupied();
+        if figure == Figure::Pawn {
+            cells &= !back_ranks();
+        }
+
+        // Adding a piece never exposes the own king, but it might block a
+        // check, which is then the only kind of drop allowed.
+        let Some(king) = state.bitboards.king_of(state.player) else {
+            return cells;
+        };
+        if !is_attacked(&state.bitboards, king, state.opponent()) {
+            return cells;
+        }
+
+        let piece = Piece::new(state.player, figure);
+        cells
+            .into_iter()
+            .filter(|at| {
+                let mut bitboards = state.bitboards;
+                bitboards.toggle(piece, *at);
+                !is_attacked(&bitboards, king, state.opponent())
+            })
+            .collect()
+    }
+
+    fn make_move(&self, state: &mut State, the_move: Move) -> Result<Undo, CantMovePiece> {
+        let undo = move_execution::make_move(state, the_move)?;
+        if let Some(captured) = pocketed(&undo) {
+            state.pocket(captured);
+        }
+
+        Ok(undo)
+    }
+
+    fn unmake_move(&self, state: &mut State, undo: Undo) {
+        if let Some(captured) = pocketed(&undo) {
+            state.unpocket(captured);
+        }
+
+        move_execution::unmake_move(state, undo);
+    }
+
+    fn outcome_events(&self, state: &State) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        let checking_pieces = checking_pieces_of_opponent(state);
+        let in_check = !checking_pieces.is_empty();
+        if in_check {
+            events.push(Event::Check {
+                by: checking_pieces,
+            });
+        }
+
+        // Only counting moves on the board would overlook drops blocking a
+        // check, which often are the only way out of it.
+        if self.legal_moves(state).is_empty() {
+            events.push(match in_check {
+                true => Event::Mate,
+                false => Event::Stalemate,
+            });
+            return events;
+        }
+
+        // Captured pieces never leave the game, so there is always enough
+        // material left to mate.
+        if state.is_seventy_five_move_draw() {
+            events.push(Event::Draw {
+                reason: DrawReason::SeventyFiveMoveRule,
+            });
+        } else if state.can_claim_fifty_move_draw() {
+            events.push(Event::DrawClaimable {
+                reason: DrawReason::FiftyMoveRule,
+            });
+        }
+
+        events
+    }
+
+    fn has_ended(&self, state: &State, legal_moves: &[Move]) -> bool {
+        legal_moves.is_empty() || state.is_seventy_five_move_draw()
+    }
+}
+
+/// The piece the capture described by [undo] puts into the pocket of the
+/// capturing player, if any.
+fn pocketed(undo: &Undo) -> Option<Piece> {
+    let (at, captured) = undo.captured?;
+    let figure = match undo.promoted.contains(at) {
+        true => Figure::Pawn,
+        false => captured.figure,
+    };
+
+    Some(Piece::new(undo.moved.color, figure))
+}
+
+/// The first and last row, which pawns can't be dropped onto.
+fn back_ranks() -> Bitboard {
+    let last = BOARD_SIZE - 1;
+
+    Bitboard::row_span(Coordinate::new(0, 0), Coordinate::new(last, 0))
+        | Bitboard::row_span(Coordinate::new(0, last), Coordinate::new(last, last))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fen::{parse_state, to_fen},
+        move_piece, move_piece_by, unmake_move, zobrist, Cell, Color, PromotedFigure,
+    };
+
+    use super::*;
+
+    #[test]
+    fn captured_pieces_can_be_dropped_by_the_capturer() {
+        let state = parse_state("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+
+        let (state, _) =
+            move_piece_by(&Crazyhouse, &state, Move::simple(Cell::E4, Cell::D5)).unwrap();
+        assert_eq!(1, state.pockets.of(Color::White).count(Figure::Pawn));
+
+        let (state, _) =
+            move_piece_by(&Crazyhouse, &state, Move::simple(Cell::E8, Cell::E7)).unwrap();
+        let drop = Move::drop(Figure::Pawn, Cell::E6);
+        assert!(Crazyhouse.legal_moves(&state).contains(&drop));
+        // Pawns can't be dropped onto the first or last row
+        assert!(!Crazyhouse
+            .legal_drops(&state, Figure::Pawn)
+            .contains(Cell::E8));
+        assert!(Crazyhouse.legal_drops(&state, Figure::Knight).is_empty());
+        // The figures of Capablanca chess never end up in a pocket
+        let fairy_drop = Move::drop(Figure::Chancellor, Cell::E6);
+        assert!(move_piece_by(&Crazyhouse, &state, fairy_drop).is_err());
+
+        let (state, events) = move_piece_by(&Crazyhouse, &state, drop).unwrap();
+        assert_eq!(
+            Some(Piece::white(Figure::Pawn)),
+            crate::piece_at(Cell::E6, &state.board)
+        );
+        assert!(state.pockets.is_empty());
+        assert!(events.contains(&Event::Drop {
+            piece: Piece::white(Figure::Pawn),
+            at: Cell::E6,
+        }));
+
+        // Outside of Crazyhouse, captured pieces are gone for good
+        let state = parse_state("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        let (state, _) = move_piece(&state, Move::simple(Cell::E4, Cell::D5)).unwrap();
+        assert!(state.pockets.is_empty());
+    }
+
+    #[test]
+    fn promoted_pieces_turn_back_into_pawns_when_captured() {
+        let state = parse_state("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        let promotion = Move::new(Cell::A7, Cell::B8, Some(PromotedFigure::Queen));
+        let (state, _) = move_piece_by(&Crazyhouse, &state, promotion).unwrap();
+        assert_eq!(1, state.pockets.of(Color::White).count(Figure::Rook));
+        assert!(state.promoted.contains(Cell::B8));
+        assert_eq!("1Q~2k3/8/8/8/8/8/8/4K3[R] b - - 0 1", to_fen(&state));
+
+        // The promoted piece is tracked as it moves
+        let (state, _) =
+            move_piece_by(&Crazyhouse, &state, Move::simple(Cell::E8, Cell::E7)).unwrap();
+        let (state, _) =
+            move_piece_by(&Crazyhouse, &state, Move::simple(Cell::B8, Cell::B7)).unwrap();
+        assert!(state.promoted.contains(Cell::B7));
+        assert!(!state.promoted.contains(Cell::B8));
+
+        let state = parse_state("2k5/1Q~6/8/8/8/8/8/7K b - - 0 1").unwrap();
+        let (state, _) =
+            move_piece_by(&Crazyhouse, &state, Move::simple(Cell::C8, Cell::B7)).unwrap();
+        assert_eq!(1, state.pockets.of(Color::Black).count(Figure::Pawn));
+        assert_eq!(0, state.pockets.of(Color::Black).count(Figure::Queen));
+        assert!(state.promoted.is_empty());
+    }
+
+    #[test]
+    fn drops_can_prevent_mate() {
+        let back_rank_mate = Move::simple(Cell::A1, Cell::A8);
+
+        let state = parse_state("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let (_, events) = move_piece_by(&Crazyhouse, &state, back_rank_mate).unwrap();
+        assert!(events.contains(&Event::Mate));
+
+        let state = parse_state("6k1/5ppp/8/8/8/8/8/R5K1[n] w - - 0 1").unwrap();
+        let (after, events) = move_piece_by(&Crazyhouse, &state, back_rank_mate).unwrap();
+        assert!(!events.contains(&Event::Mate));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, Event::Check { .. })));
+        // Only drops between the rook and the king get out of check
+        assert_eq!(5, Crazyhouse.legal_drops(&after, Figure::Knight).len());
+    }
+
+    #[test]
+    fn moves_and_drops_can_be_made_and_unmade_in_place() {
+        let original =
+            parse_state("r1b1k2r/ppp2ppp/2n5/3q4/1b1P4/2N5/PP3PPP/R1BQKB1R[NPp] w KQkq - 0 9")
+                .unwrap();
+        let mut state = original.clone();
+
+        let moves = Crazyhouse.legal_moves(&original);
+        assert!(moves.iter().any(|the_move| the_move.is_drop()));
+
+        for the_move in moves {
+            let undo = Crazyhouse.make_move(&mut state, the_move).unwrap();
+
+            let (expected, _) = move_piece_by(&Crazyhouse, &original, the_move).unwrap();
+            assert_eq!(expected, state, "after {the_move:?}");
+            assert_eq!(
+                zobrist::key_of(
+                    &state.board,
+                    state.player,
+                    state.castling_rights,
+                    state.en_passant_target
+                ) ^ zobrist::pockets_key(state.pockets),
+                state.zobrist_key(),
+                "after {the_move:?}"
+            );
+
+            Crazyhouse.unmake_move(&mut state, undo);
+            assert_eq!(original, state, "after taking back {the_move:?}");
+            assert_eq!(original.zobrist_key(), state.zobrist_key());
+        }
+
+        // Unmaking a drop without the variant still puts the piece back
+        let undo = crate::make_move(&mut state, Move::drop(Figure::Knight, Cell::E5)).unwrap();
+        unmake_move(&mut state, undo);
+        assert_eq!(original, state);
+    }
+}
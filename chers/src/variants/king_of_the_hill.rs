@@ -0,0 +1,88 @@
+use crate::{bitboard::Bitboard, Cell, DrawReason, Event, Move, State, WinReason};
+
+use super::{Standard, Variant};
+
+/// Standard chess, except that moving the king onto one of the four center
+/// cells wins the game.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct KingOfTheHill;
+
+impl Variant for KingOfTheHill {
+    fn name(&self) -> &'static str {
+        "King of the Hill"
+    }
+
+    fn outcome_events(&self, state: &State) -> Vec<Event> {
+        let reached_hill = state
+            .bitboards
+            .king_of(state.opponent())
+            .is_some_and(|king| hill().contains(king));
+        if reached_hill {
+            return vec![Event::Win {
+                winner: state.opponent(),
+                reason: WinReason::KingOfTheHill,
+            }];
+        }
+
+        // A lone king can still walk up the hill
+        let mut events = Standard.outcome_events(state);
+        events.retain(|event| {
+            *event
+                != Event::Draw {
+                    reason: DrawReason::InsufficientMaterial,
+                }
+        });
+
+        events
+    }
+
+    fn has_ended(&self, state: &State, legal_moves: &[Move]) -> bool {
+        let reached_hill = state
+            .bitboards
+            .king_of(state.opponent())
+            .is_some_and(|king| hill().contains(king));
+
+        reached_hill || legal_moves.is_empty() || state.is_seventy_five_move_draw()
+    }
+}
+
+/// The four center cells the kings race for.
+fn hill() -> Bitboard {
+    [Cell::D4, Cell::D5, Cell::E4, Cell::E5]
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fen::parse_state, move_piece_by, Color, Move};
+
+    use super::*;
+
+    #[test]
+    fn reaching_the_center_wins() {
+        let state = parse_state("4k3/8/8/8/8/4K3/8/8 w - - 0 1").unwrap();
+
+        let (_, events) =
+            move_piece_by(&KingOfTheHill, &state, Move::simple(Cell::E3, Cell::E4)).unwrap();
+        assert_eq!(
+            vec![Event::Win {
+                winner: Color::White,
+                reason: WinReason::KingOfTheHill
+            }],
+            events[1..]
+        );
+
+        let (_, events) =
+            move_piece_by(&KingOfTheHill, &state, Move::simple(Cell::E3, Cell::F3)).unwrap();
+        assert_eq!(1, events.len());
+
+        // Only the king counts
+        let state = parse_state("4k3/8/8/8/8/4N3/8/4K3 w - - 0 1").unwrap();
+        let (_, events) =
+            move_piece_by(&KingOfTheHill, &state, Move::simple(Cell::E3, Cell::D5)).unwrap();
+        assert!(events
+            .iter()
+            .all(|event| !matches!(event, Event::Win { .. })));
+    }
+}
@@ -0,0 +1,276 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use crate::{
+    move_execution::CantMovePiece, Coordinate, Event, Figure, Move, PromotedFigure, State,
+};
+
+mod antichess;
+mod atomic;
+mod capablanca;
+mod crazyhouse;
+mod king_of_the_hill;
+mod standard;
+mod three_check;
+
+pub use antichess::Antichess;
+pub use atomic::{Atomic, Exploded};
+pub use capablanca::{Capablanca, CapablancaState, CapablancaUndo, CAPABLANCA_PROMOTIONS};
+pub use crazyhouse::Crazyhouse;
+pub use king_of_the_hill::KingOfTheHill;
+pub use standard::Standard;
+pub use three_check::{ThreeCheck, CHECKS_TO_WIN};
+
+/// The rules a game is played by.
+///
+/// Every method defaults to the rules of standard chess, so a variant only
+/// needs to override what it changes. Overriding methods can still fall back
+/// to the standard rules by calling them on [Standard].
+///
+/// Variants are played on a [State] unless they need a board of another size,
+/// like [Capablanca] does.
+pub trait Variant<P: Position = State> {
+    /// The name of the variant, as used in the `Variant` tag of PGN.
+    fn name(&self) -> &'static str;
+
+    /// The position the game starts from.
+    fn start(&self) -> P {
+        P::start()
+    }
+
+    /// Every legal move of the current player, see [crate::legal_moves].
+    fn legal_moves(&self, state: &P) -> Vec<Move> {
+        state.legal_moves()
+    }
+
+    /// The cells the piece on [from] may legally move to.
+    fn legal_targets(&self, state: &P, from: Coordinate) -> P::Cells {
+        state.legal_targets(from)
+    }
+
+    /// The figures a pawn reaching the last row may be promoted to.
+    fn promotions(&self) -> &'static [PromotedFigure] {
+        &PromotedFigure::ALL
+    }
+
+    /// The cells a piece of [figure] may be dropped onto from the pocket of
+    /// the current player, which is none outside of Crazyhouse.
+    fn legal_drops(&self, state: &P, figure: Figure) -> P::Cells {
+        let _ = (state, figure);
+        P::Cells::default()
+    }
+
+    /// Executes [the_move] in place, see [crate::make_move].
+    fn make_move(&self, state: &mut P, the_move: Move) -> Result<P::Undo, CantMovePiece> {
+        state.make_move(the_move)
+    }
+
+    /// Reverts a move made by [Variant::make_move], see [crate::unmake_move].
+    fn unmake_move(&self, state: &mut P, undo: P::Undo) {
+        state.unmake_move(undo)
+    }
+
+    /// Events caused by a move in addition to moving, capturing, promoting
+    /// and castling, which are reported for every variant.
+    ///
+    /// [before] and [after] are the positions around the move described by
+    /// [undo].
+    fn special_events(&self, before: &P, undo: &P::Undo, after: &P) -> Vec<Event> {
+        let _ = (before, undo, after);
+        Vec::new()
+    }
+
+    /// Whether the player to move in [state] is in check, has been mated or
+    /// the game ended otherwise, e.g. in a draw.
+    fn outcome_events(&self, state: &P) -> Vec<Event> {
+        state.outcome_events()
+    }
+
+    /// Whether [Variant::outcome_events] reports a mate, a win or a draw in
+    /// [state], where the player to move has [legal_moves].
+    ///
+    /// Unlike the events this doesn't describe checks, which keeps it cheap
+    /// enough to ask in every position a search visits.
+    fn has_ended(&self, state: &P, legal_moves: &[Move]) -> bool {
+        legal_moves.is_empty() || state.is_drawn()
+    }
+}
+
+/// A board the rules of standard chess can be played on, which the defaults
+/// of [Variant] are implemented with.
+///
+/// [State] is the 8x8 board, whose moves are generated quickly using
+/// [crate::bitboard::Bitboard]s. [CapablancaState] is the 10x8 board of
+/// [Capablanca] chess, which doesn't fit into them.
+pub trait Position: Clone {
+    /// A set of cells, e.g. the targets of a piece.
+    type Cells: Default;
+    /// What [Position::unmake_move] needs to revert a move.
+    type Undo;
+
+    /// The start position of standard chess on this board.
+    fn start() -> Self;
+
+    fn legal_moves(&self) -> Vec<Move>;
+
+    fn legal_targets(&self, from: Coordinate) -> Self::Cells;
+
+    fn make_move(&mut self, the_move: Move) -> Result<Self::Undo, CantMovePiece>;
+
+    fn unmake_move(&mut self, undo: Self::Undo);
+
+    fn outcome_events(&self) -> Vec<Event>;
+
+    /// Whether the game is drawn by insufficient material or the
+    /// seventy-five-move rule.
+    fn is_drawn(&self) -> bool;
+}
+
+/// Selects one of the built-in [Variant]s that are played on a [State].
+///
+/// Unlike the variants themselves, this can be copied and serialized, which
+/// allows [crate::Game] to carry it around.
+#[derive(Tsify, Default, Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum VariantKind {
+    #[default]
+    Standard,
+    Crazyhouse,
+    Atomic,
+    ThreeCheck,
+    KingOfTheHill,
+    Antichess,
+}
+
+impl VariantKind {
+    pub const ALL: [VariantKind; 6] = [
+        VariantKind::Standard,
+        VariantKind::Crazyhouse,
+        VariantKind::Atomic,
+        VariantKind::ThreeCheck,
+        VariantKind::KingOfTheHill,
+        VariantKind::Antichess,
+    ];
+
+    /// The variant called [name], ignoring case, see [Variant::name].
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|variant| variant.rules().name().eq_ignore_ascii_case(name))
+    }
+
+    /// The rules of the variant.
+    pub fn rules(self) -> &'static dyn Variant {
+        match self {
+            VariantKind::Standard => &Standard,
+            VariantKind::Crazyhouse => &Crazyhouse,
+            VariantKind::Atomic => &Atomic,
+            VariantKind::ThreeCheck => &ThreeCheck,
+            VariantKind::KingOfTheHill => &KingOfTheHill,
+            VariantKind::Antichess => &Antichess,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{bitboard::Bitboard, fen::parse_state, move_piece_by, Cell, Game, Piece};
+
+    use super::*;
+
+    /// Standard chess, except that kings may not castle.
+    struct NoCastling;
+
+    impl Variant for NoCastling {
+        fn name(&self) -> &'static str {
+            "No Castling"
+        }
+
+        fn legal_targets(&self, state: &State, from: Coordinate) -> Bitboard {
+            Standard
+                .legal_targets(state, from)
+                .into_iter()
+                .filter(|to| {
+                    state
+                        .castling_rights
+                        .castle_side(state.player, from, *to)
+                        .is_none()
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn variants_override_only_what_they_change() {
+        let state = parse_state("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        let castling = Move::simple(Cell::E1, Cell::G1);
+
+        assert!(move_piece_by(&Standard, &state, castling).is_ok());
+        assert!(matches!(
+            move_piece_by(&NoCastling, &state, castling),
+            Err(CantMovePiece::IllegalMove { .. })
+        ));
+
+        // Everything else still follows the standard rules
+        let (_, events) =
+            move_piece_by(&NoCastling, &state, Move::simple(Cell::A1, Cell::A8)).unwrap();
+        assert!(events.contains(&Event::Check {
+            by: vec![(Cell::A8, Piece::white(Figure::Rook))]
+        }));
+        assert_eq!(NoCastling.start(), Standard.start());
+    }
+
+    #[test]
+    fn variants_tell_cheaply_whether_the_game_ended() {
+        let assert_agrees = |rules: &dyn Variant, notation: &str| {
+            let state = parse_state(notation).unwrap();
+            let ended = rules.outcome_events(&state).iter().any(|event| {
+                matches!(
+                    event,
+                    Event::Mate | Event::Win { .. } | Event::Stalemate | Event::Draw { .. }
+                )
+            });
+
+            assert_eq!(
+                ended,
+                rules.has_ended(&state, &rules.legal_moves(&state)),
+                "{} in {notation}",
+                rules.name()
+            );
+        };
+
+        for notation in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            // Mate, stalemate and insufficient material
+            "k7/1Q6/1K6/8/8/8/8/8 b - - 0 1",
+            "k7/8/1Q6/8/8/8/8/7K b - - 0 1",
+            "k7/8/8/8/8/8/8/7K w - - 0 1",
+            "k7/8/8/8/8/8/8/6BK w - - 0 1",
+            "k7/8/8/8/8/8/8/R6K w - - 150 100",
+            // The king reached the hill, or the third check was given
+            "8/8/8/3K4/8/8/8/k7 b - - 0 1",
+            "k7/8/8/8/8/8/8/R6K b - - 0 1 +3+0",
+        ] {
+            for variant in VariantKind::ALL {
+                assert_agrees(variant.rules(), notation);
+            }
+        }
+
+        // The king exploded, or all pieces were lost
+        let kingless = "8/8/8/8/8/8/8/R6K b - - 0 1";
+        assert_agrees(&Atomic, kingless);
+        assert_agrees(&Antichess, kingless);
+    }
+
+    #[test]
+    fn games_are_played_by_the_standard_rules_by_default() {
+        let game = Game::new();
+
+        assert_eq!(VariantKind::Standard, game.variant());
+        assert_eq!("Standard", game.rules().name());
+        assert_eq!(Standard.start(), game.start());
+        assert_eq!(
+            Standard.legal_moves(&game.start()),
+            game.legal_moves(&game.start())
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{
+    bitboard::Bitboard,
+    check::{check_by_opponent_is_mate, checking_pieces_of_opponent, is_stalemate},
+    material::is_insufficient_material,
+    move_execution::{self, CantMovePiece, Undo},
+    moves_available, CastlingRights, Color, Coordinate, DrawReason, Event, Move, State,
+    INITIAL_BOARD,
+};
+
+use super::{Position, Variant};
+
+/// The rules of standard chess, as defined by FIDE.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Standard;
+
+impl Variant for Standard {
+    fn name(&self) -> &'static str {
+        "Standard"
+    }
+}
+
+impl Position for State {
+    type Cells = Bitboard;
+    type Undo = Undo;
+
+    fn start() -> Self {
+        State::new(
+            Color::White,
+            INITIAL_BOARD,
+            CastlingRights::all(),
+            None,
+            0,
+            1,
+        )
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        moves_available::legal_moves(self)
+    }
+
+    fn legal_targets(&self, from: Coordinate) -> Bitboard {
+        moves_available::legal_targets(self, from)
+    }
+
+    fn make_move(&mut self, the_move: Move) -> Result<Undo, CantMovePiece> {
+        move_execution::make_move(self, the_move)
+    }
+
+    fn unmake_move(&mut self, undo: Undo) {
+        move_execution::unmake_move(self, undo)
+    }
+
+    fn outcome_events(&self) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        let checking_pieces = checking_pieces_of_opponent(self);
+        if !checking_pieces.is_empty() {
+            events.push(Event::Check {
+                by: checking_pieces,
+            });
+
+            if check_by_opponent_is_mate(self) {
+                events.push(Event::Mate);
+                return events;
+            }
+        } else if is_stalemate(self) {
+            events.push(Event::Stalemate);
+            return events;
+        }
+
+        // A mate on the last allowed move still counts, so we only look for
+        // draws if the game did not already end otherwise.
+        if is_insufficient_material(&self.board) {
+            events.push(Event::Draw {
+                reason: DrawReason::InsufficientMaterial,
+            });
+        } else if self.is_seventy_five_move_draw() {
+            events.push(Event::Draw {
+                reason: DrawReason::SeventyFiveMoveRule,
+            });
+        } else if self.can_claim_fifty_move_draw() {
+            events.push(Event::DrawClaimable {
+                reason: DrawReason::FiftyMoveRule,
+            });
+        }
+
+        events
+    }
+
+    fn is_drawn(&self) -> bool {
+        is_insufficient_material(&self.board) || self.is_seventy_five_move_draw()
+    }
+}
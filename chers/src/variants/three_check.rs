@@ -0,0 +1,141 @@
+use crate::{
+    bitboard::attacks::is_attacked,
+    move_execution::{self, CantMovePiece, Undo},
+    DrawReason, Event, Move, Player, State, WinReason,
+};
+
+use super::{Standard, Variant};
+
+/// The number of checks that wins a game of Three-check.
+pub const CHECKS_TO_WIN: u8 = 3;
+
+/// Standard chess, except that giving the third check wins the game.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ThreeCheck;
+
+impl Variant for ThreeCheck {
+    fn name(&self) -> &'static str {
+        "Three-check"
+    }
+
+    fn make_move(&self, state: &mut State, the_move: Move) -> Result<Undo, CantMovePiece> {
+        let player = state.player;
+        let undo = move_execution::make_move(state, the_move)?;
+        if gives_check(state, player) {
+            state.count_check(player);
+        }
+
+        Ok(undo)
+    }
+
+    fn unmake_move(&self, state: &mut State, undo: Undo) {
+        // The move was counted as a check iff it left the opponent in check
+        let player = state.opponent();
+        if gives_check(state, player) {
+            state.uncount_check(player);
+        }
+
+        move_execution::unmake_move(state, undo);
+    }
+
+    fn outcome_events(&self, state: &State) -> Vec<Event> {
+        let mut events = Standard.outcome_events(state);
+
+        if state.checks.of(state.opponent()) >= CHECKS_TO_WIN {
+            // The third check wins, even if it also mates
+            events.retain(|event| matches!(event, Event::Check { .. }));
+            events.push(Event::Win {
+                winner: state.opponent(),
+                reason: WinReason::ThreeChecks,
+            });
+        } else if state.bitboards.occupied().len() > 2 {
+            // Any piece besides the kings can still give checks
+            events.retain(|event| {
+                *event
+                    != Event::Draw {
+                        reason: DrawReason::InsufficientMaterial,
+                    }
+            });
+        }
+
+        events
+    }
+
+    fn has_ended(&self, state: &State, legal_moves: &[Move]) -> bool {
+        // Only two lone kings can't give checks anymore
+        state.checks.of(state.opponent()) >= CHECKS_TO_WIN
+            || legal_moves.is_empty()
+            || state.bitboards.occupied().len() == 2
+            || state.is_seventy_five_move_draw()
+    }
+}
+
+/// Whether [player] checks the king of the player to move.
+fn gives_check(state: &State, player: Player) -> bool {
+    state
+        .bitboards
+        .king_of(state.player)
+        .is_some_and(|king| is_attacked(&state.bitboards, king, player))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fen::{parse_state, to_fen},
+        move_piece_by, Cell, Checks, Color,
+    };
+
+    use super::*;
+
+    #[test]
+    fn the_third_check_wins() {
+        let state = parse_state("4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +2+0").unwrap();
+        assert_eq!(2, state.checks.of(Color::White));
+
+        let (after, events) =
+            move_piece_by(&ThreeCheck, &state, Move::simple(Cell::A1, Cell::A8)).unwrap();
+        assert_eq!(3, after.checks.of(Color::White));
+        assert_eq!(
+            Some(&Event::Win {
+                winner: Color::White,
+                reason: WinReason::ThreeChecks
+            }),
+            events.last()
+        );
+        assert_eq!("R3k3/8/8/8/8/8/8/4K3 b - - 1 1 +3+0", to_fen(&after));
+
+        // Quiet moves don't count
+        let (after, events) =
+            move_piece_by(&ThreeCheck, &state, Move::simple(Cell::A1, Cell::A2)).unwrap();
+        assert_eq!(state.checks, after.checks);
+        assert!(events
+            .iter()
+            .all(|event| !matches!(event, Event::Win { .. })));
+    }
+
+    #[test]
+    fn checks_are_restored_when_unmaking_moves() {
+        let original = parse_state("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        let mut state = original.clone();
+
+        let undo = ThreeCheck
+            .make_move(&mut state, Move::simple(Cell::A1, Cell::A8))
+            .unwrap();
+        assert_eq!(Checks { white: 1, black: 0 }, state.checks);
+        assert_ne!(original.zobrist_key(), state.zobrist_key());
+
+        ThreeCheck.unmake_move(&mut state, undo);
+        assert_eq!(original, state);
+        assert_eq!(original.zobrist_key(), state.zobrist_key());
+    }
+
+    #[test]
+    fn a_lone_minor_piece_can_still_win() {
+        let state = parse_state("4k3/8/8/8/8/8/8/4KB2 b - - 0 1").unwrap();
+
+        assert!(Standard.outcome_events(&state).contains(&Event::Draw {
+            reason: DrawReason::InsufficientMaterial
+        }));
+        assert!(ThreeCheck.outcome_events(&state).is_empty());
+    }
+}
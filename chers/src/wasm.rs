@@ -20,6 +20,9 @@ pub struct MoveExecutionResult {
     pub events: Vec<Event>,
     pub check: bool,
     pub mate: bool,
+    pub win: bool,
+    pub stalemate: bool,
+    pub draw: bool,
 }
 
 #[wasm_bindgen]
@@ -39,6 +42,15 @@ pub fn available_moves(state: JsValue, from: JsValue) -> Result<JsValue, JsError
     bridge::to_value(&moves).map_err(|e| JsError::new(&format!("Serialization error: {}", e)))
 }
 
+#[wasm_bindgen]
+pub fn legal_moves(state: JsValue) -> Result<JsValue, JsError> {
+    let state: State = bridge::from_value(state)
+        .map_err(|e| JsError::new(&format!("Failed to deserialize state: {}", e)))?;
+
+    let moves = crate::legal_moves(&state);
+    bridge::to_value(&moves).map_err(|e| JsError::new(&format!("Serialization error: {}", e)))
+}
+
 #[wasm_bindgen]
 pub fn next_state(state: JsValue, the_move: JsValue) -> Result<JsValue, JsError> {
     let state: State = bridge::from_value(state)
@@ -52,6 +64,7 @@ pub fn next_state(state: JsValue, the_move: JsValue) -> Result<JsValue, JsError>
                 CantMovePiece::NoPieceToMove => "No piece to move",
                 CantMovePiece::ItBelongsToOtherPlayer => "Piece belongs to opponent",
                 CantMovePiece::RequiresPromotion => "Promotion required",
+                CantMovePiece::NoPieceToDrop => "No piece to drop",
                 CantMovePiece::IllegalMove { .. } => "Illegal move",
             };
             bridge::to_value(&MoveExecutionError {
@@ -62,6 +75,9 @@ pub fn next_state(state: JsValue, the_move: JsValue) -> Result<JsValue, JsError>
             next_state,
             check: is_check(&events),
             mate: is_mate(&events),
+            win: is_win(&events),
+            stalemate: is_stalemate(&events),
+            draw: is_draw(&events),
             events,
         }),
     };
@@ -81,9 +97,23 @@ fn is_check(events: &[Event]) -> bool {
             from: _,
             to: _,
         } => false,
+        Event::Drop { piece: _, at: _ } => false,
         Event::Promotion { to: _ } => false,
+        Event::Castle {
+            side: _,
+            rook_from: _,
+            rook_to: _,
+        } => false,
+        Event::Explosion { at: _, removed: _ } => false,
         Event::Check { by: _ } => true,
         Event::Mate => false,
+        Event::Win {
+            winner: _,
+            reason: _,
+        } => false,
+        Event::Stalemate => false,
+        Event::Draw { reason: _ } => false,
+        Event::DrawClaimable { reason: _ } => false,
     })
 }
 
@@ -99,8 +129,118 @@ fn is_mate(events: &[Event]) -> bool {
             from: _,
             to: _,
         } => false,
+        Event::Drop { piece: _, at: _ } => false,
         Event::Promotion { to: _ } => false,
+        Event::Castle {
+            side: _,
+            rook_from: _,
+            rook_to: _,
+        } => false,
+        Event::Explosion { at: _, removed: _ } => false,
         Event::Check { by: _ } => false,
         Event::Mate => true,
+        Event::Win {
+            winner: _,
+            reason: _,
+        } => false,
+        Event::Stalemate => false,
+        Event::Draw { reason: _ } => false,
+        Event::DrawClaimable { reason: _ } => false,
+    })
+}
+
+fn is_win(events: &[Event]) -> bool {
+    events.iter().any(|x| match x {
+        Event::Capture {
+            at: _,
+            captured: _,
+            by: _,
+        } => false,
+        Event::Move {
+            piece: _,
+            from: _,
+            to: _,
+        } => false,
+        Event::Drop { piece: _, at: _ } => false,
+        Event::Promotion { to: _ } => false,
+        Event::Castle {
+            side: _,
+            rook_from: _,
+            rook_to: _,
+        } => false,
+        Event::Explosion { at: _, removed: _ } => false,
+        Event::Check { by: _ } => false,
+        Event::Mate => false,
+        Event::Win {
+            winner: _,
+            reason: _,
+        } => true,
+        Event::Stalemate => false,
+        Event::Draw { reason: _ } => false,
+        Event::DrawClaimable { reason: _ } => false,
+    })
+}
+
+fn is_stalemate(events: &[Event]) -> bool {
+    events.iter().any(|x| match x {
+        Event::Capture {
+            at: _,
+            captured: _,
+            by: _,
+        } => false,
+        Event::Move {
+            piece: _,
+            from: _,
+            to: _,
+        } => false,
+        Event::Drop { piece: _, at: _ } => false,
+        Event::Promotion { to: _ } => false,
+        Event::Castle {
+            side: _,
+            rook_from: _,
+            rook_to: _,
+        } => false,
+        Event::Explosion { at: _, removed: _ } => false,
+        Event::Check { by: _ } => false,
+        Event::Mate => false,
+        Event::Win {
+            winner: _,
+            reason: _,
+        } => false,
+        Event::Stalemate => true,
+        Event::Draw { reason: _ } => false,
+        Event::DrawClaimable { reason: _ } => false,
+    })
+}
+
+fn is_draw(events: &[Event]) -> bool {
+    events.iter().any(|x| match x {
+        Event::Capture {
+            at: _,
+            captured: _,
+            by: _,
+        } => false,
+        Event::Move {
+            piece: _,
+            from: _,
+            to: _,
+        } => false,
+        Event::Drop { piece: _, at: _ } => false,
+        Event::Promotion { to: _ } => false,
+        Event::Castle {
+            side: _,
+            rook_from: _,
+            rook_to: _,
+        } => false,
+        Event::Explosion { at: _, removed: _ } => false,
+        Event::Check { by: _ } => false,
+        Event::Mate => false,
+        Event::Win {
+            winner: _,
+            reason: _,
+        } => false,
+        Event::Stalemate => false,
+        Event::Draw { reason: _ } => true,
+        Event::DrawClaimable { reason: _ } => false,
     })
 }
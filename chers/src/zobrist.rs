@@ -0,0 +1,303 @@
+use crate::{
+    bitboard::Bitboard, Board, CastleSide, CastlingRights, Checks, Color, Coordinate, Piece,
+    Player, Pockets, BOARD_SIZE,
+};
+
+const CELLS: usize = BOARD_SIZE * BOARD_SIZE;
+
+/// The number of distinct pieces, i.e. of colors times figures.
+const PIECES: usize = 12;
+
+/// Random numbers assigned to each feature of a position. The key of a
+/// position is the XOR of the numbers of all features present in it.
+struct Keys {
+    /// One number per piece (color and figure) and cell.
+    pieces: [[u64; CELLS]; PIECES],
+    black_to_move: u64,
+    /// One number per player and castling direction.
+    castling: [u64; 4],
+    /// One number per column an en passant target can reside in.
+    en_passant: [u64; BOARD_SIZE],
+    /// One number per piece (color and figure) that can be in a pocket.
+    pockets: [u64; PIECES],
+    /// One number per player that can give checks.
+    checks: [u64; 2],
+    /// One number per cell a piece promoted from a pawn can reside on.
+    promoted: [u64; CELLS],
+}
+
+/// The keys are generated at compile time from a fixed seed, so they are the
+/// same across program runs and can safely be persisted.
+const KEYS: Keys = generate_keys(0x4368_6572_735f_5a6f);
+
+/// Computes the next pseudo-random number using the splitmix64 algorithm.
+const fn next(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *seed;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+const fn generate_keys(mut seed: u64) -> Keys {
+    let mut keys = Keys {
+        pieces: [[0; CELLS]; PIECES],
+        black_to_move: 0,
+        castling: [0; 4],
+        en_passant: [0; BOARD_SIZE],
+        pockets: [0; PIECES],
+        checks: [0; 2],
+        promoted: [0; CELLS],
+    };
+
+    let mut piece = 0;
+    while piece < PIECES {
+        let mut cell = 0;
+        while cell < CELLS {
+            keys.pieces[piece][cell] = next(&mut seed);
+            cell += 1;
+        }
+        piece += 1;
+    }
+
+    keys.black_to_move = next(&mut seed);
+
+    let mut index = 0;
+    while index < 4 {
+        keys.castling[index] = next(&mut seed);
+        index += 1;
+    }
+
+    let mut column = 0;
+    while column < BOARD_SIZE {
+        keys.en_passant[column] = next(&mut seed);
+        column += 1;
+    }
+
+    let mut piece = 0;
+    while piece < PIECES {
+        keys.pockets[piece] = next(&mut seed);
+        piece += 1;
+    }
+
+    keys.checks[0] = next(&mut seed);
+    keys.checks[1] = next(&mut seed);
+
+    let mut cell = 0;
+    while cell < CELLS {
+        keys.promoted[cell] = next(&mut seed);
+        cell += 1;
+    }
+
+    keys
+}
+
+fn piece_index(piece: Piece) -> usize {
+    let color = match piece.color {
+        Color::White => 0,
+        Color::Black => 6,
+    };
+
+    color + piece.figure as usize
+}
+
+/// The key of [piece] residing on [at].
+pub fn piece_key(piece: Piece, at: Coordinate) -> u64 {
+    KEYS.pieces[piece_index(piece)][at.y * BOARD_SIZE + at.x]
+}
+
+/// The key of [player] being the one to move.
+pub fn player_key(player: Player) -> u64 {
+    match player {
+        Color::White => 0,
+        Color::Black => KEYS.black_to_move,
+    }
+}
+
+pub fn castling_key(rights: CastlingRights) -> u64 {
+    let mut key = 0;
+
+    for (index, (player, side)) in [
+        (Color::White, CastleSide::KingSide),
+        (Color::White, CastleSide::QueenSide),
+        (Color::Black, CastleSide::KingSide),
+        (Color::Black, CastleSide::QueenSide),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if rights.allows(player, side) {
+            key ^= KEYS.castling[index];
+        }
+    }
+
+    key
+}
+
+/// Only the column of the target is relevant, since the row is implied by
+/// the player to move.
+pub fn en_passant_key(target: Option<Coordinate>) -> u64 {
+    match target {
+        Some(coordinate) => KEYS.en_passant[coordinate.x],
+        None => 0,
+    }
+}
+
+/// The key of [count] pieces like [piece] being in the pocket of its owner.
+///
+/// Multiplying keeps the keys of different counts apart, while no pieces at
+/// all don't change the key of a position.
+pub fn pocket_key(piece: Piece, count: u8) -> u64 {
+    KEYS.pockets[piece_index(piece)].wrapping_mul(count as u64)
+}
+
+pub fn pockets_key(pockets: Pockets) -> u64 {
+    let mut key = 0;
+
+    for color in [Color::White, Color::Black] {
+        for figure in pockets.of(color).figures() {
+            let piece = Piece::new(color, figure);
+            key ^= pocket_key(piece, pockets.of(color).count(figure));
+        }
+    }
+
+    key
+}
+
+/// Like [pocket_key], the number of checks is multiplied in, so that no
+/// checks at all don't change the key of a position.
+pub fn checks_key(checks: Checks) -> u64 {
+    KEYS.checks[0].wrapping_mul(checks.white as u64)
+        ^ KEYS.checks[1].wrapping_mul(checks.black as u64)
+}
+
+/// The key of the pieces on [promoted] having been promoted from pawns, which
+/// only matters in Crazyhouse.
+pub fn promoted_key(promoted: Bitboard) -> u64 {
+    promoted
+        .into_iter()
+        .fold(0, |key, at| key ^ KEYS.promoted[Bitboard::index(at)])
+}
+
+pub fn board_key(board: &Board) -> u64 {
+    let mut key = 0;
+
+    for (y, row) in board.iter().enumerate() {
+        for (x, cell) in row.iter().enumerate() {
+            if let Some(piece) = cell {
+                key ^= piece_key(*piece, Coordinate::new(x, y));
+            }
+        }
+    }
+
+    key
+}
+
+/// Computes the key of a position from scratch.
+pub fn key_of(
+    board: &Board,
+    player: Player,
+    castling_rights: CastlingRights,
+    en_passant_target: Option<Coordinate>,
+) -> u64 {
+    board_key(board)
+        ^ player_key(player)
+        ^ castling_key(castling_rights)
+        ^ en_passant_key(en_passant_target)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fen::parse_state, make_move, move_piece, unmake_move, Cell, Game, Move, PromotedFigure,
+    };
+
+    use super::*;
+
+    #[test]
+    fn incremental_updates_match_keys_computed_from_scratch() {
+        let mut state =
+            parse_state("r3k2r/pppq1ppp/8/3pP3/8/8/PPPQ1PPP/R3K2R w KQkq d6 0 1").unwrap();
+
+        for the_move in [
+            Move::simple(Cell::E5, Cell::D6), // En passant
+            Move::simple(Cell::E8, Cell::C8), // Castling
+            Move::simple(Cell::D6, Cell::C7), // Capture
+            Move::simple(Cell::D7, Cell::D2), // Capture with check
+            Move::simple(Cell::E1, Cell::F1), // Loses castling rights
+            Move::simple(Cell::H7, Cell::H5), // Double step
+            Move::new(Cell::C7, Cell::D8, Some(PromotedFigure::Queen)),
+        ] {
+            state = move_piece(&state, the_move).unwrap().0;
+
+            let expected = key_of(
+                &state.board,
+                state.player,
+                state.castling_rights,
+                state.en_passant_target,
+            ) ^ promoted_key(state.promoted);
+            assert_eq!(expected, state.zobrist_key(), "after {the_move:?}");
+        }
+    }
+
+    #[test]
+    fn transpositions_lead_to_the_same_key() {
+        let game = Game::new();
+        let play = |moves: [(Coordinate, Coordinate); 4]| {
+            let mut state = game.start();
+            for (from, to) in moves {
+                state = game.move_piece(&state, Move::simple(from, to)).unwrap().0;
+            }
+            state.zobrist_key()
+        };
+
+        let knight_first = play([
+            (Cell::G1, Cell::F3),
+            (Cell::G8, Cell::F6),
+            (Cell::E2, Cell::E3),
+            (Cell::E7, Cell::E6),
+        ]);
+        let pawn_first = play([
+            (Cell::E2, Cell::E3),
+            (Cell::E7, Cell::E6),
+            (Cell::G1, Cell::F3),
+            (Cell::G8, Cell::F6),
+        ]);
+        let different = play([
+            (Cell::E2, Cell::E3),
+            (Cell::E7, Cell::E6),
+            (Cell::G1, Cell::F3),
+            (Cell::B8, Cell::C6),
+        ]);
+
+        assert_eq!(knight_first, pawn_first);
+        assert_ne!(knight_first, different);
+    }
+
+    #[test]
+    fn side_to_move_and_en_passant_are_part_of_the_key() {
+        let white = parse_state("4k3/8/8/3pP3/8/8/8/4K3 w - - 0 1").unwrap();
+        let black = parse_state("4k3/8/8/3pP3/8/8/8/4K3 b - - 0 1").unwrap();
+        let en_passant = parse_state("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+
+        assert_ne!(white.zobrist_key(), black.zobrist_key());
+        assert_ne!(white.zobrist_key(), en_passant.zobrist_key());
+        assert_eq!(white.zobrist_key(), black.reversed().zobrist_key());
+    }
+
+    #[test]
+    fn promoted_pieces_are_part_of_the_key() {
+        let promoted = parse_state("4k3/8/8/8/8/8/8/Q~3K3[] w - - 0 1").unwrap();
+        let original = parse_state("4k3/8/8/8/8/8/8/Q3K3[] w - - 0 1").unwrap();
+        assert_ne!(promoted.zobrist_key(), original.zobrist_key());
+
+        // The marker follows the piece as it moves
+        let mut moved = promoted.clone();
+        let undo = make_move(&mut moved, Move::simple(Cell::A1, Cell::A2)).unwrap();
+        let expected = parse_state("4k3/8/8/8/8/8/Q~7/4K3[] b - - 1 1").unwrap();
+        assert_eq!(expected.zobrist_key(), moved.zobrist_key());
+
+        unmake_move(&mut moved, undo);
+        assert_eq!(promoted.zobrist_key(), moved.zobrist_key());
+    }
+}
@@ -1,4 +1,4 @@
-use chers::{Coordinate, Game, Move, State};
+use chers::{Coordinate, Game, History, Move};
 
 use crate::{
     rendering::TerminalRenderer,
@@ -14,7 +14,7 @@ enum InputState {
 pub struct TerminalChersMatch {
     engine: Game,
     renderer: TerminalRenderer,
-    game_state: State,
+    history: History,
     input_state: InputState,
 }
 
@@ -25,7 +25,7 @@ impl TerminalChersMatch {
         Self {
             engine,
             renderer: TerminalRenderer {},
-            game_state: initial_state,
+            history: History::new(initial_state),
             input_state: InputState::PromptingFrom,
         }
     }
@@ -33,20 +33,20 @@ impl TerminalChersMatch {
     fn print_possible_moves(&self, from: Coordinate) {
         println!("Possible moves:");
 
-        for possible in self.engine.available_moves(&self.game_state, from) {
+        for possible in self.engine.available_moves(self.history.current(), from) {
             println!("- {}", possible)
         }
     }
 
     pub fn run(&mut self) {
-        self.renderer.render(&self.game_state.board);
+        self.renderer.render(self.history.current().board());
 
         'game: loop {
             let new_state = match self.input_state {
                 InputState::PromptingFrom => {
                     match prompt_for_coordinate_or_quit(&format!(
                         "{:?}'s turn, input from: ",
-                        self.game_state.player
+                        self.history.current().player()
                     )) {
                         CoordinatePromptResult::Coordinate(from, _) => {
                             InputState::PromptingTo(from)
@@ -59,36 +59,45 @@ impl TerminalChersMatch {
                     self.print_possible_moves(from);
                     match prompt_for_coordinate_or_quit(&format!(
                         "{:?}'s turn, input to: ",
-                        self.game_state.player
+                        self.history.current().player()
                     )) {
                         CoordinatePromptResult::Coordinate(to, input) => {
-                            InputState::Execute(Move {
-                                from,
-                                to,
-                                promotion: parse_promotion(input),
-                            })
+                            InputState::Execute(Move::new(from, to, parse_promotion(input)))
                         }
                         CoordinatePromptResult::Back => InputState::PromptingFrom,
                     }
                 }
 
                 InputState::Execute(r#move) => {
-                    match self.engine.move_piece(&self.game_state, r#move) {
+                    let current_player = self.history.current().player();
+                    match self.engine.play(&mut self.history, r#move) {
                         Err(error) => {
                             println!("{:#?}", error);
                             InputState::PromptingTo(r#move.from)
                         }
-                        Ok((new_state, events)) => {
-                            let current_player = self.game_state.player;
-                            self.game_state = new_state;
-
-                            self.renderer.render(&self.game_state.board);
+                        Ok(events) => {
+                            self.renderer.render(self.history.current().board());
 
                             for event in events {
                                 println!("{:?}", event);
-                                if let chers::Event::Mate = event {
-                                    println!("{:?} wins!", current_player);
-                                    break 'game;
+                                match event {
+                                    chers::Event::Mate => {
+                                        println!("{:?} wins!", current_player);
+                                        break 'game;
+                                    }
+                                    chers::Event::Win { winner, reason } => {
+                                        println!("{:?} wins by {:?}!", winner, reason);
+                                        break 'game;
+                                    }
+                                    chers::Event::Stalemate => {
+                                        println!("Stalemate, it's a draw!");
+                                        break 'game;
+                                    }
+                                    chers::Event::Draw { reason } => {
+                                        println!("{:?}, it's a draw!", reason);
+                                        break 'game;
+                                    }
+                                    _ => {}
                                 }
                             }
 
@@ -116,7 +116,7 @@ fn other_confirms_connection(stream: &mut TcpStream) -> bool {
         return false;
     }
 
-    return buffer.trim() == "accept";
+    buffer.trim() == "accept"
 }
 
 // =============================================================================
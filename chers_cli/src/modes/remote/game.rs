@@ -42,14 +42,14 @@ impl RemoteChersMatch {
     }
 
     pub fn run(&mut self) {
-        self.renderer.render(&self.game_state.board);
+        self.renderer.render(self.game_state.board());
 
         'game: loop {
             self.input_state = match self.input_state {
                 InputState::PromptingFrom => {
                     match prompt_for_coordinate_or_quit(&format!(
                         "{:?}'s turn, input from: ",
-                        self.game_state.player
+                        self.game_state.player()
                     )) {
                         CoordinatePromptResult::Coordinate(from, _) => {
                             InputState::PromptingTo(from)
@@ -62,14 +62,10 @@ impl RemoteChersMatch {
                     self.print_possible_moves(from);
                     match prompt_for_coordinate_or_quit(&format!(
                         "{:?}'s turn, input to: ",
-                        self.game_state.player
+                        self.game_state.player()
                     )) {
                         CoordinatePromptResult::Coordinate(to, input) => {
-                            InputState::Execute(Move {
-                                from,
-                                to,
-                                promotion: parse_promotion(input),
-                            })
+                            InputState::Execute(Move::new(from, to, parse_promotion(input)))
                         }
                         CoordinatePromptResult::Back => InputState::PromptingFrom,
                     }
@@ -82,16 +78,31 @@ impl RemoteChersMatch {
                             InputState::PromptingTo(the_move.from)
                         }
                         Ok((new_state, events)) => {
-                            let current_player = self.game_state.player;
+                            let current_player = self.game_state.player();
                             self.game_state = new_state;
 
-                            self.renderer.render(&self.game_state.board);
+                            self.renderer.render(self.game_state.board());
 
                             for event in events {
                                 println!("{:?}", event);
-                                if let chers::Event::Mate = event {
-                                    println!("{:?} wins!", current_player);
-                                    break 'game;
+                                match event {
+                                    chers::Event::Mate => {
+                                        println!("{:?} wins!", current_player);
+                                        break 'game;
+                                    }
+                                    chers::Event::Win { winner, reason } => {
+                                        println!("{:?} wins by {:?}!", winner, reason);
+                                        break 'game;
+                                    }
+                                    chers::Event::Stalemate => {
+                                        println!("Stalemate, it's a draw!");
+                                        break 'game;
+                                    }
+                                    chers::Event::Draw { reason } => {
+                                        println!("{:?}, it's a draw!", reason);
+                                        break 'game;
+                                    }
+                                    _ => {}
                                 }
                             }
 
@@ -18,7 +18,7 @@ fn clear_terminal() {
 // ============================================================================
 
 fn show_board(board: &Board) -> String {
-    return board
+    board
         .iter()
         .enumerate()
         .map(|(row_index, row)| {
@@ -38,7 +38,7 @@ fn show_board(board: &Board) -> String {
         })
         .collect::<Vec<String>>()
         .join("\n")
-        + "\na b c d e f g h\n";
+        + "\na b c d e f g h\n"
 }
 
 fn to_terminal_string(coordinate: Coordinate, piece: Option<Piece>) -> String {
@@ -80,6 +80,9 @@ fn piece_to_string(piece: &Piece) -> String {
             Figure::Bishop => "♗",
             Figure::Knight => "♞",
             Figure::Pawn => "♙",
+            Figure::Archbishop | Figure::Chancellor => {
+                unreachable!("Only played on a CapablancaState")
+            }
         },
         Color::Black => match piece.figure {
             Figure::King => "♚",
@@ -88,6 +91,9 @@ fn piece_to_string(piece: &Piece) -> String {
             Figure::Bishop => "♝",
             Figure::Knight => "♞",
             Figure::Pawn => "♙",
+            Figure::Archbishop | Figure::Chancellor => {
+                unreachable!("Only played on a CapablancaState")
+            }
         },
     }
     .to_string()
@@ -1,22 +1,56 @@
 use std::sync::Arc;
 
-use axum::extract::{Json, State};
-use tracing::{info, instrument};
+use axum::{
+    extract::{Json, Query, State},
+    http::StatusCode,
+};
+use chers::{chess960, Game};
+use rand::Rng;
+use tracing::{info, instrument, warn};
 
 use crate::AppState;
 
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct NewMatchOptions {
+    /// Start from a random Chess960 position instead of the standard one.
+    #[serde(default)]
+    chess960: bool,
+    /// Start from the Chess960 position with this index (0-959), which
+    /// implies `chess960`.
+    chess960_position: Option<u16>,
+}
+
 #[derive(serde::Serialize)]
 pub struct NewMatchResponse {
     id: String, // UUID as string
 }
 
 #[instrument(skip(state))]
-pub async fn create_new_match(State(state): State<Arc<AppState>>) -> Json<NewMatchResponse> {
-    let match_arc = state.matches.create();
+pub async fn create_new_match(
+    State(state): State<Arc<AppState>>,
+    Query(options): Query<NewMatchOptions>,
+) -> Result<Json<NewMatchResponse>, StatusCode> {
+    let game = match options.chess960_position {
+        Some(index) => Game::chess960(index).ok_or_else(|| {
+            warn!(index, "❌ Invalid Chess960 position requested");
+            StatusCode::BAD_REQUEST
+        })?,
+        None if options.chess960 => {
+            let index = rand::thread_rng().gen_range(0..chess960::POSITIONS);
+            Game::chess960(index).expect("index is within range")
+        }
+        None => Game::new(),
+    };
+
+    let match_arc = state.matches.create_with_game(game);
     let match_guard = match_arc.read().await;
     let match_id = match_guard.id.to_string();
 
-    info!(match_id = %match_id, "🎮 New match created");
+    info!(
+        match_id = %match_id,
+        chess960_position = ?game.chess960_position(),
+        "🎮 New match created"
+    );
 
-    Json(NewMatchResponse { id: match_id })
+    Ok(Json(NewMatchResponse { id: match_id }))
 }
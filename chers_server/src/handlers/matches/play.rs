@@ -17,7 +17,7 @@ use tracing::{debug, error, info, info_span, instrument, warn, Instrument};
 use crate::{
     matches::{
         parse_match_id,
-        state::{GameEndReason, GameResult, MatchState, MoveError, ReconnectError, StartError},
+        state::{ClaimError, GameEndReason, GameResult, MatchState, MoveError, ReconnectError},
         Match,
     },
     AppState,
@@ -459,42 +459,6 @@ async fn authenticate_player(
     Some((context, private_rx, public_rx))
 }
 
-async fn try_start_game(
-    match_arc: &Arc<tokio::sync::RwLock<Match>>,
-) -> Option<(crate::matches::state::ActiveGame, bool)> {
-    let mut match_guard = match_arc.write().await;
-    match match_guard.start_game() {
-        Ok((game, player1_is_white)) => Some((game, player1_is_white)),
-        Err(StartError::NotReady) => None,
-    }
-}
-
-async fn broadcast_game_started(
-    match_arc: &Arc<tokio::sync::RwLock<Match>>,
-    match_id: &str,
-    game: &crate::matches::state::ActiveGame,
-) {
-    info!(
-        "📢 Broadcasting GameStarted in match {} - White: {}, Black: {}",
-        match_id, game.white.name, game.black.name
-    );
-    
-    let event = PublicEvent::GameStarted {
-        game_state: game.state.clone(),
-        white_player: chers_server_api::server::PlayerInfo {
-            name: game.white.name.clone(),
-            connected: true,
-        },
-        black_player: chers_server_api::server::PlayerInfo {
-            name: game.black.name.clone(),
-            connected: true,
-        },
-    };
-
-    let match_guard = match_arc.read().await;
-    let _ = match_guard.channels.public_tx.send(event);
-}
-
 #[instrument(
     skip(socket, match_arc, private_rx, public_rx, context),
     fields(
@@ -532,7 +496,6 @@ async fn game_loop(
                                 tokio::time::sleep(Duration::from_millis(100)).await;
                                 break;
                             }
-                            MessageHandlingResult::Error => break,
                         }
                     }
                     Some(Ok(Message::Close(_))) | None => {
@@ -701,7 +664,6 @@ async fn handle_disconnection(
 enum MessageHandlingResult {
     Continue,
     GameOver,
-    Error,
 }
 
 #[instrument(
@@ -725,10 +687,14 @@ async fn handle_client_message(
         }) => {
             info!(
                 "♟️  Move attempt by {} ({:?}): {} -> {} (promotion: {:?})",
-                context.name, context.color, format_coords(from), format_coords(to), promotion
+                context.name, context.color, from, to, promotion
             );
             handle_make_move(socket, match_arc, context, from, to, promotion).await
         }
+        Ok(ClientMessage::ClaimDraw) => {
+            info!("🤝 Draw claim by {} ({:?})", context.name, context.color);
+            handle_claim_draw(socket, match_arc, context).await
+        }
         Ok(ClientMessage::Heartbeat) => {
             debug!("💓 Heartbeat from {}", context.name);
             // Send heartbeat acknowledgment
@@ -759,12 +725,6 @@ async fn handle_client_message(
     }
 }
 
-fn format_coords(coord: Coordinate) -> String {
-    let file = (b'a' + coord.x as u8) as char;
-    let rank = 8 - coord.y;
-    format!("{}{}", file, rank)
-}
-
 #[instrument(
     skip(socket, match_arc, context),
     fields(
@@ -799,12 +759,12 @@ async fn handle_make_move(
     match move_result {
         Ok(move_result) => {
             info!(
-                "✅ Move successful by {}: {} -> {} (check: {}, checkmate: {})",
+                "✅ Move successful by {}: {} (check: {}, checkmate: {}, position: {})",
                 context.name,
-                format_coords(from),
-                format_coords(to),
+                move_result.san,
                 move_result.is_check,
-                move_result.is_checkmate
+                move_result.is_checkmate,
+                chers::fen::to_fen(&move_result.new_state)
             );
             
             // Broadcast MoveMade
@@ -889,6 +849,69 @@ async fn handle_make_move(
     }
 }
 
+async fn handle_claim_draw(
+    socket: &mut WebSocket,
+    match_arc: &Arc<tokio::sync::RwLock<Match>>,
+    context: &PlayerContext,
+) -> MessageHandlingResult {
+    let claim_result = {
+        let mut match_guard = match_arc.write().await;
+        match_guard.claim_draw(&context.token)
+    };
+
+    let result = match claim_result {
+        Ok(result) => result,
+        Err(e) => {
+            let reason = match e {
+                ClaimError::NotClaimable => {
+                    warn!("⛔ Draw claim rejected for {}: Not claimable", context.name);
+                    chers_server_api::server::DrawClaimRejectionReason::NotClaimable
+                }
+                ClaimError::NotYourTurn => {
+                    warn!("⛔ Draw claim rejected for {}: Not your turn", context.name);
+                    chers_server_api::server::DrawClaimRejectionReason::NotYourTurn
+                }
+                ClaimError::GameNotInProgress | ClaimError::PlayerNotFound => {
+                    warn!("⛔ Draw claim rejected for {}: Game not in progress", context.name);
+                    chers_server_api::server::DrawClaimRejectionReason::GameOver
+                }
+            };
+
+            let rejection = PrivateEvent::DrawClaimRejected { reason };
+            let msg = ServerMessage::Private(rejection);
+
+            if let Ok(json) = serde_json::to_string(&msg) {
+                let _ = socket.send(Message::Text(json.into())).await;
+                debug!("📤 Sent DrawClaimRejected to {}", context.name);
+            }
+
+            return MessageHandlingResult::Continue;
+        }
+    };
+
+    let (api_result, api_reason) = convert_game_result(&result);
+    let game_over_event = PublicEvent::GameOver {
+        result: api_result,
+        reason: api_reason,
+    };
+
+    {
+        let match_guard = match_arc.read().await;
+        let _ = match_guard.channels.public_tx.send(game_over_event);
+    }
+
+    info!(
+        "🏁 Game over! Result: {:?}, Reason: {:?}",
+        api_result, api_reason
+    );
+
+    // Give more time for messages to propagate to both players
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let _ = socket.close().await;
+    MessageHandlingResult::GameOver
+}
+
 fn convert_game_result(
     result: &GameResult,
 ) -> (
@@ -899,6 +922,14 @@ fn convert_game_result(
         GameResult::WhiteWins(reason) => {
             let api_reason = match reason {
                 GameEndReason::Checkmate => chers_server_api::server::GameEndReason::Checkmate,
+                GameEndReason::ThreeChecks => chers_server_api::server::GameEndReason::ThreeChecks,
+                GameEndReason::KingOfTheHill => {
+                    chers_server_api::server::GameEndReason::KingOfTheHill
+                }
+                GameEndReason::AllPiecesLost => {
+                    chers_server_api::server::GameEndReason::AllPiecesLost
+                }
+                GameEndReason::Stalemate => chers_server_api::server::GameEndReason::Stalemate,
                 GameEndReason::Abandoned => chers_server_api::server::GameEndReason::Abandoned,
                 _ => chers_server_api::server::GameEndReason::Abandoned,
             };
@@ -907,6 +938,14 @@ fn convert_game_result(
         GameResult::BlackWins(reason) => {
             let api_reason = match reason {
                 GameEndReason::Checkmate => chers_server_api::server::GameEndReason::Checkmate,
+                GameEndReason::ThreeChecks => chers_server_api::server::GameEndReason::ThreeChecks,
+                GameEndReason::KingOfTheHill => {
+                    chers_server_api::server::GameEndReason::KingOfTheHill
+                }
+                GameEndReason::AllPiecesLost => {
+                    chers_server_api::server::GameEndReason::AllPiecesLost
+                }
+                GameEndReason::Stalemate => chers_server_api::server::GameEndReason::Stalemate,
                 GameEndReason::Abandoned => chers_server_api::server::GameEndReason::Abandoned,
                 _ => chers_server_api::server::GameEndReason::Abandoned,
             };
@@ -915,6 +954,16 @@ fn convert_game_result(
         GameResult::Draw(reason) => {
             let api_reason = match reason {
                 GameEndReason::Stalemate => chers_server_api::server::GameEndReason::Stalemate,
+                GameEndReason::FiftyMoveRule => chers_server_api::server::GameEndReason::FiftyMoveRule,
+                GameEndReason::SeventyFiveMoveRule => {
+                    chers_server_api::server::GameEndReason::SeventyFiveMoveRule
+                }
+                GameEndReason::ThreefoldRepetition => {
+                    chers_server_api::server::GameEndReason::ThreefoldRepetition
+                }
+                GameEndReason::InsufficientMaterial => {
+                    chers_server_api::server::GameEndReason::InsufficientMaterial
+                }
                 GameEndReason::Abandoned => chers_server_api::server::GameEndReason::Abandoned,
                 _ => chers_server_api::server::GameEndReason::Abandoned,
             };
@@ -1111,7 +1160,7 @@ async fn start_countdown(match_arc: &Arc<tokio::sync::RwLock<Match>>) {
                     // Broadcast GameStarted event
                     let match_guard = match_arc.read().await;
                     let event = PublicEvent::GameStarted {
-                        game_state: active_game.state.clone(),
+                        game_state: active_game.history.current().clone(),
                         white_player: chers_server_api::server::PlayerInfo {
                             name: active_game.white.name.clone(),
                             connected: active_game.white.connected,
@@ -3,8 +3,6 @@ pub mod matches;
 
 pub use matches::repository::MatchRepository;
 
-use std::sync::Arc;
-
 pub struct AppState {
     pub matches: MatchRepository,
 }
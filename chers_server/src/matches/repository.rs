@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use chers::Game;
 use tokio::sync::RwLock;
 
 use crate::matches::{state::Match, MatchId};
@@ -24,8 +25,13 @@ impl MatchRepository {
     /// Create a new match in Lobby state
     /// Uses scc's lock-free insert - no global lock on the map
     pub fn create(&self) -> Arc<RwLock<Match>> {
+        self.create_with_game(Game::new())
+    }
+
+    /// Create a new match in Lobby state, playing by the rules of [game]
+    pub fn create_with_game(&self, game: Game) -> Arc<RwLock<Match>> {
         let id = MatchId::new_v4();
-        let match_state = Match::new(id);
+        let match_state = Match::with_game(id, game);
         let arc = Arc::new(RwLock::new(match_state));
 
         // scc::HashMap::insert_sync is lock-free and thread-safe
@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use chers::{Color, Coordinate, Game, PromotedFigure, State};
+use chers::{Color, Coordinate, Game, History, PromotedFigure, State};
 use jiff::Timestamp;
 use tokio::sync::broadcast;
 use tokio::task::JoinHandle;
@@ -20,14 +20,23 @@ pub struct Match {
     pub channels: MatchChannels,
     pub move_count: u32,
     pub span: tracing::Span, // Root span for the match, parent of all player spans
+    /// The rules the game is played by, chosen when the match is created.
+    pub game: Game,
 }
 
 impl Match {
     pub fn new(id: MatchId) -> Self {
+        Self::with_game(id, Game::new())
+    }
+
+    /// Create a match whose game starts from the position of [game], e.g. a
+    /// Chess960 one.
+    pub fn with_game(id: MatchId, game: Game) -> Self {
         let span = info_span!(
             "match",
             match.id = %id,
             match.created_at = %Timestamp::now(),
+            match.chess960_position = ?game.chess960_position(),
         );
 
         Self {
@@ -37,6 +46,7 @@ impl Match {
             channels: MatchChannels::new(),
             move_count: 0,
             span,
+            game,
         }
     }
 
@@ -168,8 +178,8 @@ impl Match {
             started_at: Timestamp::now(),
             white,
             black,
-            state: Game::new().start(),
-            game: Game::new(),
+            history: History::new(self.game.start()),
+            game: self.game,
             move_history: Vec::new(),
             disconnection_timer: None,
             paused: false,
@@ -217,19 +227,19 @@ impl Match {
         };
 
         // Check turn
-        if active.state.player != player_color {
+        if active.history.current().player() != player_color {
             return Err(MoveError::NotYourTurn);
         }
 
         // Validate and apply move
-        let chess_move = chers::Move {
-            from,
-            to,
-            promotion,
-        };
+        let chess_move = chers::Move::new(from, to, promotion);
+        // Described before playing, since SAN depends on the position it is played in
+        let san = chers::moves::san::to_san(active.history.current(), &chess_move);
+
+        match active.game.play(&mut active.history, chess_move) {
+            Ok(events) => {
+                let new_state = active.history.current().clone();
 
-        match active.game.move_piece(&active.state, chess_move) {
-            Ok((new_state, events)) => {
                 // Record the move
                 let record = MoveRecord {
                     move_number: self.move_count + 1,
@@ -241,8 +251,6 @@ impl Match {
                 };
                 active.move_history.push(record);
 
-                // Update game state
-                active.state = new_state.clone();
                 self.move_count += 1;
 
                 // Check for game end using the check module
@@ -252,20 +260,35 @@ impl Match {
                         .iter()
                         .any(|e| matches!(e, chers::Event::Check { .. }));
 
-                // Check for stalemate - need to see if player has any legal moves
-                let is_stalemate = false; // TODO: Implement proper stalemate detection
+                let win = events.iter().find_map(|e| match e {
+                    chers::Event::Win { winner, reason } => Some((*winner, *reason)),
+                    _ => None,
+                });
+                let is_stalemate = events.iter().any(|e| matches!(e, chers::Event::Stalemate));
+                let draw = events.iter().find_map(|e| match e {
+                    chers::Event::Draw { reason } => Some(*reason),
+                    _ => None,
+                });
 
                 let game_over = if is_checkmate {
                     Some(match player_color {
                         Color::White => GameResult::WhiteWins(GameEndReason::Checkmate),
                         Color::Black => GameResult::BlackWins(GameEndReason::Checkmate),
                     })
+                } else if let Some((winner, reason)) = win {
+                    Some(match winner {
+                        Color::White => GameResult::WhiteWins(reason.into()),
+                        Color::Black => GameResult::BlackWins(reason.into()),
+                    })
+                } else if is_stalemate {
+                    Some(GameResult::Draw(GameEndReason::Stalemate))
                 } else {
-                    None
+                    draw.map(|reason| GameResult::Draw(reason.into()))
                 };
 
                 Ok(MoveResult {
                     new_state,
+                    san,
                     is_check,
                     is_checkmate,
                     is_stalemate,
@@ -276,6 +299,36 @@ impl Match {
         }
     }
 
+    /// Attempt to claim a draw.
+    /// Only the player to move may claim, and only if the position allows it.
+    pub fn claim_draw(&mut self, player_token: &str) -> Result<GameResult, ClaimError> {
+        let active = match &self.state {
+            MatchState::InProgress(active) => active,
+            _ => return Err(ClaimError::GameNotInProgress),
+        };
+
+        let player_color = if active.white.token == player_token {
+            Color::White
+        } else if active.black.token == player_token {
+            Color::Black
+        } else {
+            return Err(ClaimError::PlayerNotFound);
+        };
+
+        if active.history.current().player() != player_color {
+            return Err(ClaimError::NotYourTurn);
+        }
+
+        let Some(reason) = active.game.claimable_draw(&active.history) else {
+            return Err(ClaimError::NotClaimable);
+        };
+
+        let result = GameResult::Draw(reason.into());
+        self.end_game(result.clone());
+
+        Ok(result)
+    }
+
     /// Handle player disconnection
     /// Returns information needed to start grace period timer
     pub fn handle_disconnection(&mut self, token: &str) -> Option<DisconnectionResult> {
@@ -347,7 +400,7 @@ impl Match {
                 game_resumed: false,
                 state: self
                     .get_final_state()
-                    .unwrap_or_else(|| chers::Game::new().start()),
+                    .unwrap_or_else(|| self.game.start()),
                 move_history: Vec::new(),   // Could store this if needed
                 current_turn: player_color, // Doesn't matter for finished game
                 white_connected: true,      // Mark both as connected
@@ -405,9 +458,9 @@ impl Match {
         Ok(ReconnectionResult {
             player: player_color,
             game_resumed: both_connected,
-            state: active.state.clone(),
+            state: active.history.current().clone(),
             move_history: active.move_history.clone(),
-            current_turn: active.state.player,
+            current_turn: active.history.current().player(),
             white_connected: active.white.connected,
             black_connected: active.black.connected,
             game_result: None,
@@ -417,7 +470,7 @@ impl Match {
     }
 
     /// Get player color from a finished game
-    fn get_player_color_in_finished_game(&self, token: &str) -> Option<Color> {
+    fn get_player_color_in_finished_game(&self, _token: &str) -> Option<Color> {
         if let MatchState::Finished(_, _) = &self.state {
             // We need to access the player info from the last InProgress state
             // This requires storing player info in Finished state
@@ -636,12 +689,14 @@ impl Match {
     }
 }
 
+#[allow(clippy::large_enum_variant)] // Matches are few and long-lived
 pub enum MatchState {
     Lobby(LobbyState),
     InProgress(ActiveGame),
     Finished(GameResult, Timestamp),
 }
 
+#[derive(Default)]
 pub struct LobbyState {
     pub player1: Option<PlayerSlot>,
     pub player2: Option<PlayerSlot>,
@@ -650,18 +705,6 @@ pub struct LobbyState {
     pub countdown_task: Option<tokio::task::JoinHandle<()>>,
 }
 
-impl Default for LobbyState {
-    fn default() -> Self {
-        Self {
-            player1: None,
-            player2: None,
-            player1_ready: false,
-            player2_ready: false,
-            countdown_task: None,
-        }
-    }
-}
-
 impl LobbyState {
     /// True when both slots are filled and both players are ready
     pub fn ready_to_start(&self) -> bool {
@@ -744,7 +787,7 @@ pub struct ActiveGame {
     pub started_at: Timestamp,
     pub white: PlayerInfo,
     pub black: PlayerInfo,
-    pub state: State,
+    pub history: History,
     pub game: Game,
     pub move_history: Vec<MoveRecord>,
     pub disconnection_timer: Option<JoinHandle<()>>,
@@ -757,8 +800,8 @@ impl Clone for ActiveGame {
             started_at: self.started_at,
             white: self.white.clone(),
             black: self.black.clone(),
-            state: self.state.clone(),
-            game: self.game.clone(),
+            history: self.history.clone(),
+            game: self.game,
             move_history: self.move_history.clone(),
             disconnection_timer: None, // Can't clone JoinHandle
             paused: self.paused,
@@ -779,6 +822,8 @@ pub struct MoveRecord {
 #[derive(Clone, Debug)]
 pub struct MoveResult {
     pub new_state: State,
+    /// The move in Standard Algebraic Notation, e.g. `Nbd7` or `e8=Q+`.
+    pub san: String,
     pub is_check: bool,
     pub is_checkmate: bool,
     pub is_stalemate: bool,
@@ -793,6 +838,13 @@ pub enum MoveError {
     GamePaused,
 }
 
+pub enum ClaimError {
+    GameNotInProgress,
+    NotYourTurn,
+    NotClaimable,
+    PlayerNotFound,
+}
+
 pub struct DisconnectionResult {
     pub player: Color,
     pub both_disconnected: bool,
@@ -841,6 +893,10 @@ impl GameResult {
             GameResult::WhiteWins(reason) => {
                 let api_reason = match reason {
                     GameEndReason::Checkmate => ApiReason::Checkmate,
+                    GameEndReason::ThreeChecks => ApiReason::ThreeChecks,
+                    GameEndReason::KingOfTheHill => ApiReason::KingOfTheHill,
+                    GameEndReason::AllPiecesLost => ApiReason::AllPiecesLost,
+                    GameEndReason::Stalemate => ApiReason::Stalemate,
                     GameEndReason::Abandoned => ApiReason::Abandoned,
                     _ => ApiReason::Abandoned,
                 };
@@ -849,6 +905,10 @@ impl GameResult {
             GameResult::BlackWins(reason) => {
                 let api_reason = match reason {
                     GameEndReason::Checkmate => ApiReason::Checkmate,
+                    GameEndReason::ThreeChecks => ApiReason::ThreeChecks,
+                    GameEndReason::KingOfTheHill => ApiReason::KingOfTheHill,
+                    GameEndReason::AllPiecesLost => ApiReason::AllPiecesLost,
+                    GameEndReason::Stalemate => ApiReason::Stalemate,
                     GameEndReason::Abandoned => ApiReason::Abandoned,
                     _ => ApiReason::Abandoned,
                 };
@@ -857,6 +917,10 @@ impl GameResult {
             GameResult::Draw(reason) => {
                 let api_reason = match reason {
                     GameEndReason::Stalemate => ApiReason::Stalemate,
+                    GameEndReason::FiftyMoveRule => ApiReason::FiftyMoveRule,
+                    GameEndReason::SeventyFiveMoveRule => ApiReason::SeventyFiveMoveRule,
+                    GameEndReason::ThreefoldRepetition => ApiReason::ThreefoldRepetition,
+                    GameEndReason::InsufficientMaterial => ApiReason::InsufficientMaterial,
                     GameEndReason::Abandoned => ApiReason::Abandoned,
                     _ => ApiReason::Abandoned,
                 };
@@ -874,6 +938,37 @@ pub enum GameEndReason {
     DrawAgreement,
     Timeout,
     Abandoned,
+    FiftyMoveRule,
+    SeventyFiveMoveRule,
+    ThreefoldRepetition,
+    InsufficientMaterial,
+    ThreeChecks,
+    KingOfTheHill,
+    AllPiecesLost,
+}
+
+impl From<chers::WinReason> for GameEndReason {
+    fn from(reason: chers::WinReason) -> Self {
+        match reason {
+            chers::WinReason::ThreeChecks => GameEndReason::ThreeChecks,
+            chers::WinReason::KingOfTheHill => GameEndReason::KingOfTheHill,
+            chers::WinReason::NoPiecesLeft => GameEndReason::AllPiecesLost,
+            chers::WinReason::Stalemated => GameEndReason::Stalemate,
+        }
+    }
+}
+
+impl From<chers::DrawReason> for GameEndReason {
+    fn from(reason: chers::DrawReason) -> Self {
+        match reason {
+            chers::DrawReason::FiftyMoveRule => GameEndReason::FiftyMoveRule,
+            chers::DrawReason::SeventyFiveMoveRule => GameEndReason::SeventyFiveMoveRule,
+            chers::DrawReason::ThreefoldRepetition | chers::DrawReason::FivefoldRepetition => {
+                GameEndReason::ThreefoldRepetition
+            }
+            chers::DrawReason::InsufficientMaterial => GameEndReason::InsufficientMaterial,
+        }
+    }
 }
 
 pub enum JoinError {
@@ -883,13 +978,6 @@ pub enum JoinError {
     DuplicateToken,
 }
 
-pub enum AuthError {
-    InvalidToken,
-    MatchNotFound,
-    MatchAlreadyStarted,
-    AlreadyConnected,
-}
-
 #[derive(Debug)]
 pub enum StartError {
     NotReady,
@@ -108,7 +108,6 @@ pub struct TestClient {
     ws: tokio_tungstenite::WebSocketStream<
         tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
     >,
-    match_id: String,
 }
 
 impl TestClient {
@@ -119,10 +118,7 @@ impl TestClient {
             .await
             .expect("Failed to connect WebSocket");
 
-        Self {
-            ws,
-            match_id: match_id.to_string(),
-        }
+        Self { ws }
     }
 
     pub async fn authenticate(&mut self, token: &str, name: &str) {
@@ -172,6 +168,16 @@ impl TestClient {
             .expect("Failed to send move");
     }
 
+    pub async fn claim_draw(&mut self) {
+        use chers_server_api::ClientMessage;
+
+        let json = serde_json::to_string(&ClientMessage::ClaimDraw).unwrap();
+        self.ws
+            .send(tokio_tungstenite::tungstenite::Message::Text(json))
+            .await
+            .expect("Failed to send draw claim");
+    }
+
     pub async fn expect_message(
         &mut self,
         timeout_secs: u64,
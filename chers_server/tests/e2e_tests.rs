@@ -53,6 +53,35 @@ async fn test_create_match() {
     server.stop();
 }
 
+#[tokio::test]
+async fn test_create_chess960_match() {
+    let server = TestServer::start().await;
+    let client = reqwest::Client::new();
+
+    for (query, expected_success) in [
+        ("chess960=true", true),
+        ("chess960_position=0", true),
+        ("chess960_position=959", true),
+        ("chess960_position=960", false),
+    ] {
+        let resp = client
+            .post(format!("{}/matches/new?{}", server.base_url(), query))
+            .send()
+            .await
+            .expect("Failed to create match");
+
+        assert_eq!(
+            resp.status().is_success(),
+            expected_success,
+            "Unexpected status for {}: {}",
+            query,
+            resp.status()
+        );
+    }
+
+    server.stop();
+}
+
 #[tokio::test]
 async fn test_two_players_connect_and_start_game() {
     let server = TestServer::start().await;
@@ -194,14 +223,7 @@ async fn test_turn_enforcement() {
 
     // Extract which player is playing which color from GameStarted
     let (player1_color, player2_color) = match (game_started_1, game_started_2) {
-        (
-            PublicEvent::GameStarted {
-                white_player,
-                black_player,
-                ..
-            },
-            _,
-        ) => {
+        (PublicEvent::GameStarted { white_player, .. }, _) => {
             // Determine which client is which color based on the player info
             let p1_is_white = white_player.name == "Player1";
             if p1_is_white {
@@ -297,6 +319,88 @@ async fn test_turn_enforcement() {
     server.stop();
 }
 
+#[tokio::test]
+async fn test_rejected_draw_claim() {
+    let server = TestServer::start().await;
+
+    let match_id = server.create_match().await;
+
+    let mut player1 = TestClient::connect(&server, &match_id).await;
+    let mut player2 = TestClient::connect(&server, &match_id).await;
+
+    player1.authenticate("player1", "Player1").await;
+    player2.authenticate("player2", "Player2").await;
+
+    let _ = player1.expect_message(5).await; // LobbyJoined
+    let _ = player2.expect_message(5).await; // LobbyJoined
+
+    player1.ready(true).await;
+    player2.ready(true).await;
+
+    use chers_server_api::{
+        server::DrawClaimRejectionReason, PrivateEvent, PublicEvent, ServerMessage,
+    };
+
+    let game_started = wait_for_game_started(&mut player1).await;
+    let _ = wait_for_game_started(&mut player2).await;
+
+    let player1_is_white = match game_started {
+        PublicEvent::GameStarted { white_player, .. } => white_player.name == "Player1",
+        _ => panic!("Failed to get GameStarted message"),
+    };
+    let (mut white, mut black) = if player1_is_white {
+        (player1, player2)
+    } else {
+        (player2, player1)
+    };
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    // Nothing can be claimed in the starting position
+    white.claim_draw().await;
+    let rejection = white.expect_message(2).await;
+    assert!(
+        matches!(
+            rejection,
+            Some(ServerMessage::Private(PrivateEvent::DrawClaimRejected {
+                reason: DrawClaimRejectionReason::NotClaimable
+            }))
+        ),
+        "White should receive DrawClaimRejected, got: {:?}",
+        rejection
+    );
+
+    // Only the player to move may claim
+    black.claim_draw().await;
+    let rejection = black.expect_message(2).await;
+    assert!(
+        matches!(
+            rejection,
+            Some(ServerMessage::Private(PrivateEvent::DrawClaimRejected {
+                reason: DrawClaimRejectionReason::NotYourTurn
+            }))
+        ),
+        "Black should receive DrawClaimRejected, got: {:?}",
+        rejection
+    );
+
+    // The game goes on
+    white.make_move("e2", "e4").await;
+    let move_msg = white.expect_message(5).await;
+    assert!(
+        matches!(
+            move_msg,
+            Some(ServerMessage::Public(PublicEvent::MoveMade { .. }))
+        ),
+        "White should still be able to move, got: {:?}",
+        move_msg
+    );
+
+    white.close().await;
+    black.close().await;
+    server.stop();
+}
+
 #[tokio::test]
 async fn test_disconnection_and_reconnection() {
     let server = TestServer::start().await;
@@ -423,8 +527,8 @@ async fn wait_for_game_started(
         }
         
         // Once we have both, we can return
-        if found_game_started.is_some() && found_colors_assigned {
-            return found_game_started.unwrap();
+        if let (Some(event), true) = (&found_game_started, found_colors_assigned) {
+            return event.clone();
         }
     }
     
@@ -161,6 +161,13 @@ pub mod client {
         /// The opponent is notified via `PrivateEvent::DrawDeclined`.
         DeclineDraw,
 
+        /// Claim a draw, e.g. because of the fifty-move rule or threefold repetition.
+        ///
+        /// Only the player to move may claim a draw. If the claim is valid, the game
+        /// ends and `PublicEvent::GameOver` is broadcast to all. Otherwise the
+        /// player receives `PrivateEvent::DrawClaimRejected` and the game goes on.
+        ClaimDraw,
+
         /// Resign from the current game.
         ///
         /// Ends the game with the opponent as the winner.
@@ -324,6 +331,7 @@ pub mod server {
     #[derive(Debug, Clone, Serialize, Deserialize, TS)]
     #[serde(tag = "kind")]
     #[ts(export)]
+    #[allow(clippy::large_enum_variant)] // Events are sent right after creation
     pub enum PrivateEvent {
         /// Player has joined the lobby and is waiting for opponent.
         ///
@@ -419,6 +427,15 @@ pub mod server {
         ///
         /// Sent when the opponent sends `ClientMessage::DeclineDraw`.
         DrawDeclined,
+
+        /// A draw claim was rejected.
+        ///
+        /// Sent in response to `ClientMessage::ClaimDraw` when no draw can be
+        /// claimed. The game goes on.
+        DrawClaimRejected {
+            /// The reason the claim was rejected.
+            reason: DrawClaimRejectionReason,
+        },
     }
 
     /// A server message containing either a public or private event.
@@ -483,6 +500,10 @@ pub mod server {
         /// 50-move rule - 50 moves without pawn move or capture.
         #[serde(rename = "fifty_move_rule")]
         FiftyMoveRule,
+        /// 75-move rule - 75 moves without pawn move or capture, which ends the
+        /// game automatically rather than by a claim.
+        #[serde(rename = "seventy_five_move_rule")]
+        SeventyFiveMoveRule,
         /// Insufficient material to checkmate (e.g., king vs king).
         #[serde(rename = "insufficient_material")]
         InsufficientMaterial,
@@ -492,6 +513,15 @@ pub mod server {
         /// A player disconnected and didn't reconnect in time.
         #[serde(rename = "abandoned")]
         Abandoned,
+        /// Three-check - one player gave the third check.
+        #[serde(rename = "three_checks")]
+        ThreeChecks,
+        /// King of the Hill - one player's king reached the center.
+        #[serde(rename = "king_of_the_hill")]
+        KingOfTheHill,
+        /// Antichess - one player lost all of their pieces, which wins.
+        #[serde(rename = "all_pieces_lost")]
+        AllPiecesLost,
     }
 
     /// A player's connection status.
@@ -531,6 +561,18 @@ pub mod server {
         /// The game is already over.
         GameOver,
     }
+
+    /// Reasons why a draw claim might be rejected.
+    #[derive(Debug, Clone, Serialize, Deserialize, TS)]
+    #[ts(export)]
+    pub enum DrawClaimRejectionReason {
+        /// Neither the fifty-move rule nor threefold repetition applies.
+        NotClaimable,
+        /// Only the player to move may claim a draw.
+        NotYourTurn,
+        /// The game is already over.
+        GameOver,
+    }
 }
 
 // Re-export the main types for convenience